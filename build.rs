@@ -0,0 +1,13 @@
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    #[cfg(feature = "grpc")]
+    {
+        println!("cargo:rerun-if-changed=proto/weather_forecaster.proto");
+        let protoc = protoc_bin_vendored::protoc_bin_path().expect("protoc is vendored");
+        // SAFETY: the build script does not spawn any threads
+        unsafe { std::env::set_var("PROTOC", protoc) };
+        tonic_prost_build::compile_protos("proto/weather_forecaster.proto")
+            .expect("proto/weather_forecaster.proto compiles");
+    }
+}
@@ -0,0 +1,215 @@
+use std::{collections::HashMap, ops::Range};
+
+use strum::IntoEnumIterator;
+
+use crate::{
+    error::ForecastError,
+    forecaster::{Sessions, WeatherForecast},
+    sessions::{SessionDefinition, key_prefix},
+};
+
+/// Writes the forecast into the content of a game settings file (e.g. LMU/rFactor2 `player.JSON`).
+///
+/// Only the values of the `*WeatherSlots` and `*WeatherSlotN` keys are replaced, everything else
+/// including the formatting of the file is kept as is.
+pub fn apply_forecast(settings: &str, forecast: &WeatherForecast) -> Result<String, ForecastError> {
+    // make sure we are not about to edit something that is not even valid JSON
+    serde_json::from_str::<serde_json::Value>(settings)?;
+
+    let mut settings = settings.to_string();
+    for session in forecast.sessions() {
+        let key = forecast.key_prefix(session);
+        let slots = forecast.forecast[&session].len();
+        replace_value(
+            &mut settings,
+            &format!("{key}WeatherSlots"),
+            &slots.to_string(),
+        )?;
+        for index in 0..slots {
+            replace_value(
+                &mut settings,
+                &format!("{key}WeatherSlot{}", index + 1),
+                &format!(r#""{}""#, forecast.game_string(session, index)),
+            )?;
+        }
+    }
+    Ok(settings)
+}
+
+/// Reads the game strings of the weather slots back from a settings file or the printed forecast.
+/// The keys of the declared sessions use their key prefixes.
+///
+/// Sessions without a `*WeatherSlots` key are skipped, but at least one session has to be present.
+pub fn read_forecast(
+    settings: &str,
+    definitions: &[SessionDefinition],
+) -> Result<HashMap<Sessions, Vec<String>>, ForecastError> {
+    let mut game_strings = HashMap::new();
+    for session in Sessions::iter() {
+        let prefix = key_prefix(definitions, session);
+        let key = format!("{prefix}WeatherSlots");
+        let Some(slots) = read_value(settings, &key) else {
+            continue;
+        };
+        let slots: usize = slots
+            .parse()
+            .map_err(|_| ForecastError::InvalidSettingsValue {
+                key: key.clone(),
+                value: slots.to_string(),
+            })?;
+
+        let slots = (1..=slots)
+            .map(|index| {
+                let key = format!("{prefix}WeatherSlot{index}");
+                let value = read_value(settings, &key)
+                    .ok_or_else(|| ForecastError::MissingSettingsKey(key.clone()))?;
+                serde_json::from_str(value).map_err(|_| ForecastError::InvalidSettingsValue {
+                    key,
+                    value: value.to_string(),
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        game_strings.insert(session, slots);
+    }
+
+    if game_strings.is_empty() {
+        return Err(ForecastError::MissingSettingsKey(format!(
+            "{}WeatherSlots",
+            key_prefix(definitions, Sessions::Race)
+        )));
+    }
+    Ok(game_strings)
+}
+
+fn read_value<'a>(settings: &'a str, key: &str) -> Option<&'a str> {
+    value_ranges(settings, key)
+        .first()
+        .map(|range| &settings[range.clone()])
+}
+
+fn replace_value(settings: &mut String, key: &str, value: &str) -> Result<(), ForecastError> {
+    let ranges = value_ranges(settings, key);
+    if ranges.is_empty() {
+        return Err(ForecastError::MissingSettingsKey(key.to_string()));
+    }
+
+    // back to front so the earlier ranges stay valid
+    for range in ranges.into_iter().rev() {
+        settings.replace_range(range, value);
+    }
+    Ok(())
+}
+
+/// Positions of all values of `key` in `settings`.
+fn value_ranges(settings: &str, key: &str) -> Vec<Range<usize>> {
+    let needle = format!(r#""{key}""#);
+    let mut ranges = Vec::new();
+    let mut search_start = 0;
+
+    while let Some(position) = settings[search_start..].find(&needle) {
+        let key_end = search_start + position + needle.len();
+        search_start = key_end;
+
+        let Some(value) = settings[key_end..].trim_start().strip_prefix(':') else {
+            // the key name appears as a value, not as a key
+            continue;
+        };
+        let value_start = settings.len() - value.trim_start().len();
+        let value_end = value_start + value_length(&settings[value_start..]);
+
+        ranges.push(value_start..value_end);
+        search_start = value_end;
+    }
+    ranges
+}
+
+fn value_length(value: &str) -> usize {
+    if let Some(string) = value.strip_prefix('"') {
+        let mut escaped = false;
+        for (index, char) in string.char_indices() {
+            match char {
+                '\\' if !escaped => escaped = true,
+                '"' if !escaped => return index + 2,
+                _ => escaped = false,
+            }
+        }
+        value.len()
+    } else {
+        value
+            .find(|char: char| char == ',' || char == '}' || char == ']' || char.is_whitespace())
+            .unwrap_or(value.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::forecaster::WeatherOptions;
+
+    use super::*;
+
+    #[test]
+    fn only_weather_keys_are_replaced() {
+        let settings = r#"{
+  "Race Conditions":{
+    "RaceWeatherSlots":4,
+    "RaceWeatherSlot1":"Clear",
+    "RaceWeatherSlot2" : "Clear",
+    "RaceWeatherSlot3":"Clear",
+    "RaceWeatherSlot4":"Clear",
+    "RaceWeatherSlots#":"number of weather slots",
+    "Other":"RaceWeatherSlot1"
+  }
+}"#;
+        let mut forecast = WeatherForecast::default();
+        forecast.forecast.insert(
+            Sessions::Race,
+            vec![WeatherOptions::Rain, WeatherOptions::Overcast],
+        );
+
+        let applied = apply_forecast(settings, &forecast).unwrap();
+        assert_eq!(
+            applied,
+            r#"{
+  "Race Conditions":{
+    "RaceWeatherSlots":2,
+    "RaceWeatherSlot1":"Rain",
+    "RaceWeatherSlot2" : "Overcast",
+    "RaceWeatherSlot3":"Clear",
+    "RaceWeatherSlot4":"Clear",
+    "RaceWeatherSlots#":"number of weather slots",
+    "Other":"RaceWeatherSlot1"
+  }
+}"#
+        );
+    }
+
+    #[test]
+    fn missing_keys_are_reported() {
+        let mut forecast = WeatherForecast::default();
+        forecast
+            .forecast
+            .insert(Sessions::Practice, vec![WeatherOptions::Clear]);
+
+        assert!(matches!(
+            apply_forecast("{}", &forecast),
+            Err(ForecastError::MissingSettingsKey(_))
+        ));
+    }
+
+    #[test]
+    fn printed_forecast_can_be_read_back() {
+        let mut forecast = WeatherForecast::default();
+        forecast.forecast.insert(
+            Sessions::Race,
+            vec![WeatherOptions::Rain, WeatherOptions::Overcast],
+        );
+        forecast
+            .forecast
+            .insert(Sessions::Qualifying, vec![WeatherOptions::Clear]);
+
+        let game_strings = read_forecast(&forecast.to_string(), &[]).unwrap();
+        assert_eq!(game_strings.len(), 2);
+        assert_eq!(game_strings[&Sessions::Race], ["Rain", "Overcast"]);
+        assert_eq!(game_strings[&Sessions::Qualifying], ["Clear"]);
+    }
+}
@@ -0,0 +1,74 @@
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+use crate::{
+    config::Config,
+    error::ForecastError,
+    forecaster::{Sessions, WeatherForecast, WeatherForecaster},
+};
+
+/// Forecasts generated for a batch before one like a previous forecast gets accepted.
+const MAX_ATTEMPTS: usize = 100;
+
+/// Forecast of a batch with the seed to generate it again.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchForecast {
+    pub seed: u64,
+    pub forecast: WeatherForecast,
+}
+
+/// Generates `count` different forecasts at once, e.g. for several lobbies. The seeds of the
+/// forecasts are drawn from `seed`, so each of them can be generated again on its own.
+pub fn generate(
+    config: &Config,
+    sessions: &[Sessions],
+    count: usize,
+    seed: u64,
+) -> Result<Vec<BatchForecast>, ForecastError> {
+    let mut seeds = StdRng::seed_from_u64(seed);
+    let mut batch: Vec<BatchForecast> = Vec::with_capacity(count);
+    while batch.len() < count {
+        // a config with few options may not allow enough different forecasts
+        for attempt in 1..=MAX_ATTEMPTS {
+            let seed = seeds.random();
+            let forecast = WeatherForecaster::new(config.clone())?
+                .with_seed(seed)
+                .generate_constrained_forecast(sessions)?;
+            let repeated = batch
+                .iter()
+                .any(|other| other.forecast.forecast == forecast.forecast);
+            if !repeated || attempt == MAX_ATTEMPTS {
+                batch.push(BatchForecast { seed, forecast });
+                break;
+            }
+        }
+    }
+    Ok(batch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forecasts_differ_and_can_be_generated_again() {
+        let config = Config::default();
+        let sessions = [Sessions::Qualifying, Sessions::Race];
+        let batch = generate(&config, &sessions, 5, 3).unwrap();
+        assert_eq!(batch.len(), 5);
+        for (index, entry) in batch.iter().enumerate() {
+            assert!(
+                batch[..index]
+                    .iter()
+                    .all(|other| other.forecast.forecast != entry.forecast.forecast)
+            );
+        }
+
+        let again = WeatherForecaster::new(config.clone())
+            .unwrap()
+            .with_seed(batch[2].seed)
+            .generate_constrained_forecast(&sessions)
+            .unwrap();
+        assert_eq!(again, batch[2].forecast);
+        assert_eq!(generate(&config, &sessions, 5, 3).unwrap(), batch);
+    }
+}
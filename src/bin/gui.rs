@@ -0,0 +1,22 @@
+use std::path::PathBuf;
+
+use weather_forecaster::{Config, config::ConfigFormat, gui};
+
+/// Opens the GUI with the config file given as the only argument, `./config.yml` by default.
+fn main() -> eframe::Result {
+    let path = std::env::args_os()
+        .nth(1)
+        .map_or_else(|| PathBuf::from("./config.yml"), PathBuf::from);
+    let config = if path.exists() {
+        match Config::load(&path, ConfigFormat::from_path(&path), false) {
+            Ok(config) => config,
+            Err(error) => {
+                eprintln!("{error}");
+                std::process::exit(1);
+            }
+        }
+    } else {
+        Config::default()
+    };
+    gui::run(config)
+}
@@ -0,0 +1,201 @@
+use crate::{
+    config::Config,
+    constraints::Constraints,
+    coupling::CouplingConfig,
+    drift::DriftConfig,
+    error::ForecastError,
+    forecaster::{Sessions, WeatherForecaster, WeatherOptions},
+    geography::Geography,
+    presets::TrackPreset,
+    realism::Realism,
+    sessions::SessionDefinition,
+    temperature::TemperatureConfig,
+    time_of_day::TimeOfDay,
+    transitions::TransitionConfig,
+    variety::ReintroductionConfig,
+    weekend::WeekendConfig,
+};
+
+/// Creates a [`WeatherForecaster`] step by step instead of from a whole [`Config`]. Everything
+/// that is not set keeps the default of the config.
+#[derive(Debug, Clone, Default)]
+pub struct WeatherForecasterBuilder {
+    config: Config,
+    seed: Option<u64>,
+}
+
+impl WeatherForecaster {
+    pub fn builder() -> WeatherForecasterBuilder {
+        WeatherForecasterBuilder::default()
+    }
+}
+
+impl WeatherForecasterBuilder {
+    /// Continues from an existing config, e.g. one loaded from a file.
+    pub fn from_config(config: Config) -> Self {
+        Self { config, seed: None }
+    }
+
+    /// Replaces all probabilities, options without one share what is left to 100%.
+    #[must_use]
+    pub fn probabilities(
+        mut self,
+        probabilities: impl IntoIterator<Item = (WeatherOptions, f64)>,
+    ) -> Self {
+        self.config.probabilities = probabilities.into_iter().collect();
+        self
+    }
+
+    /// Sets the probability of a single option.
+    #[must_use]
+    pub fn probability(mut self, option: WeatherOptions, probability: f64) -> Self {
+        self.config.probabilities.insert(option, probability);
+        self
+    }
+
+    /// Number of weather slots of the session.
+    #[must_use]
+    pub fn slots(mut self, session: Sessions, slots: usize) -> Self {
+        self.config.weather_slots.insert(session, slots);
+        self
+    }
+
+    /// Seed to generate reproducible forecasts, a random one is used otherwise.
+    #[must_use]
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    #[must_use]
+    pub fn coupling(mut self, coupling: CouplingConfig) -> Self {
+        self.config.coupling = coupling;
+        self
+    }
+
+    /// Lets the sessions share the weather regime of their day instead of the coupling.
+    #[must_use]
+    pub fn weekend(mut self, weekend: WeekendConfig) -> Self {
+        self.config.weekend = Some(weekend);
+        self
+    }
+
+    /// Climate preset the probabilities are based on.
+    #[must_use]
+    pub fn track(mut self, track: TrackPreset) -> Self {
+        self.config.track = Some(track);
+        self
+    }
+
+    /// Altitude and coast of the track, replacing the geography of the track preset.
+    #[must_use]
+    pub fn geography(mut self, geography: Geography) -> Self {
+        self.config.geography = Some(geography);
+        self
+    }
+
+    #[must_use]
+    pub fn sessions(mut self, sessions: Vec<SessionDefinition>) -> Self {
+        self.config.sessions = sessions;
+        self
+    }
+
+    #[must_use]
+    pub fn start_time(mut self, session: Sessions, time: TimeOfDay) -> Self {
+        self.config.start_times.insert(session, time);
+        self
+    }
+
+    #[must_use]
+    pub fn night_session(mut self, session: Sessions) -> Self {
+        self.config.night_sessions.insert(session);
+        self
+    }
+
+    #[must_use]
+    pub fn transitions(mut self, transitions: TransitionConfig) -> Self {
+        self.config.transitions = Some(transitions);
+        self
+    }
+
+    #[must_use]
+    pub fn drift(mut self, drift: DriftConfig) -> Self {
+        self.config.drift = Some(drift);
+        self
+    }
+
+    /// Enables `variety_mode` with the reintroduction factors of the held back weather groups.
+    #[must_use]
+    pub fn variety(mut self, reintroduction: ReintroductionConfig) -> Self {
+        self.config.variety_mode = true;
+        self.config.reintroduction = reintroduction;
+        self
+    }
+
+    #[must_use]
+    pub fn realism(mut self, realism: Realism) -> Self {
+        self.config.realism = realism;
+        self
+    }
+
+    #[must_use]
+    pub fn temperature(mut self, temperature: TemperatureConfig) -> Self {
+        self.config.temperature = temperature;
+        self
+    }
+
+    #[must_use]
+    pub fn constraints(mut self, constraints: Constraints) -> Self {
+        self.config.constraints = constraints;
+        self
+    }
+
+    /// Validates the settings like a config file.
+    pub fn build(self) -> Result<WeatherForecaster, ForecastError> {
+        let forecaster = WeatherForecaster::new(self.config)?;
+        Ok(match self.seed {
+            Some(seed) => forecaster.with_seed(seed),
+            None => forecaster,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builder_matches_the_config() {
+        let sessions = [Sessions::Qualifying, Sessions::Race];
+        let built = WeatherForecaster::builder()
+            .probabilities([(WeatherOptions::Clear, 0.5)])
+            .probability(WeatherOptions::Storm, 0.2)
+            .slots(Sessions::Race, 2)
+            .seed(11)
+            .build()
+            .unwrap()
+            .generate_forecast(&sessions)
+            .unwrap();
+
+        let config = Config {
+            probabilities: [(WeatherOptions::Clear, 0.5), (WeatherOptions::Storm, 0.2)]
+                .into_iter()
+                .collect(),
+            weather_slots: [(Sessions::Race, 2)].into_iter().collect(),
+            ..Config::default()
+        };
+        let configured = WeatherForecaster::new(config)
+            .unwrap()
+            .with_seed(11)
+            .generate_forecast(&sessions)
+            .unwrap();
+        assert_eq!(built, configured);
+
+        assert!(matches!(
+            WeatherForecaster::builder()
+                .probability(WeatherOptions::Rain, -1.0)
+                .build(),
+            Err(ForecastError::InvalidProbability { .. })
+        ));
+    }
+}
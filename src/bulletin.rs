@@ -0,0 +1,113 @@
+use crate::{
+    forecaster::{Sessions, WeatherForecast, WeatherOptions},
+    weekend::WeekendConfig,
+};
+
+impl WeatherForecast {
+    /// Race control bulletin telling the weather story of the weekend session by session, e.g.
+    /// for league briefings and broadcasts.
+    pub fn bulletin(&self) -> Bulletin<'_> {
+        Bulletin(self)
+    }
+
+    /// How the sky looks during a dry session.
+    fn describe_sky(&self, session: Sessions) -> &'static str {
+        let options = &self.forecast[&session];
+        if options
+            .iter()
+            .any(|option| matches!(option, WeatherOptions::Foggy | WeatherOptions::HeavyFog))
+        {
+            return "dry with fog";
+        }
+        let sunshine =
+            options.iter().map(WeatherOptions::sunshine).sum::<f64>() / options.len().max(1) as f64;
+        match sunshine {
+            sunshine if sunshine >= 0.7 => "dry and sunny",
+            sunshine if sunshine >= 0.4 => "dry with sunny spells",
+            _ => "dry under thick cloud",
+        }
+    }
+
+    /// Whether the first and the last slot of the session have rain.
+    fn wet_edges(&self, session: Sessions) -> (bool, bool) {
+        let options = &self.forecast[&session];
+        let wet = |option: Option<&WeatherOptions>| option.is_some_and(|o| o.rain_intensity() > 0);
+        (wet(options.first()), wet(options.last()))
+    }
+}
+
+pub struct Bulletin<'a>(&'a WeatherForecast);
+
+impl std::fmt::Display for Bulletin<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let forecast = self.0;
+        // the usual days of the sessions, the forecast does not know the configured ones
+        let weekend = WeekendConfig::default();
+        writeln!(f, "RACE CONTROL - WEATHER BULLETIN")?;
+
+        let mut previous: Option<Sessions> = None;
+        for session in forecast.sessions() {
+            let name = forecast.spoken_name(session);
+            let starts_wet = forecast.wet_edges(session).0;
+            if let Some(previous) = previous {
+                let previous_ends_wet = forecast.wet_edges(previous).1;
+                let gap = match (weekend.day(previous), weekend.day(session)) {
+                    (before, after) if before != after => format!("on {before:?} night"),
+                    _ => format!("after {}", forecast.spoken_name(previous)),
+                };
+                let lights_out = if session == Sessions::Race {
+                    " by lights-out"
+                } else {
+                    ""
+                };
+                match (previous_ends_wet, starts_wet) {
+                    (false, true) => writeln!(f, "A front moves in {gap}.")?,
+                    (true, false) => writeln!(f, "The rain clears {gap}, drying out{lights_out}.")?,
+                    (true, true) => writeln!(f, "The rain persists {gap}.")?,
+                    (false, false) => {}
+                }
+            }
+
+            let description = forecast
+                .describe_rain(session)
+                .unwrap_or_else(|| forecast.describe_sky(session).to_string());
+            writeln!(f, "{:?} {name}: {description}.", weekend.day(session))?;
+            previous = Some(session);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bulletin_tells_the_weather_story() {
+        let mut forecast = WeatherForecast::default();
+        forecast.forecast.insert(
+            Sessions::Practice,
+            vec![WeatherOptions::Clear, WeatherOptions::LightCloud],
+        );
+        forecast.forecast.insert(
+            Sessions::Qualifying,
+            vec![WeatherOptions::Overcast, WeatherOptions::Rain],
+        );
+        forecast.forecast.insert(
+            Sessions::Race,
+            vec![
+                WeatherOptions::HeavyCloud,
+                WeatherOptions::MediumCloud,
+                WeatherOptions::LightCloud,
+            ],
+        );
+        assert_eq!(
+            forecast.bulletin().to_string(),
+            "RACE CONTROL - WEATHER BULLETIN\n\
+             Friday practice: dry and sunny.\n\
+             Saturday qualifying: rain arriving mid-qualifying.\n\
+             The rain clears on Saturday night, drying out by lights-out.\n\
+             Sunday race: dry with sunny spells.\n"
+        );
+    }
+}
@@ -0,0 +1,347 @@
+#[cfg(feature = "fs")]
+use std::path::Path;
+use std::{collections::HashMap, fmt::Display, str::FromStr};
+
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+
+use crate::{
+    config::Config,
+    error::ForecastError,
+    forecaster::{Sessions, WeatherForecast, WeatherForecaster, WeatherOptions},
+    presets::TrackPreset,
+    season::Month,
+};
+
+/// Events of a championship that get their forecasts generated at once.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Calendar {
+    pub events: Vec<CalendarEvent>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CalendarEvent {
+    pub name: String,
+    /// Day of the event, its month determines the season instead of the config
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date: Option<EventDate>,
+    /// Circuit of the event, replaces the circuit and track of the config
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub circuit: Option<String>,
+    /// Climate preset of the event, replaces the track of the config
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub track: Option<TrackPreset>,
+    /// Sessions of the event, replaces the sessions selected on the command line
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sessions: Option<Vec<Sessions>>,
+    /// Replaces the slot counts of the config for the given sessions
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub weather_slots: HashMap<Sessions, usize>,
+    /// Replaces the probabilities of the config for the given options
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub probabilities: HashMap<WeatherOptions, f64>,
+}
+
+impl CalendarEvent {
+    /// The config of the event, based on the config shared by all events.
+    pub fn config(&self, config: &Config) -> Config {
+        let mut config = config.clone();
+        if let Some(date) = self.date {
+            config.month = Some(date.month());
+            config.season = None;
+        }
+        if self.circuit.is_some() {
+            config.circuit = self.circuit.clone();
+            config.track = None;
+        }
+        if self.track.is_some() {
+            config.track = self.track;
+        }
+        config.weather_slots.extend(&self.weather_slots);
+        config.probabilities.extend(&self.probabilities);
+        config
+    }
+}
+
+/// Day of an event, written as `YYYY-MM-DD`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct EventDate {
+    year: u16,
+    month: u8,
+    day: u8,
+}
+
+impl EventDate {
+    pub fn new(year: u16, month: u8, day: u8) -> Result<Self, ForecastError> {
+        if day == 0 || day > days_in_month(year, month) {
+            return Err(ForecastError::InvalidDate(format!(
+                "{year:04}-{month:02}-{day:02}"
+            )));
+        }
+        Ok(Self { year, month, day })
+    }
+
+    pub fn year(&self) -> u16 {
+        self.year
+    }
+
+    pub fn month(&self) -> Month {
+        Month::iter()
+            .nth(usize::from(self.month) - 1)
+            .expect("month is validated")
+    }
+
+    /// First and last day of the month of the date in `year`.
+    pub fn month_in(&self, year: u16) -> (EventDate, EventDate) {
+        let first = Self {
+            year,
+            month: self.month,
+            day: 1,
+        };
+        let last = Self {
+            day: days_in_month(year, self.month),
+            ..first
+        };
+        (first, last)
+    }
+}
+
+/// Number of days of the month, 0 if it is not a month.
+fn days_in_month(year: u16, month: u8) -> u8 {
+    let leap_year =
+        year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400));
+    match month {
+        2 if leap_year => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        1..=12 => 31,
+        _ => 0,
+    }
+}
+
+impl FromStr for EventDate {
+    type Err = ForecastError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ForecastError::InvalidDate(s.to_string());
+        let (year, rest) = s.trim().split_once('-').ok_or_else(invalid)?;
+        let (month, day) = rest.split_once('-').ok_or_else(invalid)?;
+        let year = year.parse().map_err(|_| invalid())?;
+        let month = month.parse().map_err(|_| invalid())?;
+        let day = day.parse().map_err(|_| invalid())?;
+        Self::new(year, month, day).map_err(|_| invalid())
+    }
+}
+
+impl TryFrom<String> for EventDate {
+    type Error = ForecastError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<EventDate> for String {
+    fn from(value: EventDate) -> Self {
+        value.to_string()
+    }
+}
+
+impl Display for EventDate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl Calendar {
+    #[cfg(feature = "fs")]
+    pub fn load(path: &Path, format: crate::config::ConfigFormat) -> Result<Self, ForecastError> {
+        let content =
+            std::fs::read_to_string(path).map_err(|error| ForecastError::io(path, error))?;
+        format.parse(&content)
+    }
+
+    /// Generates the forecasts of all events. Every event gets its own seed derived from `seed`,
+    /// so a single event can be reproduced later on.
+    pub fn generate(
+        &self,
+        config: &Config,
+        sessions: &[Sessions],
+        seed: u64,
+    ) -> Result<Vec<EventForecast>, ForecastError> {
+        let mut seeds = StdRng::seed_from_u64(seed);
+        self.events
+            .iter()
+            .map(|event| {
+                let seed = seeds.random();
+                let sessions = event.sessions.as_deref().unwrap_or(sessions);
+                let forecast = WeatherForecaster::new(event.config(config))?
+                    .with_seed(seed)
+                    .generate_constrained_forecast(sessions)?;
+                Ok(EventForecast {
+                    name: event.name.clone(),
+                    date: event.date,
+                    seed,
+                    forecast,
+                })
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventForecast {
+    pub name: String,
+    pub date: Option<EventDate>,
+    pub seed: u64,
+    pub forecast: WeatherForecast,
+}
+
+impl EventForecast {
+    /// File name friendly version of the event name.
+    pub fn file_stem(&self, round: usize) -> String {
+        let name: String = self
+            .name
+            .chars()
+            .map(|char| {
+                if char.is_alphanumeric() {
+                    char.to_ascii_lowercase()
+                } else {
+                    '-'
+                }
+            })
+            .collect();
+        format!("{round:02}-{}", name.trim_matches('-'))
+    }
+
+    fn is_wet(&self, session: Sessions) -> Option<bool> {
+        self.forecast
+            .forecast
+            .get(&session)
+            .map(|options| options.iter().any(|option| option.rain_intensity() > 0))
+    }
+}
+
+/// Overview of the weather of all events of a season.
+pub struct SeasonSummary<'a>(pub &'a [EventForecast]);
+
+impl std::fmt::Display for SeasonSummary<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name_length = self
+            .0
+            .iter()
+            .map(|event| event.name.len())
+            .max()
+            .unwrap_or_default()
+            .max("Event".len());
+
+        writeln!(
+            f,
+            "Round : {:<name_length$} : Date       : Qualify : Race : Seed",
+            "Event"
+        )?;
+        let mut wet_races = 0;
+        for (index, event) in self.0.iter().enumerate() {
+            let describe = |wet: Option<bool>| match wet {
+                Some(true) => "wet",
+                Some(false) => "dry",
+                None => "-",
+            };
+            let race = event.is_wet(Sessions::Race);
+            if race == Some(true) {
+                wet_races += 1;
+            }
+            writeln!(
+                f,
+                "{:>5} : {:<name_length$} : {:<10} : {:<7} : {:<4} : {}",
+                index + 1,
+                event.name,
+                event
+                    .date
+                    .map(|date| date.to_string())
+                    .unwrap_or_else(|| "-".to_string()),
+                describe(event.is_wet(Sessions::Qualifying)),
+                describe(race),
+                event.seed,
+            )?;
+        }
+        writeln!(f)?;
+        writeln!(f, "{wet_races} of {} races are wet", self.0.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::season::Season;
+
+    #[test]
+    fn every_event_gets_a_forecast() {
+        let calendar: Calendar = serde_json::from_value(serde_json::json!({
+            "events": [
+                {"name": "Spa-Francorchamps", "track": "spa"},
+                {"name": "Bahrain GP", "track": "sakhir"},
+            ],
+        }))
+        .unwrap();
+        let sessions = [Sessions::Qualifying, Sessions::Race];
+        let season = calendar.generate(&Config::default(), &sessions, 3).unwrap();
+
+        assert_eq!(season.len(), 2);
+        assert_ne!(season[0].seed, season[1].seed);
+        assert_eq!(season[1].file_stem(2), "02-bahrain-gp");
+        assert_eq!(
+            calendar.generate(&Config::default(), &sessions, 3).unwrap(),
+            season
+        );
+
+        let event = &season[0];
+        let config = Config {
+            track: Some(TrackPreset::Spa),
+            ..Config::default()
+        };
+        let forecast = WeatherForecaster::new(config)
+            .unwrap()
+            .with_seed(event.seed)
+            .generate_forecast(&sessions)
+            .unwrap();
+        assert_eq!(forecast, event.forecast);
+    }
+
+    #[test]
+    fn events_override_the_config() {
+        let calendar: Calendar = serde_json::from_value(serde_json::json!({
+            "events": [{
+                "name": "Suzuka",
+                "date": "2025-10-05",
+                "track": "suzuka",
+                "sessions": ["Race"],
+                "weather_slots": {"Race": 2},
+                "probabilities": {"Rain": 0.5},
+            }],
+        }))
+        .unwrap();
+        let event = &calendar.events[0];
+        assert_eq!(event.date, Some(EventDate::new(2025, 10, 5).unwrap()));
+
+        let config = event.config(&Config {
+            season: Some(Season::Summer),
+            ..Config::default()
+        });
+        assert_eq!(config.get_season(), Some(Season::Autumn));
+        assert_eq!(config.weather_slots[&Sessions::Race], 2);
+        assert_eq!(config.probabilities[&WeatherOptions::Rain], 0.5);
+
+        let season = calendar
+            .generate(&Config::default(), &[Sessions::Practice], 0)
+            .unwrap();
+        assert!(!season[0].forecast.contains(Sessions::Practice));
+        assert_eq!(season[0].forecast.forecast[&Sessions::Race].len(), 2);
+
+        assert!("2025-02-29".parse::<EventDate>().is_err());
+        assert!("2024-02-29".parse::<EventDate>().is_ok());
+        assert!("2024-13-01".parse::<EventDate>().is_err());
+    }
+}
@@ -0,0 +1,292 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+
+use crate::{error::ForecastError, forecaster::WeatherOptions, groups::WeatherGroups};
+
+/// Weather option defined in the config.
+///
+/// Every field apart from the name is optional for the built-in options. Custom options need a
+/// probability and behave like `like` (default: `Random`) when temperatures and other physical
+/// conditions are generated.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct WeatherDefinition {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub probability: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rain_intensity: Option<usize>,
+    /// Value written into the game's settings, defaults to the name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub game_string: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub like: Option<WeatherOptions>,
+}
+
+/// Fully resolved weather option the forecaster samples from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CatalogEntry {
+    pub name: String,
+    /// Built-in option used for everything that is not configurable per entry
+    pub option: WeatherOptions,
+    pub group: String,
+    pub rain_intensity: usize,
+    pub game_string: String,
+    /// Explicitly configured probability
+    pub probability: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeatherCatalog {
+    entries: Vec<CatalogEntry>,
+}
+
+impl WeatherCatalog {
+    /// Catalog of all built-in [`WeatherOptions`].
+    pub fn built_in(groups: &WeatherGroups) -> Self {
+        Self {
+            entries: WeatherOptions::iter()
+                .map(|option| CatalogEntry {
+                    name: format!("{option:?}"),
+                    option,
+                    group: groups.get_group_name(option).to_string(),
+                    rain_intensity: option.rain_intensity(),
+                    game_string: option.to_string(),
+                    probability: None,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn new(
+        definitions: Vec<WeatherDefinition>,
+        groups: &WeatherGroups,
+    ) -> Result<Self, ForecastError> {
+        let mut entries: Vec<CatalogEntry> = Vec::with_capacity(definitions.len());
+        for definition in definitions {
+            if entries.iter().any(|entry| entry.name == definition.name) {
+                return Err(ForecastError::DuplicateWeather(definition.name));
+            }
+            let built_in =
+                WeatherOptions::iter().find(|option| format!("{option:?}") == definition.name);
+            if built_in.is_none() && definition.probability.is_none() {
+                return Err(ForecastError::MissingProbability(definition.name));
+            }
+
+            let option = definition
+                .like
+                .or(built_in)
+                .unwrap_or(WeatherOptions::Random);
+            entries.push(CatalogEntry {
+                group: definition
+                    .group
+                    .unwrap_or_else(|| groups.get_group_name(option).to_string()),
+                rain_intensity: definition
+                    .rain_intensity
+                    .unwrap_or_else(|| option.rain_intensity()),
+                game_string: definition
+                    .game_string
+                    .unwrap_or_else(|| definition.name.clone()),
+                probability: definition.probability,
+                name: definition.name,
+                option,
+            });
+        }
+
+        if entries.is_empty() {
+            return Err(ForecastError::NoProbabilities);
+        }
+        Ok(Self { entries })
+    }
+
+    pub fn entries(&self) -> &[CatalogEntry] {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Gives each weighted group the configured share of the `probabilities`, distributed among its
+    /// entries proportionally to their probabilities. The other entries share what is left.
+    pub fn weight_groups(
+        &self,
+        probabilities: &mut [f64],
+        weights: &HashMap<String, f64>,
+    ) -> Result<(), ForecastError> {
+        if weights.is_empty() {
+            return Ok(());
+        }
+        for (group, weight) in weights {
+            if !self.entries.iter().any(|entry| entry.group == *group) {
+                return Err(ForecastError::UnknownGroup(group.clone()));
+            }
+            if !weight.is_finite() || !(0.0..=1.0).contains(weight) {
+                return Err(ForecastError::InvalidProbability {
+                    option: format!("group {group}"),
+                    value: *weight,
+                });
+            }
+        }
+        let total_weight: f64 = weights.values().sum();
+        if total_weight > 1.0 {
+            return Err(ForecastError::InvalidProbability {
+                option: "all weighted groups".to_string(),
+                value: total_weight,
+            });
+        }
+
+        let mut scale = |members: Vec<usize>, share: f64| {
+            let sum: f64 = members.iter().map(|index| probabilities[*index]).sum();
+            for index in &members {
+                probabilities[*index] = if sum > 0.0 {
+                    probabilities[*index] / sum * share
+                } else {
+                    share / members.len() as f64
+                };
+            }
+        };
+        for (group, weight) in weights {
+            scale(self.members(|entry| entry.group == *group), *weight);
+        }
+        let rest = self.members(|entry| !weights.contains_key(&entry.group));
+        if !rest.is_empty() {
+            scale(rest, 1.0 - total_weight);
+        }
+        Ok(())
+    }
+
+    fn members(&self, predicate: impl Fn(&CatalogEntry) -> bool) -> Vec<usize> {
+        (0..self.entries.len())
+            .filter(|index| predicate(&self.entries[*index]))
+            .collect()
+    }
+
+    /// Index of the first entry based on the given built-in option.
+    pub fn position(&self, option: WeatherOptions) -> Option<usize> {
+        self.entries.iter().position(|entry| entry.option == option)
+    }
+}
+
+impl std::ops::Index<usize> for WeatherCatalog {
+    type Output = CatalogEntry;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.entries[index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::assert_approx_eq;
+
+    use super::*;
+
+    fn definition(name: &str) -> WeatherDefinition {
+        WeatherDefinition {
+            name: name.to_string(),
+            probability: None,
+            group: None,
+            rain_intensity: None,
+            game_string: None,
+            like: None,
+        }
+    }
+
+    #[test]
+    fn definitions_fall_back_to_built_in_values() {
+        let groups = WeatherGroups::default();
+        let catalog = WeatherCatalog::new(
+            vec![
+                definition("Rain"),
+                WeatherDefinition {
+                    probability: Some(0.1),
+                    rain_intensity: Some(1),
+                    like: Some(WeatherOptions::LightRain),
+                    ..definition("Drizzle")
+                },
+                WeatherDefinition {
+                    probability: Some(0.1),
+                    group: Some("desert".to_string()),
+                    game_string: Some("SAND".to_string()),
+                    ..definition("Sandstorm")
+                },
+            ],
+            &groups,
+        )
+        .unwrap();
+
+        assert_eq!(catalog[0].option, WeatherOptions::Rain);
+        assert_eq!(catalog[0].rain_intensity, 2);
+        assert_eq!(catalog[0].group, "wet");
+        assert_eq!(catalog[1].option, WeatherOptions::LightRain);
+        assert_eq!(catalog[1].group, "drizzle");
+        assert_eq!(catalog[1].game_string, "Drizzle");
+        assert_eq!(catalog[2].option, WeatherOptions::Random);
+        assert_eq!(catalog[2].group, "desert");
+        assert_eq!(catalog[2].game_string, "SAND");
+    }
+
+    #[test]
+    fn custom_options_need_a_probability() {
+        let groups = WeatherGroups::default();
+        assert!(matches!(
+            WeatherCatalog::new(vec![definition("Sleet")], &groups),
+            Err(ForecastError::MissingProbability(_))
+        ));
+        assert!(matches!(
+            WeatherCatalog::new(vec![definition("Rain"), definition("Rain")], &groups),
+            Err(ForecastError::DuplicateWeather(_))
+        ));
+    }
+
+    #[test]
+    fn group_weights_are_distributed_proportionally() {
+        let catalog = WeatherCatalog::built_in(&WeatherGroups::default());
+        let defaults = WeatherOptions::get_default_probablities();
+        let mut probabilities: Vec<f64> = catalog
+            .entries()
+            .iter()
+            .map(|entry| defaults[&entry.option])
+            .collect();
+        let weights = [("wet".to_string(), 0.25)].into_iter().collect();
+        catalog.weight_groups(&mut probabilities, &weights).unwrap();
+
+        let share = |group: &str| -> f64 {
+            catalog
+                .entries()
+                .iter()
+                .zip(&probabilities)
+                .filter(|(entry, _)| entry.group == group)
+                .map(|(_, probability)| probability)
+                .sum()
+        };
+        assert_approx_eq!(f64, share("wet"), 0.25, epsilon = 1e-9);
+        assert_approx_eq!(f64, probabilities.iter().sum(), 1.0, epsilon = 1e-9);
+        let rain = catalog.position(WeatherOptions::Rain).unwrap();
+        let fog_with_rain = catalog.position(WeatherOptions::FogWithRain).unwrap();
+        assert_approx_eq!(
+            f64,
+            probabilities[rain] / probabilities[fog_with_rain],
+            defaults[&WeatherOptions::Rain] / defaults[&WeatherOptions::FogWithRain],
+            epsilon = 1e-9
+        );
+
+        let weights = [("sleet".to_string(), 0.25)].into_iter().collect();
+        assert!(matches!(
+            catalog.weight_groups(&mut probabilities, &weights),
+            Err(ForecastError::UnknownGroup(_))
+        ));
+        let weights = [("wet".to_string(), 0.75), ("dry".to_string(), 0.5)]
+            .into_iter()
+            .collect();
+        assert!(catalog.weight_groups(&mut probabilities, &weights).is_err());
+    }
+}
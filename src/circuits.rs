@@ -0,0 +1,146 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{error::ForecastError, live::Coordinates, presets::TrackPreset};
+
+/// Built-in circuits as name, country, latitude, longitude and typical climate.
+const BUILT_IN_CIRCUITS: &[(&str, &str, f64, f64, TrackPreset)] = {
+    use TrackPreset::*;
+    &[
+        ("Spa-Francorchamps", "Belgium", 50.437, 5.971, Spa),
+        ("Silverstone", "United Kingdom", 52.079, -1.017, Spa),
+        ("Nurburgring", "Germany", 50.336, 6.947, Spa),
+        ("Zandvoort", "Netherlands", 52.389, 4.541, Spa),
+        ("Sakhir", "Bahrain", 26.032, 50.511, Sakhir),
+        ("Yas Marina", "United Arab Emirates", 24.467, 54.603, Sakhir),
+        ("Jeddah", "Saudi Arabia", 21.632, 39.104, Sakhir),
+        ("Lusail", "Qatar", 25.490, 51.454, Sakhir),
+        ("Las Vegas", "United States", 36.116, -115.174, Sakhir),
+        ("Suzuka", "Japan", 34.843, 136.541, Suzuka),
+        ("Fuji", "Japan", 35.372, 138.927, Suzuka),
+        ("Shanghai", "China", 31.338, 121.220, Suzuka),
+        ("Monza", "Italy", 45.618, 9.281, Suzuka),
+        (
+            "Circuit of the Americas",
+            "United States",
+            30.133,
+            -97.641,
+            Suzuka,
+        ),
+        ("Interlagos", "Brazil", -23.704, -46.700, Interlagos),
+        ("Marina Bay", "Singapore", 1.291, 103.864, Interlagos),
+        ("Sepang", "Malaysia", 2.761, 101.737, Interlagos),
+        ("Miami", "United States", 25.958, -80.239, Interlagos),
+    ]
+};
+
+/// Real world circuit, its location is used for live weather and its climate if no track preset
+/// is chosen.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct Circuit {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub country: Option<String>,
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Climate preset the probabilities are based on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub climate: Option<TrackPreset>,
+}
+
+impl Circuit {
+    pub fn coordinates(&self) -> Coordinates {
+        Coordinates {
+            latitude: self.latitude,
+            longitude: self.longitude,
+        }
+    }
+}
+
+/// All built-in circuits.
+pub fn built_in() -> Vec<Circuit> {
+    BUILT_IN_CIRCUITS
+        .iter()
+        .map(|(name, country, latitude, longitude, climate)| Circuit {
+            name: name.to_string(),
+            country: Some(country.to_string()),
+            latitude: *latitude,
+            longitude: *longitude,
+            climate: Some(*climate),
+        })
+        .collect()
+}
+
+/// The circuit with the name, the `custom` circuits take precedence over the built-in ones.
+/// Case and punctuation are ignored and the beginning of a name is enough if it is unique, e.g.
+/// `spa` or `cota` for the Circuit of the Americas.
+pub fn find(name: &str, custom: &[Circuit]) -> Result<Circuit, ForecastError> {
+    let query = normalize(name);
+    let circuits: Vec<Circuit> = custom.iter().cloned().chain(built_in()).collect();
+    if let Some(circuit) = circuits.iter().find(|circuit| {
+        normalize(&circuit.name) == query
+            || abbreviation(&circuit.name).is_some_and(|abbreviation| abbreviation == query)
+    }) {
+        return Ok(circuit.clone());
+    }
+
+    let mut matches = circuits
+        .iter()
+        .filter(|circuit| !query.is_empty() && normalize(&circuit.name).starts_with(&query));
+    match (matches.next(), matches.next()) {
+        (Some(circuit), None) => Ok(circuit.clone()),
+        _ => Err(ForecastError::UnknownCircuit(name.to_string())),
+    }
+}
+
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|char| char.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// First letters of the words of the name, `None` for names of a single word.
+fn abbreviation(name: &str) -> Option<String> {
+    (name.split_whitespace().count() > 1).then(|| {
+        name.split_whitespace()
+            .filter_map(|word| word.chars().next())
+            .flat_map(char::to_lowercase)
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn circuits_are_found_by_name() {
+        assert_eq!(find("spa", &[]).unwrap().climate, Some(TrackPreset::Spa));
+        assert_eq!(find("COTA", &[]).unwrap().name, "Circuit of the Americas");
+        assert_eq!(find("nurburg", &[]).unwrap().name, "Nurburgring");
+        assert!(matches!(
+            find("S", &[]),
+            Err(ForecastError::UnknownCircuit(_))
+        ));
+
+        let custom = Circuit {
+            name: "Spa Karting".to_string(),
+            country: None,
+            latitude: 50.4,
+            longitude: 5.9,
+            climate: None,
+        };
+        assert!(find("spa", std::slice::from_ref(&custom)).is_err());
+        assert_eq!(
+            find("spakarting", std::slice::from_ref(&custom)).unwrap(),
+            custom
+        );
+
+        let config = crate::config::Config {
+            circuit: Some("silverstone".to_string()),
+            ..Default::default()
+        };
+        let circuit = config.find_circuit().unwrap().unwrap();
+        assert_eq!(circuit.climate, Some(TrackPreset::Spa));
+    }
+}
@@ -0,0 +1,80 @@
+#[cfg(feature = "online")]
+use std::collections::HashMap;
+
+use crate::{calendar::EventDate, live::Coordinates};
+#[cfg(feature = "online")]
+use crate::{
+    error::ForecastError,
+    forecaster::WeatherOptions,
+    live::{HourlyWeather, LiveForecast},
+};
+
+const ARCHIVE_URL: &str = "https://archive-api.open-meteo.com/v1/archive";
+
+/// Number of past years the climate of a date is based on by default.
+pub const DEFAULT_YEARS: u16 = 10;
+
+/// Periods the climate of `date` is based on: its month in each of the `years` before it.
+pub fn periods(date: EventDate, years: u16) -> Vec<(EventDate, EventDate)> {
+    (1..=years.min(date.year()))
+        .map(|offset| date.month_in(date.year() - offset))
+        .collect()
+}
+
+/// Open-Meteo request of the hourly weather codes from the ERA5 reanalysis.
+pub fn archive_url(coordinates: Coordinates, start: EventDate, end: EventDate) -> String {
+    format!(
+        "{ARCHIVE_URL}?latitude={}&longitude={}&start_date={start}&end_date={end}\
+         &hourly=weather_code",
+        coordinates.latitude, coordinates.longitude
+    )
+}
+
+/// How often every weather option appeared at the coordinates in the month of `date` over the
+/// last `years` years.
+#[cfg(feature = "online")]
+pub fn fetch(
+    coordinates: Coordinates,
+    date: EventDate,
+    years: u16,
+) -> Result<HashMap<WeatherOptions, f64>, ForecastError> {
+    let mut weather_code = Vec::new();
+    for (start, end) in periods(date, years) {
+        tracing::debug!("Fetching the weather from {start} to {end}");
+        let body = ureq::get(&archive_url(coordinates, start, end))
+            .call()
+            .and_then(|mut response| response.body_mut().read_to_string())
+            .map_err(|error| ForecastError::LiveWeather(error.to_string()))?;
+        let archive: LiveForecast = serde_json::from_str(&body)?;
+        weather_code.extend(archive.hourly.weather_code);
+    }
+    LiveForecast {
+        hourly: HourlyWeather { weather_code },
+    }
+    .probabilities()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_month_of_previous_years() {
+        let date: EventDate = "2025-02-14".parse().unwrap();
+        let periods: Vec<_> = periods(date, 2)
+            .iter()
+            .map(|(start, end)| format!("{start}..{end}"))
+            .collect();
+        assert_eq!(
+            periods,
+            ["2024-02-01..2024-02-29", "2023-02-01..2023-02-28"]
+        );
+
+        let spa: Coordinates = "50.437,5.971".parse().unwrap();
+        let (start, end) = date.month_in(2024);
+        assert!(
+            archive_url(spa, start, end)
+                .ends_with("start_date=2024-02-01&end_date=2024-02-29&hourly=weather_code")
+        );
+    }
+}
@@ -1,20 +1,188 @@
-use std::{collections::HashMap, path::Path};
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
 
-use serde::{Deserialize, Serialize};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
 
-use crate::forecaster::{Sessions, WeatherOptions};
+use crate::{
+    catalog::WeatherDefinition,
+    circuits::{self, Circuit},
+    constraints::Constraints,
+    coupling::CouplingConfig,
+    drift::DriftConfig,
+    error::ForecastError,
+    forecaster::{DEFAULT_MAX_WEATHER_SLOTS, Sessions, WeatherOptions},
+    geography::Geography,
+    history::HistoryConfig,
+    migration::{self, CONFIG_VERSION},
+    mqtt::MqttConfig,
+    output::{OutputConfig, OutputFormat},
+    presets::TrackPreset,
+    realism::Realism,
+    season::{Month, Season, SeasonModifier},
+    sessions::SessionDefinition,
+    temperature::TemperatureConfig,
+    time_of_day::TimeOfDay,
+    transitions::TransitionConfig,
+    unknown_keys::{self, UnknownKey},
+    variety::ReintroductionConfig,
+    weekend::WeekendConfig,
+};
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// Directory next to the config file with overrides per track preset.
+pub const TRACK_OVERRIDES_DIRECTORY: &str = "config.d";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct Config {
+    /// Version of the structure of the config file, older files are migrated when they are
+    /// loaded
+    #[serde(default)]
+    pub version: u32,
+    /// Climate preset used as base for the probabilities
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub track: Option<TrackPreset>,
+    /// Real world circuit of the event, its climate is used if no track preset is chosen and its
+    /// location for live weather
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub circuit: Option<String>,
+    /// Circuits in addition to the built-in ones
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub circuits: Vec<Circuit>,
+    /// Altitude and coast of the track shaping storms, fog and temperatures, defaults to the
+    /// geography of the track preset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub geography: Option<Geography>,
+    /// Explicit probabilities, these take precedence over the ones of the track preset. Without
+    /// any and without a preset the built-in probabilities are used
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub probabilities: HashMap<WeatherOptions, f64>,
+    /// Probability of a whole group, e.g. `wet: 0.25`, distributed among its options
+    /// proportionally to their probabilities
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub group_probabilities: HashMap<String, f64>,
+    /// Month of the event, used to determine the season if it is not set explicitly
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub month: Option<Month>,
+    /// Season of the event, scales the probabilities of rain, fog and storms
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub season: Option<Season>,
+    /// Replaces the built-in modifiers of the given seasons
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub season_modifiers: HashMap<Season, SeasonModifier>,
     pub weather_slots: HashMap<Sessions, usize>,
+    /// Sessions of the weekend in the order they are printed, replacing the built-in names and key
+    /// prefixes
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sessions: Vec<SessionDefinition>,
+    /// Highest number of weather slots per session, defaults to 4. Raise it for endurance events
+    /// if the game supports more slots
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_weather_slots: Option<usize>,
+    /// Local start time of the sessions, used to make fog more likely in the morning and
+    /// thunderstorms in the afternoon
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub start_times: HashMap<Sessions, TimeOfDay>,
+    /// Sessions that take place at night, with more fog, lower temperatures and no haze
+    #[serde(default, skip_serializing_if = "HashSet::is_empty")]
+    pub night_sessions: HashSet<Sessions>,
+    /// Weather of the first slot of a session, e.g. the current weather at the track
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub start_weather: HashMap<Sessions, WeatherOptions>,
+    /// Highest chance of each weather slot of a session to be wet
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub max_rain_chance: HashMap<Sessions, f64>,
+    /// Generate when each weather slot begins and how long it lasts and print them as additional
+    /// keys
+    #[serde(default)]
+    pub slot_start_times: bool,
+    /// Print the wetness of the track at the start of every session as additional keys, for
+    /// games with a starting road wetness setting
+    #[serde(default)]
+    pub track_wetness: bool,
+    /// Relative duration of the weather slots of a group, e.g. `storm: 0.4` for short bursts,
+    /// replaces the built-in duration of the group
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub slot_durations: HashMap<String, f64>,
+    /// Length of the sessions in minutes, together with the start time of a session the slot
+    /// starts are also printed as clock times
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub session_lengths: HashMap<Sessions, u32>,
+    /// Weather options to pick from, replacing the built-in options
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub catalog: Option<Vec<WeatherDefinition>>,
+    /// Custom groups of similar weather options, replacing the built-in groups
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub groups: Option<IndexMap<String, Vec<WeatherOptions>>>,
+    /// How the rain of the sessions depends on the race
+    #[serde(default)]
+    pub coupling: CouplingConfig,
+    /// Models the weekend as days whose sessions share the weather regime of the day when set,
+    /// replaces the coupling
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weekend: Option<WeekendConfig>,
+    /// Makes the weather of a slot depend on the previous slot when set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transitions: Option<TransitionConfig>,
+    /// Lets the weather of a slot drift from the previous slots when set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub drift: Option<DriftConfig>,
+    /// How strictly consecutive slots follow the built-in graph of plausible weather changes
+    #[serde(default)]
+    pub realism: Realism,
+    /// Holds back the weather groups the previous sessions of the same kind used, e.g. for a
+    /// season of races that should not all look alike
+    #[serde(default)]
+    pub variety_mode: bool,
+    /// How quickly held back weather groups come back in `variety_mode`
+    #[serde(default)]
+    pub reintroduction: ReintroductionConfig,
+    #[serde(default)]
+    pub temperature: TemperatureConfig,
+    /// Requirements every forecast has to fulfill
+    #[serde(default, skip_serializing_if = "Constraints::is_empty")]
+    pub constraints: Constraints,
     pub set_clipboard: bool,
+    /// What gets copied to the clipboard
+    #[serde(default)]
+    pub clipboard_format: OutputFormat,
+    /// Show an emoji next to the weather in markdown tables
+    #[serde(default)]
+    pub emoji: bool,
+    /// Discord webhook URL the forecast gets posted to, requires the `discord` feature
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub discord_webhook: Option<String>,
+    /// MQTT broker the forecast gets published to, requires the `mqtt` feature
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mqtt: Option<MqttConfig>,
+    /// File the forecast gets written to
+    #[serde(default)]
+    pub output: OutputConfig,
+    /// Handlebars template file the forecast is printed with instead of the game's keys
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<PathBuf>,
+    /// Archive of all generated forecasts
+    #[serde(default)]
+    pub history: HistoryConfig,
+    /// Named sets of overrides, a profile replaces the top level keys of the config it contains
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub profiles: IndexMap<String, serde_json::Value>,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Config {
-            probabilities: WeatherOptions::get_default_probablities(),
+            version: CONFIG_VERSION,
+            track: None,
+            circuit: None,
+            circuits: Vec::new(),
+            geography: None,
+            probabilities: HashMap::new(),
+            group_probabilities: HashMap::new(),
+            month: None,
+            season: None,
+            season_modifiers: HashMap::new(),
             weather_slots: [
                 (Sessions::Practice, 4),
                 (Sessions::Qualifying, 2),
@@ -22,15 +190,664 @@ impl Default for Config {
             ]
             .into_iter()
             .collect(),
+            sessions: Vec::new(),
+            max_weather_slots: None,
+            start_times: HashMap::new(),
+            night_sessions: HashSet::new(),
+            start_weather: HashMap::new(),
+            max_rain_chance: HashMap::new(),
+            slot_start_times: false,
+            track_wetness: false,
+            slot_durations: HashMap::new(),
+            session_lengths: HashMap::new(),
+            catalog: None,
+            groups: None,
+            coupling: CouplingConfig::default(),
+            weekend: None,
+            transitions: None,
+            drift: None,
+            realism: Realism::default(),
+            variety_mode: false,
+            reintroduction: ReintroductionConfig::default(),
+            temperature: TemperatureConfig::default(),
+            constraints: Constraints::default(),
             set_clipboard: false,
+            clipboard_format: OutputFormat::default(),
+            emoji: false,
+            discord_webhook: None,
+            mqtt: None,
+            output: OutputConfig::default(),
+            template: None,
+            history: HistoryConfig::default(),
+            profiles: IndexMap::new(),
+        }
+    }
+}
+
+/// File format of a config file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum ConfigFormat {
+    Yaml,
+    Toml,
+    Json,
+}
+
+impl ConfigFormat {
+    /// Detects the format by the file extension, falling back to YAML.
+    pub fn from_path(path: &Path) -> Self {
+        match path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| extension.to_ascii_lowercase())
+            .as_deref()
+        {
+            Some("toml") => ConfigFormat::Toml,
+            Some("json") => ConfigFormat::Json,
+            _ => ConfigFormat::Yaml,
+        }
+    }
+
+    pub fn parse<T: DeserializeOwned>(&self, content: &str) -> Result<T, ForecastError> {
+        Ok(match self {
+            #[cfg(feature = "yaml")]
+            ConfigFormat::Yaml => serde_yaml::from_str(content)?,
+            #[cfg(feature = "toml")]
+            ConfigFormat::Toml => toml::from_str(content)?,
+            ConfigFormat::Json => serde_json::from_str(content)?,
+            #[allow(unreachable_patterns)]
+            format => return Err(format.unavailable()),
+        })
+    }
+
+    pub fn serialize<T: Serialize>(&self, value: &T) -> Result<String, ForecastError> {
+        Ok(match self {
+            #[cfg(feature = "yaml")]
+            ConfigFormat::Yaml => serde_yaml::to_string(value)?,
+            #[cfg(feature = "toml")]
+            ConfigFormat::Toml => toml::to_string_pretty(value)?,
+            ConfigFormat::Json => serde_json::to_string_pretty(value)?,
+            #[allow(unreachable_patterns)]
+            format => return Err(format.unavailable()),
+        })
+    }
+
+    /// Error for a format whose feature is disabled, JSON is always available.
+    fn unavailable(&self) -> ForecastError {
+        let (what, feature) = if *self == ConfigFormat::Toml {
+            ("TOML configs", "toml")
+        } else {
+            ("YAML configs", "yaml")
+        };
+        ForecastError::MissingFeature { what, feature }
+    }
+
+    /// Finds the line of `key` inside the top level map `section`. An empty section searches for
+    /// top level keys.
+    fn find_line(&self, content: &str, section: &str, key: &str) -> Option<usize> {
+        match self {
+            ConfigFormat::Yaml => find_yaml_line(content, section, key),
+            ConfigFormat::Toml => find_toml_line(content, section, key),
+            ConfigFormat::Json => find_json_line(content, section, key),
         }
     }
 }
 
 impl Config {
-    pub fn generate_default_config(path: &Path) -> Result<(), std::io::Error> {
-        let yaml = serde_yaml::to_string(&Config::default()).unwrap();
-        std::fs::write(path, yaml)?;
+    /// The circuit of the event, if one is chosen.
+    pub fn find_circuit(&self) -> Result<Option<Circuit>, ForecastError> {
+        self.circuit
+            .as_deref()
+            .map(|name| circuits::find(name, &self.circuits))
+            .transpose()
+    }
+
+    /// Loads the config, unknown keys are ignored with a warning or rejected if `strict`.
+    #[cfg(feature = "fs")]
+    pub fn load(path: &Path, format: ConfigFormat, strict: bool) -> Result<Self, ForecastError> {
+        let content =
+            std::fs::read_to_string(path).map_err(|error| ForecastError::io(path, error))?;
+        let (config, unknown) = Self::parse(&content, format)?;
+        report_unknown_keys(unknown, strict)?;
+        Ok(config)
+    }
+
+    /// Applies the overrides of the track preset from the `config.d` directory next to the
+    /// config file, e.g. `config.d/spa.yml`, whose top level keys replace the ones of the config.
+    /// The config stays unchanged if there is no file for the track.
+    #[cfg(feature = "fs")]
+    pub fn with_track_overrides(
+        self,
+        config_file: &Path,
+        strict: bool,
+    ) -> Result<Self, ForecastError> {
+        let Some(track) = self.track else {
+            return Ok(self);
+        };
+        let directory = config_file
+            .parent()
+            .unwrap_or(Path::new(""))
+            .join(TRACK_OVERRIDES_DIRECTORY);
+        let name = format!("{track:?}").to_lowercase();
+        let Some(path) = ["yml", "yaml", "toml", "json"]
+            .iter()
+            .map(|extension| directory.join(format!("{name}.{extension}")))
+            .find(|path| path.is_file())
+        else {
+            return Ok(self);
+        };
+
+        tracing::info!("Applying the track overrides {}", path.display());
+        let content =
+            std::fs::read_to_string(&path).map_err(|error| ForecastError::io(&path, error))?;
+        let overrides = match ConfigFormat::from_path(&path).parse(&content)? {
+            serde_json::Value::Object(overrides) => overrides,
+            serde_json::Value::Null => return Ok(self),
+            _ => return Err(ForecastError::InvalidOverrides(path)),
+        };
+        let mut config = serde_json::to_value(&self)?;
+        config
+            .as_object_mut()
+            .expect("config is a map")
+            .extend(overrides);
+        let (config, unknown) = unknown_keys::deserialize(config)?;
+        report_unknown_keys(unknown, strict)?;
+        Ok(config)
+    }
+
+    /// Parses the content of a config file and returns the keys that are ignored because the
+    /// config does not know them.
+    pub fn parse(
+        content: &str,
+        format: ConfigFormat,
+    ) -> Result<(Self, Vec<UnknownKey>), ForecastError> {
+        let mut value: serde_json::Value = format.parse(content)?;
+        if migration::migrate(&mut value)?.is_some() {
+            return Ok(unknown_keys::deserialize(value)?);
+        }
+
+        Ok(match format {
+            #[cfg(feature = "yaml")]
+            ConfigFormat::Yaml => {
+                unknown_keys::deserialize(serde_yaml::Deserializer::from_str(content))?
+            }
+            #[cfg(feature = "toml")]
+            ConfigFormat::Toml => unknown_keys::deserialize(toml::Deserializer::parse(content)?)?,
+            ConfigFormat::Json => {
+                let mut deserializer = serde_json::Deserializer::from_str(content);
+                let parsed = unknown_keys::deserialize(&mut deserializer)?;
+                deserializer.end()?;
+                parsed
+            }
+            #[allow(unreachable_patterns)]
+            format => return Err(format.unavailable()),
+        })
+    }
+
+    /// Upgrades a config file to the current version in place, the original file is kept with a
+    /// `.bak` extension. Returns the version the file had, `None` if it is up to date.
+    #[cfg(feature = "fs")]
+    pub fn migrate_file(path: &Path, format: ConfigFormat) -> Result<Option<u32>, ForecastError> {
+        let content =
+            std::fs::read_to_string(path).map_err(|error| ForecastError::io(path, error))?;
+        let mut value: serde_json::Value = format.parse(&content)?;
+        let Some(version) = migration::migrate(&mut value)? else {
+            return Ok(None);
+        };
+        // only replace the file if the migrated config can be loaded
+        unknown_keys::deserialize(value.clone())?;
+
+        let mut backup = path.as_os_str().to_owned();
+        backup.push(".bak");
+        std::fs::copy(path, &backup).map_err(|error| ForecastError::io(path, error))?;
+        std::fs::write(path, format.serialize(&value)?)
+            .map_err(|error| ForecastError::io(path, error))?;
+        Ok(Some(version))
+    }
+
+    /// Checks the content of a config file and collects every problem instead of stopping at the
+    /// first one.
+    pub fn validate(content: &str, format: ConfigFormat) -> Vec<ConfigIssue> {
+        let mut value: serde_json::Value = match format.parse(content) {
+            Ok(value) => value,
+            Err(error) => return vec![ConfigIssue::from_error(&error, content)],
+        };
+        if let Err(error) = migration::migrate(&mut value) {
+            return vec![ConfigIssue::new(
+                format.find_line(content, "", "version"),
+                error.to_string(),
+            )];
+        }
+
+        let mut issues = Vec::new();
+
+        if let Some(probabilities) = value.get("probabilities").and_then(|v| v.as_object()) {
+            let mut sum = 0.0;
+            for (name, probability) in probabilities {
+                let line = format.find_line(content, "probabilities", name);
+                if serde_json::from_value::<WeatherOptions>(name.as_str().into()).is_err() {
+                    issues.push(ConfigIssue::new(
+                        line,
+                        format!("unknown weather option '{name}'"),
+                    ));
+                }
+                match probability.as_f64() {
+                    Some(probability) if probability.is_finite() && probability >= 0.0 => {
+                        sum += probability;
+                    }
+                    Some(probability) => issues.push(ConfigIssue::new(
+                        line,
+                        format!(
+                            "probability of '{name}' must be a positive number, got {probability}"
+                        ),
+                    )),
+                    None => issues.push(ConfigIssue::new(
+                        line,
+                        format!("probability of '{name}' is not a number"),
+                    )),
+                }
+            }
+            if sum > 1.0 {
+                issues.push(ConfigIssue::new(
+                    format.find_line(content, "", "probabilities"),
+                    format!(
+                        "probabilities accumulate to {}% which is more than 100%",
+                        sum * 100.0
+                    ),
+                ));
+            }
+        }
+
+        let max_weather_slots = value
+            .get("max_weather_slots")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(DEFAULT_MAX_WEATHER_SLOTS as u64);
+        if max_weather_slots == 0 {
+            issues.push(ConfigIssue::new(
+                format.find_line(content, "", "max_weather_slots"),
+                "max weather slots must be at least 1".to_string(),
+            ));
+        }
+        if let Some(weather_slots) = value.get("weather_slots").and_then(|v| v.as_object()) {
+            for (name, slots) in weather_slots {
+                let line = format.find_line(content, "weather_slots", name);
+                if serde_json::from_value::<Sessions>(name.as_str().into()).is_err() {
+                    issues.push(ConfigIssue::new(line, format!("unknown session '{name}'")));
+                }
+                match slots.as_u64() {
+                    Some(slots) if (1..=max_weather_slots).contains(&slots) => {}
+                    _ => issues.push(ConfigIssue::new(
+                        line,
+                        format!(
+                            "weather slots of '{name}' must be between 1 and {max_weather_slots}, got {}",
+                            value_name(slots)
+                        ),
+                    )),
+                }
+            }
+        }
+
+        // catch everything the checks above do not know about (e.g. missing fields)
+        if issues.is_empty() {
+            match unknown_keys::deserialize(value) {
+                Ok((_, unknown)) => issues.extend(unknown.into_iter().map(|key| {
+                    let line = match key.path.as_slice() {
+                        [key] => format.find_line(content, "", key),
+                        [section, key] => format.find_line(content, section, key),
+                        _ => None,
+                    };
+                    ConfigIssue::new(line, key.to_string())
+                })),
+                Err(error) => issues.push(ConfigIssue::new(None, error.to_string())),
+            }
+        }
+
+        issues
+    }
+
+    /// JSON Schema of config files, YAML and TOML files have the same structure.
+    pub fn json_schema() -> serde_json::Value {
+        serde_json::to_value(schemars::schema_for!(Config)).expect("schemas are valid JSON")
+    }
+
+    /// Returns the config with the overrides of the given profile applied.
+    pub fn with_profile(mut self, name: &str) -> Result<Self, ForecastError> {
+        let Some(profile) = self.profiles.shift_remove(name) else {
+            return Err(ForecastError::UnknownProfile {
+                name: name.to_string(),
+                available: self.profiles.keys().cloned().collect(),
+            });
+        };
+        let serde_json::Value::Object(overrides) = profile else {
+            return Err(ForecastError::InvalidProfile(name.to_string()));
+        };
+
+        let mut config = serde_json::to_value(&self)?;
+        let base = config.as_object_mut().expect("config is a map");
+        base.remove("profiles");
+        base.extend(overrides);
+        Ok(serde_json::from_value(config)?)
+    }
+
+    /// Season of the event, either set explicitly or derived from the month.
+    pub fn get_season(&self) -> Option<Season> {
+        self.season.or(self.month.map(|month| month.season()))
+    }
+
+    #[cfg(feature = "fs")]
+    pub fn generate_default_config(path: &Path, format: ConfigFormat) -> Result<(), ForecastError> {
+        let content = format.serialize(&Config::default())?;
+        std::fs::write(path, content).map_err(|error| ForecastError::io(path, error))?;
         Ok(())
     }
 }
+
+/// Ignores the unknown keys with a warning or rejects them if `strict`.
+pub(crate) fn report_unknown_keys(
+    unknown: Vec<UnknownKey>,
+    strict: bool,
+) -> Result<(), ForecastError> {
+    if strict && !unknown.is_empty() {
+        return Err(ForecastError::UnknownKeys(unknown));
+    }
+    for key in unknown {
+        tracing::warn!("Ignoring {key}");
+    }
+    Ok(())
+}
+
+/// A single problem found by [`Config::validate`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigIssue {
+    /// 1-based line in the config file the issue refers to, if it could be determined
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl ConfigIssue {
+    fn new(line: Option<usize>, message: String) -> Self {
+        Self { line, message }
+    }
+
+    #[cfg_attr(not(feature = "toml"), allow(unused_variables))]
+    fn from_error(error: &ForecastError, content: &str) -> Self {
+        let line = match error {
+            #[cfg(feature = "yaml")]
+            ForecastError::Yaml(error) => error.location().map(|location| location.line()),
+            #[cfg(feature = "toml")]
+            ForecastError::TomlParse(error) => error
+                .span()
+                .map(|span| content[..span.start].lines().count().max(1)),
+            ForecastError::Json(error) => Some(error.line()),
+            _ => None,
+        };
+        Self::new(line, error.to_string())
+    }
+}
+
+impl std::fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {line}: {}", self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+fn value_name(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(string) => string.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn find_yaml_line(content: &str, section: &str, key: &str) -> Option<usize> {
+    let mut in_section = section.is_empty();
+    for (index, line) in content.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        let is_top_level = !line.starts_with(char::is_whitespace);
+        if is_top_level && !section.is_empty() {
+            in_section = is_key(trimmed, section, ':');
+            continue;
+        }
+        if in_section && is_top_level == section.is_empty() && is_key(trimmed, key, ':') {
+            return Some(index + 1);
+        }
+    }
+    None
+}
+
+fn find_toml_line(content: &str, section: &str, key: &str) -> Option<usize> {
+    let mut in_section = section.is_empty();
+    for (index, line) in content.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        if let Some(table) = trimmed
+            .strip_prefix('[')
+            .and_then(|table| table.strip_suffix(']'))
+        {
+            if section.is_empty() && table.trim() == key {
+                return Some(index + 1);
+            }
+            in_section = table.trim() == section;
+            continue;
+        }
+        if in_section && is_key(trimmed, key, '=') {
+            return Some(index + 1);
+        }
+    }
+    None
+}
+
+fn find_json_line(content: &str, section: &str, key: &str) -> Option<usize> {
+    let key_depth = if section.is_empty() { 1 } else { 2 };
+    let mut in_section = section.is_empty();
+    let mut depth = 0;
+    for (index, line) in content.lines().enumerate() {
+        let trimmed = line.trim();
+        if in_section && depth == key_depth && is_key(trimmed, key, ':') {
+            return Some(index + 1);
+        }
+        if depth == 1 && !section.is_empty() && trimmed.starts_with('"') {
+            in_section = is_key(trimmed, section, ':');
+        }
+
+        for character in trimmed.chars() {
+            match character {
+                '{' | '[' => depth += 1,
+                '}' | ']' => depth -= 1,
+                _ => {}
+            }
+        }
+    }
+    None
+}
+
+fn is_key(line: &str, key: &str, separator: char) -> bool {
+    let line = line.trim_start_matches(['"', '\'']);
+    line.strip_prefix(key).is_some_and(|rest| {
+        rest.trim_start_matches(['"', '\''])
+            .trim_start()
+            .starts_with(separator)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn default_config_is_valid() {
+        let yaml = serde_yaml::to_string(&Config::default()).unwrap();
+        assert_eq!(Config::validate(&yaml, ConfigFormat::Yaml), Vec::new());
+
+        let misspelled = format!("{yaml}emojis: true\n");
+        assert_eq!(
+            Config::validate(&misspelled, ConfigFormat::Yaml),
+            [ConfigIssue::new(
+                Some(yaml.lines().count() + 1),
+                "unknown key 'emojis', did you mean 'emoji'?".to_string()
+            )]
+        );
+        let (config, unknown) = Config::parse(&misspelled, ConfigFormat::Yaml).unwrap();
+        assert_eq!(config, Config::default());
+        assert_eq!(unknown.len(), 1);
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn all_issues_are_reported_with_lines() {
+        let yaml = "\
+probabilities:
+  Clear: 0.9
+  Rain: -0.5
+  Sunny: 0.5
+weather_slots:
+  Race: 9
+  Superpole: 1
+set_clipboard: false
+";
+        let lines: Vec<_> = Config::validate(yaml, ConfigFormat::Yaml)
+            .into_iter()
+            .map(|issue| issue.line)
+            .collect();
+        assert_eq!(lines, [Some(3), Some(4), Some(1), Some(6), Some(7)]);
+    }
+
+    #[test]
+    fn profile_overrides_top_level_keys() {
+        let config: Config = serde_json::from_value(serde_json::json!({
+            "probabilities": {"Clear": 1.0},
+            "weather_slots": {"Race": 4},
+            "set_clipboard": true,
+            "profiles": {"endurance": {"weather_slots": {"Race": 2}}},
+        }))
+        .unwrap();
+        let endurance = config.clone().with_profile("endurance").unwrap();
+        assert_eq!(endurance.weather_slots[&Sessions::Race], 2);
+        assert_eq!(endurance.probabilities, config.probabilities);
+        assert!(endurance.set_clipboard);
+        assert!(endurance.profiles.is_empty());
+
+        assert!(matches!(
+            config.with_profile("sprint"),
+            Err(ForecastError::UnknownProfile { .. })
+        ));
+    }
+
+    #[cfg(all(feature = "fs", feature = "yaml"))]
+    #[test]
+    fn track_overrides_replace_top_level_keys() {
+        let directory =
+            std::env::temp_dir().join(format!("weather_forecaster_{}", std::process::id()));
+        let overrides = directory.join(TRACK_OVERRIDES_DIRECTORY);
+        std::fs::create_dir_all(&overrides).unwrap();
+        std::fs::write(overrides.join("spa.yml"), "weather_slots:\n  Race: 2\n").unwrap();
+        let config_file = directory.join("config.yml");
+
+        let config = Config {
+            track: Some(TrackPreset::Spa),
+            set_clipboard: true,
+            ..Config::default()
+        };
+        let spa = config
+            .clone()
+            .with_track_overrides(&config_file, true)
+            .unwrap();
+        assert_eq!(spa.weather_slots, HashMap::from([(Sessions::Race, 2)]));
+        assert!(spa.set_clipboard);
+
+        let sakhir = Config {
+            track: Some(TrackPreset::Sakhir),
+            ..config
+        };
+        assert_eq!(
+            sakhir
+                .clone()
+                .with_track_overrides(&config_file, true)
+                .unwrap(),
+            sakhir
+        );
+
+        std::fs::write(overrides.join("spa.yml"), "rain_dance: true\n").unwrap();
+        let strict = Config {
+            track: Some(TrackPreset::Spa),
+            ..Config::default()
+        }
+        .with_track_overrides(&config_file, true);
+        assert!(matches!(strict, Err(ForecastError::UnknownKeys(_))));
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn toml_round_trip_and_validation() {
+        let toml = ConfigFormat::Toml.serialize(&Config::default()).unwrap();
+        let config: Config = ConfigFormat::Toml.parse(&toml).unwrap();
+        assert_eq!(config, Config::default());
+        assert_eq!(Config::validate(&toml, ConfigFormat::Toml), Vec::new());
+
+        let toml = "\
+set_clipboard = false
+
+[probabilities]
+Clear = 0.5
+Rain = -1.0
+
+[weather_slots]
+Race = 5
+";
+        let lines: Vec<_> = Config::validate(toml, ConfigFormat::Toml)
+            .into_iter()
+            .map(|issue| issue.line)
+            .collect();
+        assert_eq!(lines, [Some(5), Some(8)]);
+    }
+
+    #[test]
+    fn json_validation_reports_lines() {
+        let json = ConfigFormat::Json.serialize(&Config::default()).unwrap();
+        assert_eq!(Config::validate(&json, ConfigFormat::Json), Vec::new());
+
+        let json = r#"{
+  "probabilities": {
+    "Clear": 0.5,
+    "Rain": -1.0
+  },
+  "weather_slots": {
+    "Race": 5
+  }
+}"#;
+        let lines: Vec<_> = Config::validate(json, ConfigFormat::Json)
+            .into_iter()
+            .map(|issue| issue.line)
+            .collect();
+        assert_eq!(lines, [Some(4), Some(7)]);
+    }
+
+    #[test]
+    fn schema_describes_every_key() {
+        let schema = Config::json_schema();
+        let properties = schema["properties"].as_object().unwrap();
+        let config = serde_json::to_value(Config::default()).unwrap();
+        for key in config.as_object().unwrap().keys() {
+            assert!(properties.contains_key(key), "{key} is missing");
+        }
+        assert_eq!(
+            schema["required"],
+            serde_json::json!(["weather_slots", "set_clipboard"])
+        );
+        assert_eq!(
+            schema["$defs"]["TimeOfDay"]["type"],
+            serde_json::Value::from("string")
+        );
+    }
+}
@@ -0,0 +1,275 @@
+use std::{collections::HashMap, fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    catalog::{CatalogEntry, WeatherCatalog},
+    error::ForecastError,
+    forecaster::Sessions,
+};
+
+/// Requirements a forecast has to fulfill, checked by generating forecasts until one fits.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(default)]
+pub struct Constraints {
+    /// Conditions every forecast has to fulfill, e.g. `wet_race`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub require: Vec<Constraint>,
+    /// Conditions no forecast may fulfill, e.g. `thunderstorm_in_quali`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub forbid: Vec<Constraint>,
+    /// Weather that has to appear at least once, e.g. `fog`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub at_least_one: Vec<Constraint>,
+    /// Rain intensity may only change by one step between consecutive slots of a session, e.g.
+    /// from light rain to rain but not from clear to a thunderstorm
+    pub smooth_rain: bool,
+    /// Number of forecasts to generate before giving up
+    pub max_attempts: usize,
+}
+
+impl Default for Constraints {
+    fn default() -> Self {
+        Self {
+            require: Vec::new(),
+            forbid: Vec::new(),
+            at_least_one: Vec::new(),
+            smooth_rain: false,
+            max_attempts: 1000,
+        }
+    }
+}
+
+impl Constraints {
+    pub fn is_empty(&self) -> bool {
+        self.require.is_empty()
+            && self.forbid.is_empty()
+            && self.at_least_one.is_empty()
+            && !self.smooth_rain
+    }
+
+    /// Makes sure all referenced weather exists in the catalog.
+    pub fn validate(&self, catalog: &WeatherCatalog) -> Result<(), ForecastError> {
+        for constraint in self
+            .require
+            .iter()
+            .chain(&self.forbid)
+            .chain(&self.at_least_one)
+        {
+            if let Target::Weather(name) = &constraint.target
+                && !catalog.entries().iter().any(|entry| matches(entry, name))
+            {
+                return Err(ForecastError::UnknownWeather(constraint.to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Descriptions of all constraints the slots violate.
+    pub(crate) fn violations(&self, slots: &HashMap<Sessions, Vec<&CatalogEntry>>) -> Vec<String> {
+        let required = self
+            .require
+            .iter()
+            .map(|constraint| ("require", constraint, true));
+        let forbidden = self
+            .forbid
+            .iter()
+            .map(|constraint| ("forbid", constraint, false));
+        let at_least_one = self
+            .at_least_one
+            .iter()
+            .map(|constraint| ("at_least_one", constraint, true));
+
+        let mut violations: Vec<String> = required
+            .chain(forbidden)
+            .chain(at_least_one)
+            .filter(|(_, constraint, expected)| constraint.holds(slots) != *expected)
+            .map(|(kind, constraint, _)| format!("{kind} {constraint}"))
+            .collect();
+        if self.smooth_rain && !is_smooth(slots) {
+            violations.push("smooth_rain".to_string());
+        }
+        violations
+    }
+}
+
+/// Whether the rain intensity changes by at most one step between consecutive slots.
+fn is_smooth(slots: &HashMap<Sessions, Vec<&CatalogEntry>>) -> bool {
+    slots.values().all(|entries| {
+        entries
+            .windows(2)
+            .all(|pair| pair[0].rain_intensity.abs_diff(pair[1].rain_intensity) <= 1)
+    })
+}
+
+/// A condition on the weather of a forecast, written as `<weather>`, `<weather>_<session>` or
+/// `<weather>_in_<session>`. The weather is `wet`, `dry` or the name of a weather option or group.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(try_from = "String", into = "String")]
+pub struct Constraint {
+    source: String,
+    target: Target,
+    session: Option<Sessions>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Target {
+    Wet,
+    Dry,
+    Weather(String),
+}
+
+impl Constraint {
+    fn holds(&self, slots: &HashMap<Sessions, Vec<&CatalogEntry>>) -> bool {
+        let mut entries = slots
+            .iter()
+            .filter(|(session, _)| self.session.is_none_or(|expected| expected == **session))
+            .flat_map(|(_, entries)| entries);
+
+        match &self.target {
+            Target::Wet => entries.any(|entry| entry.rain_intensity > 0),
+            Target::Dry => entries.all(|entry| entry.rain_intensity == 0),
+            Target::Weather(name) => entries.any(|entry| matches(entry, name)),
+        }
+    }
+}
+
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|char| char.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn matches(entry: &CatalogEntry, name: &str) -> bool {
+    [
+        normalize(&entry.name),
+        normalize(&format!("{:?}", entry.option)),
+        normalize(&entry.group),
+    ]
+    .contains(&name.to_string())
+}
+
+impl FromStr for Constraint {
+    type Err = ForecastError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        const SESSIONS: &[(&str, Sessions)] = &[
+            ("fp1", Sessions::Fp1),
+            ("fp2", Sessions::Fp2),
+            ("fp3", Sessions::Fp3),
+            ("practice", Sessions::Practice),
+            ("qualifying", Sessions::Qualifying),
+            ("qualify", Sessions::Qualifying),
+            ("quali", Sessions::Qualifying),
+            ("sprint", Sessions::Sprint),
+            ("warmup", Sessions::Warmup),
+            ("race", Sessions::Race),
+        ];
+
+        let lowercase = s.trim().to_lowercase();
+        let (weather, session) = SESSIONS
+            .iter()
+            .find_map(|(name, session)| {
+                let weather = lowercase.strip_suffix(name)?.strip_suffix('_')?;
+                Some((
+                    weather.strip_suffix("_in").unwrap_or(weather),
+                    Some(*session),
+                ))
+            })
+            .unwrap_or((&lowercase, None));
+
+        let target = match normalize(weather).as_str() {
+            "" => return Err(ForecastError::InvalidConstraint(s.to_string())),
+            "wet" => Target::Wet,
+            "dry" => Target::Dry,
+            name => Target::Weather(name.to_string()),
+        };
+        Ok(Self {
+            source: s.trim().to_string(),
+            target,
+            session,
+        })
+    }
+}
+
+impl TryFrom<String> for Constraint {
+    type Error = ForecastError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Constraint> for String {
+    fn from(value: Constraint) -> Self {
+        value.source
+    }
+}
+
+impl Display for Constraint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{forecaster::WeatherOptions, groups::WeatherGroups};
+
+    #[test]
+    fn parse_constraints() {
+        let constraint: Constraint = "wet_race".parse().unwrap();
+        assert_eq!(constraint.target, Target::Wet);
+        assert_eq!(constraint.session, Some(Sessions::Race));
+
+        let constraint: Constraint = "thunderstorm_in_quali".parse().unwrap();
+        assert_eq!(
+            constraint.target,
+            Target::Weather("thunderstorm".to_string())
+        );
+        assert_eq!(constraint.session, Some(Sessions::Qualifying));
+
+        let constraint: Constraint = "HeavyFog".parse().unwrap();
+        assert_eq!(constraint.target, Target::Weather("heavyfog".to_string()));
+        assert_eq!(constraint.session, None);
+
+        assert!("_race".parse::<Constraint>().is_err());
+    }
+
+    #[test]
+    fn smooth_rain_limits_jumps_between_slots() {
+        let catalog = WeatherCatalog::built_in(&WeatherGroups::default());
+        let entry = |option| &catalog[catalog.position(option).unwrap()];
+        let constraints = Constraints {
+            smooth_rain: true,
+            ..Constraints::default()
+        };
+        assert!(!constraints.is_empty());
+
+        let slots = [(
+            Sessions::Race,
+            vec![
+                entry(WeatherOptions::Clear),
+                entry(WeatherOptions::LightRain),
+                entry(WeatherOptions::Rain),
+                entry(WeatherOptions::Storm),
+            ],
+        )]
+        .into_iter()
+        .collect();
+        assert!(constraints.violations(&slots).is_empty());
+
+        let slots = [(
+            Sessions::Race,
+            vec![
+                entry(WeatherOptions::Clear),
+                entry(WeatherOptions::Thunderstorm),
+            ],
+        )]
+        .into_iter()
+        .collect();
+        assert_eq!(constraints.violations(&slots), ["smooth_rain"]);
+    }
+}
@@ -0,0 +1,133 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{error::ForecastError, forecaster::Sessions};
+
+/// How the rain of the sessions depends on a leading session, by default the race.
+///
+/// Following sessions may only be wet if the leader is wet, previewing sessions additionally get
+/// a slot with rain like the heaviest rain of the leader, so a wet race can be anticipated.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(default)]
+pub struct CouplingConfig {
+    /// Generate every session independently when disabled
+    pub enabled: bool,
+    /// Session the others follow
+    pub leader: Sessions,
+    /// Sessions that may only be wet if the leader is, defaults to all other sessions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub followers: Option<Vec<Sessions>>,
+    /// Sessions that preview the rain of the leader, they are followers as well
+    pub previews: Vec<Sessions>,
+    /// Chance that a session is coupled to the leader at all
+    pub strength: f64,
+    /// Whether previews get rain of the same group as the leader or the very same weather
+    pub preview_match: PreviewMatch,
+}
+
+impl Default for CouplingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            leader: Sessions::Race,
+            followers: None,
+            previews: vec![Sessions::Practice],
+            strength: 1.0,
+            preview_match: PreviewMatch::Group,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PreviewMatch {
+    Group,
+    Weather,
+}
+
+/// Part a session plays in the coupling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Leader,
+    Preview,
+    Follower,
+    Independent,
+}
+
+impl CouplingConfig {
+    pub fn validate(&self) -> Result<(), ForecastError> {
+        if !self.strength.is_finite() || !(0.0..=1.0).contains(&self.strength) {
+            return Err(ForecastError::InvalidProbability {
+                option: "coupling strength".to_string(),
+                value: self.strength,
+            });
+        }
+        let followers = self.followers.iter().flatten();
+        if followers
+            .chain(&self.previews)
+            .any(|session| *session == self.leader)
+        {
+            return Err(ForecastError::InvalidCoupling(format!(
+                "the leader {:?} cannot follow itself",
+                self.leader
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn role(&self, session: Sessions) -> Role {
+        if !self.enabled {
+            Role::Independent
+        } else if session == self.leader {
+            Role::Leader
+        } else if self.previews.contains(&session) {
+            Role::Preview
+        } else if self
+            .followers
+            .as_ref()
+            .is_none_or(|followers| followers.contains(&session))
+        {
+            Role::Follower
+        } else {
+            Role::Independent
+        }
+    }
+
+    /// Whether a wet leader forces and a dry leader forbids rain in the other sessions.
+    pub fn is_strict(&self) -> bool {
+        self.enabled && self.strength >= 1.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roles_of_the_sessions() {
+        let coupling = CouplingConfig::default();
+        assert_eq!(coupling.role(Sessions::Race), Role::Leader);
+        assert_eq!(coupling.role(Sessions::Practice), Role::Preview);
+        assert_eq!(coupling.role(Sessions::Qualifying), Role::Follower);
+
+        let coupling = CouplingConfig {
+            leader: Sessions::Qualifying,
+            followers: Some(vec![Sessions::Race]),
+            previews: Vec::new(),
+            ..CouplingConfig::default()
+        };
+        assert_eq!(coupling.role(Sessions::Race), Role::Follower);
+        assert_eq!(coupling.role(Sessions::Practice), Role::Independent);
+
+        let coupling = CouplingConfig {
+            enabled: false,
+            ..CouplingConfig::default()
+        };
+        assert_eq!(coupling.role(Sessions::Race), Role::Independent);
+
+        let coupling = CouplingConfig {
+            previews: vec![Sessions::Race],
+            ..CouplingConfig::default()
+        };
+        assert!(coupling.validate().is_err());
+    }
+}
@@ -0,0 +1,134 @@
+use serde::Serialize;
+
+#[cfg(feature = "discord")]
+use crate::error::ForecastError;
+use crate::{forecaster::WeatherForecast, template::TemplateData};
+
+/// Color of the embed if any session is wet, a dry forecast gets [`DRY_COLOR`].
+const WET_COLOR: u32 = 0x3b82f6;
+const DRY_COLOR: u32 = 0xf59e0b;
+
+/// Message posted to a Discord webhook.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WebhookMessage {
+    pub embeds: Vec<Embed>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Embed {
+    pub title: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    pub color: u32,
+    /// One field per session
+    pub fields: Vec<EmbedField>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub footer: Option<EmbedFooter>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EmbedField {
+    pub name: String,
+    pub value: String,
+    pub inline: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EmbedFooter {
+    pub text: String,
+}
+
+impl WebhookMessage {
+    /// An embed listing the slots of every session with their weather emoji below a summary, the
+    /// seed is put in the footer so the forecast can be reproduced.
+    pub fn new(forecast: &WeatherForecast, seed: Option<u64>) -> Self {
+        let data = TemplateData::new(forecast, seed);
+        let fields = data
+            .sessions
+            .iter()
+            .map(|session| {
+                let slots: Vec<String> = session
+                    .slots
+                    .iter()
+                    .map(|slot| {
+                        let mut line = format!("{} {}", slot.emoji, slot.weather);
+                        if let Some(temperature) = slot.ambient_temperature {
+                            line.push_str(&format!(" · {temperature}°C"));
+                        }
+                        line
+                    })
+                    .collect();
+                EmbedField {
+                    name: session.name.clone(),
+                    value: slots.join("\n"),
+                    inline: true,
+                }
+            })
+            .collect();
+        let wet = data.sessions.iter().any(|session| session.wet);
+
+        Self {
+            embeds: vec![Embed {
+                title: "Weather forecast".to_string(),
+                description: data.summary,
+                color: if wet { WET_COLOR } else { DRY_COLOR },
+                fields,
+                footer: data.seed.map(|seed| EmbedFooter {
+                    text: format!("Seed: {seed}"),
+                }),
+            }],
+        }
+    }
+}
+
+/// Posts the forecast to the webhook.
+#[cfg(feature = "discord")]
+pub fn publish(
+    webhook: &str,
+    forecast: &WeatherForecast,
+    seed: Option<u64>,
+) -> Result<(), ForecastError> {
+    let body = serde_json::to_string(&WebhookMessage::new(forecast, seed))?;
+    ureq::post(webhook)
+        .header("Content-Type", "application/json")
+        .send(body)
+        .map_err(|error| ForecastError::Webhook(error.to_string()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forecaster::{Sessions, WeatherOptions};
+
+    #[test]
+    fn sessions_become_fields() {
+        let mut forecast = WeatherForecast::default();
+        forecast.forecast.insert(
+            Sessions::Race,
+            vec![WeatherOptions::Clear, WeatherOptions::Rain],
+        );
+        forecast
+            .forecast
+            .insert(Sessions::Qualifying, vec![WeatherOptions::Overcast]);
+
+        let message = WebhookMessage::new(&forecast, Some(7));
+        let embed = &message.embeds[0];
+        assert_eq!(embed.color, WET_COLOR);
+        assert_eq!(embed.fields.len(), 2);
+        assert_eq!(embed.fields[0].name, "Qualify");
+        assert_eq!(embed.fields[1].value, "☀️ Clear\n🌧️ Rain");
+        assert_eq!(
+            embed.description,
+            "Expect a dry qualifying; rain arriving mid-race."
+        );
+
+        let json = serde_json::to_value(&message).unwrap();
+        assert_eq!(json["embeds"][0]["footer"]["text"], "Seed: 7");
+
+        forecast.forecast.remove(&Sessions::Race);
+        let message = WebhookMessage::new(&forecast, None);
+        assert_eq!(message.embeds[0].color, DRY_COLOR);
+        assert_eq!(message.embeds[0].footer, None);
+    }
+}
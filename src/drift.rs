@@ -0,0 +1,142 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    catalog::{CatalogEntry, WeatherCatalog},
+    error::ForecastError,
+};
+
+/// Drift model for consecutive weather slots of a session.
+///
+/// The first slot is picked from the base probabilities, every following slot favours weather
+/// close to where the previous slots were heading, e.g. clouds thickening into rain keep getting
+/// wetter.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct DriftConfig {
+    /// How strongly the weather keeps its course from `0.0` (independent slots) to `1.0` (only
+    /// gradual changes)
+    #[serde(default = "default_momentum")]
+    pub momentum: f64,
+}
+
+fn default_momentum() -> f64 {
+    0.5
+}
+
+impl Default for DriftConfig {
+    fn default() -> Self {
+        Self {
+            momentum: default_momentum(),
+        }
+    }
+}
+
+impl DriftConfig {
+    pub fn validate(&self) -> Result<(), ForecastError> {
+        if !self.momentum.is_finite() || !(0.0..=1.0).contains(&self.momentum) {
+            return Err(ForecastError::InvalidProbability {
+                option: "drift momentum".to_string(),
+                value: self.momentum,
+            });
+        }
+        Ok(())
+    }
+
+    /// State of a new session without any slots yet.
+    pub fn start(&self) -> Drift {
+        Drift {
+            momentum: self.momentum,
+            previous: None,
+            trend: 0.0,
+        }
+    }
+}
+
+/// Course of the weather through the slots of a session.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Drift {
+    momentum: f64,
+    /// Wetness of the previous slot, `None` before the first slot
+    previous: Option<f64>,
+    /// Change of the wetness from the slot before the previous one to the previous one
+    trend: f64,
+}
+
+impl Drift {
+    /// Weighs the probabilities of the catalog entries by how close they are to where the
+    /// weather is heading. The probabilities are unchanged for the first slot.
+    pub fn condition(&self, catalog: &WeatherCatalog, probabilities: &[f64]) -> Vec<f64> {
+        let Some(previous) = self.previous else {
+            return probabilities.to_vec();
+        };
+        let target = previous + self.momentum * self.trend;
+        let mut conditioned: Vec<_> = catalog
+            .entries()
+            .iter()
+            .zip(probabilities)
+            .map(|(entry, probability)| {
+                let distance = wetness(entry) - target;
+                let closeness = 1.0 / (1.0 + 4.0 * distance * distance);
+                probability * (1.0 - self.momentum + self.momentum * closeness)
+            })
+            .collect();
+        let sum: f64 = conditioned.iter().sum();
+        if sum > 0.0 {
+            for probability in &mut conditioned {
+                *probability /= sum;
+            }
+        }
+        conditioned
+    }
+
+    /// Moves the state on to the weather picked for the next slot.
+    pub fn advance(&mut self, entry: &CatalogEntry) {
+        let wetness = wetness(entry);
+        self.trend = self.previous.map_or(0.0, |previous| wetness - previous);
+        self.previous = Some(wetness);
+    }
+}
+
+/// Position of the entry on a scale from clear sky (`0.0`) over clouds to heavy rain.
+fn wetness(entry: &CatalogEntry) -> f64 {
+    entry.rain_intensity as f64 + 1.0 - entry.option.sunshine()
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::assert_approx_eq;
+
+    use super::*;
+    use crate::{forecaster::WeatherOptions, groups::WeatherGroups};
+
+    #[test]
+    fn weather_keeps_its_course() {
+        let catalog = WeatherCatalog::built_in(&WeatherGroups::default());
+        let entry = |option| &catalog[catalog.position(option).unwrap()];
+        let uniform = vec![1.0 / catalog.len() as f64; catalog.len()];
+        let probability = |drift: &Drift, option| {
+            drift.condition(&catalog, &uniform)[catalog.position(option).unwrap()]
+        };
+
+        let mut drift = DriftConfig { momentum: 1.0 }.start();
+        assert_eq!(drift.condition(&catalog, &uniform), uniform);
+        drift.advance(entry(WeatherOptions::Overcast));
+        assert!(
+            probability(&drift, WeatherOptions::HeavyCloud)
+                > 5.0 * probability(&drift, WeatherOptions::Storm)
+        );
+
+        // thickening clouds rather turn into rain than clear up again
+        drift.advance(entry(WeatherOptions::LightRain));
+        assert!(
+            probability(&drift, WeatherOptions::Rain)
+                > probability(&drift, WeatherOptions::Overcast)
+        );
+
+        let mut independent = DriftConfig { momentum: 0.0 }.start();
+        independent.advance(entry(WeatherOptions::Clear));
+        for probability in independent.condition(&catalog, &uniform) {
+            assert_approx_eq!(f64, probability, uniform[0]);
+        }
+        assert!(DriftConfig { momentum: 1.5 }.validate().is_err());
+    }
+}
@@ -0,0 +1,115 @@
+use std::ops::Range;
+
+use serde::Serialize;
+
+use crate::{catalog::WeatherCatalog, forecaster::WeatherOptions, time_of_day::TimeOfDay};
+
+/// Hour by hour weather of an endurance race, e.g. over 6, 12 or 24 hours.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct HourlyForecast {
+    pub hours: Vec<HourlyWeather>,
+    /// Catalog entries of the hours
+    #[serde(skip)]
+    pub(crate) entries: Vec<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct HourlyWeather {
+    /// Local time at the start of the hour
+    pub time: TimeOfDay,
+    pub weather: WeatherOptions,
+    /// Ambient temperature in °C
+    pub ambient_temperature: f64,
+    /// Track temperature in °C
+    pub track_temperature: f64,
+}
+
+impl HourlyForecast {
+    /// Hours of at most `slots` weather slots covering equally many hours.
+    pub fn slot_hours(&self, slots: usize) -> Vec<Range<usize>> {
+        let count = self.entries.len();
+        let slots = slots.clamp(1, count.max(1));
+        (0..slots)
+            .map(|slot| slot * count / slots..(slot + 1) * count / slots)
+            .filter(|hours| !hours.is_empty())
+            .collect()
+    }
+
+    /// Compresses the hours into the weather slots of [`Self::slot_hours`]. Each slot gets the
+    /// weather that lasts the longest in its hours, the heavier rain wins a tie.
+    pub(crate) fn compress(&self, catalog: &WeatherCatalog, slots: usize) -> Vec<usize> {
+        self.slot_hours(slots)
+            .into_iter()
+            .filter_map(|hours| {
+                let hours = &self.entries[hours];
+                hours.iter().copied().max_by_key(|entry| {
+                    let duration = hours.iter().filter(|other| *other == entry).count();
+                    // earlier weather wins a complete tie
+                    let first =
+                        hours.len() - hours.iter().position(|other| other == entry).unwrap();
+                    (duration, catalog[*entry].rain_intensity, first)
+                })
+            })
+            .collect()
+    }
+}
+
+impl std::fmt::Display for HourlyForecast {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (index, hour) in self.hours.iter().enumerate() {
+            writeln!(
+                f,
+                "Hour {:>2} ({}): {:<18}{:.1}°C air, {:.1}°C track",
+                index + 1,
+                hour.time,
+                hour.weather.to_string(),
+                hour.ambient_temperature,
+                hour.track_temperature,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::groups::WeatherGroups;
+
+    #[test]
+    fn hours_are_compressed_into_slots() {
+        let catalog = WeatherCatalog::built_in(&WeatherGroups::default());
+        let position = |option| catalog.position(option).unwrap();
+        let hours = [
+            WeatherOptions::Clear,
+            WeatherOptions::Clear,
+            WeatherOptions::LightCloud,
+            WeatherOptions::Overcast,
+            WeatherOptions::LightRain,
+            WeatherOptions::Rain,
+        ];
+        let forecast = HourlyForecast {
+            hours: Vec::new(),
+            entries: hours.into_iter().map(position).collect(),
+        };
+        let compress = |slots| -> Vec<WeatherOptions> {
+            forecast
+                .compress(&catalog, slots)
+                .into_iter()
+                .map(|entry| catalog[entry].option)
+                .collect()
+        };
+
+        assert_eq!(
+            compress(3),
+            [
+                WeatherOptions::Clear,
+                WeatherOptions::LightCloud,
+                WeatherOptions::Rain
+            ]
+        );
+        assert_eq!(compress(2), [WeatherOptions::Clear, WeatherOptions::Rain]);
+        assert_eq!(compress(10), hours);
+        assert!(HourlyForecast::default().compress(&catalog, 4).is_empty());
+    }
+}
@@ -0,0 +1,172 @@
+use serde_json::{Map, Value};
+use strum::IntoEnumIterator;
+
+use crate::{
+    config::{Config, report_unknown_keys},
+    error::ForecastError,
+    forecaster::{Sessions, WeatherOptions},
+    unknown_keys::{UnknownKey, known_keys},
+};
+
+/// Prefix of the environment variables overriding config keys.
+pub const ENV_PREFIX: &str = "WF_";
+
+/// Maps of the config whose keys are names, e.g. of weather groups, rather than sessions or
+/// weather options.
+const NAMED_MAPS: &[&str] = &[
+    "group_probabilities",
+    "slot_durations",
+    "groups",
+    "profiles",
+];
+
+impl Config {
+    /// Applies the `WF_*` variables of `vars` on top of the config, e.g. `WF_SET_CLIPBOARD=false`
+    /// or `WF_PROBABILITIES_RAIN=0.2` for nested keys. Values are read like YAML, so lists can be
+    /// given as `[a, b]`, or like JSON without the `yaml` feature. Unknown variables are ignored
+    /// with a warning or rejected if `strict`.
+    pub fn with_env_overrides(
+        self,
+        vars: impl IntoIterator<Item = (String, String)>,
+        strict: bool,
+    ) -> Result<Self, ForecastError> {
+        let mut overrides = Vec::new();
+        let mut unknown = Vec::new();
+        for (name, value) in vars {
+            let Some(name) = name.strip_prefix(ENV_PREFIX) else {
+                continue;
+            };
+            let name = name.to_ascii_lowercase();
+            match resolve(&name) {
+                Some(path) => {
+                    #[cfg(feature = "yaml")]
+                    let parsed = serde_yaml::from_str(&value);
+                    #[cfg(not(feature = "yaml"))]
+                    let parsed = serde_json::from_str(&value);
+                    let value = parsed.unwrap_or(Value::String(value));
+                    overrides.push((path, value));
+                }
+                None => unknown.push(UnknownKey::new(vec![name])),
+            }
+        }
+        report_unknown_keys(unknown, strict)?;
+        if overrides.is_empty() {
+            return Ok(self);
+        }
+
+        let mut config = serde_json::to_value(&self)?;
+        for (path, value) in overrides {
+            tracing::debug!("Overriding {} from the environment", path.join("."));
+            set(&mut config, &path, value);
+        }
+        Ok(serde_json::from_value(config)?)
+    }
+}
+
+/// Splits the lowercase name of a variable into the keys of the config it overrides. Key names
+/// may contain underscores themselves, so the longest known key wins. Keys of maps are matched
+/// to the sessions and weather options unless the map is one of the [`NAMED_MAPS`].
+fn resolve(name: &str) -> Option<Vec<String>> {
+    let mut path: Vec<String> = Vec::new();
+    let mut rest = name;
+    while !rest.is_empty() {
+        let known = known_keys(&path);
+        if known.is_empty() {
+            // the rest is the key of a map, nested maps are not supported
+            if path.is_empty() {
+                return None;
+            }
+            let named = path
+                .last()
+                .is_some_and(|map| NAMED_MAPS.contains(&map.as_str()));
+            path.push(if named {
+                rest.to_string()
+            } else {
+                variant_name(rest)
+            });
+            return Some(path);
+        }
+        let key = known
+            .iter()
+            .filter(|key| {
+                rest.strip_prefix(**key)
+                    .is_some_and(|after| after.is_empty() || after.starts_with('_'))
+            })
+            .max_by_key(|key| key.len())?;
+        path.push(key.to_string());
+        rest = rest[key.len()..].trim_start_matches('_');
+    }
+    Some(path)
+}
+
+/// Name of the session or weather option `key` stands for, e.g. `LightRain` for `light_rain`.
+fn variant_name(key: &str) -> String {
+    let normalized: String = key.chars().filter(|char| *char != '_').collect();
+    Sessions::iter()
+        .map(|session| format!("{session:?}"))
+        .chain(WeatherOptions::iter().map(|option| format!("{option:?}")))
+        .find(|name| name.eq_ignore_ascii_case(&normalized))
+        .unwrap_or_else(|| key.to_string())
+}
+
+/// Sets the value at `path`, creating maps on the way, e.g. for config sections that are unset.
+fn set(config: &mut Value, path: &[String], value: Value) {
+    let Some((key, parents)) = path.split_last() else {
+        return;
+    };
+    let mut current = config;
+    for parent in parents {
+        if !current.is_object() {
+            *current = Value::Object(Map::new());
+        }
+        current = current
+            .as_object_mut()
+            .expect("parent is a map")
+            .entry(parent.clone())
+            .or_insert(Value::Null);
+    }
+    if !current.is_object() {
+        *current = Value::Object(Map::new());
+    }
+    current
+        .as_object_mut()
+        .expect("parent is a map")
+        .insert(key.clone(), value);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drift::DriftConfig;
+
+    #[test]
+    fn environment_overrides_nested_keys() {
+        let vars = [
+            ("WF_SET_CLIPBOARD", "true"),
+            ("WF_PROBABILITIES_LIGHT_RAIN", "0.2"),
+            ("WF_WEATHER_SLOTS_RACE", "2"),
+            ("WF_MAX_WEATHER_SLOTS", "6"),
+            ("WF_DRIFT_MOMENTUM", "0.8"),
+            ("WF_GROUP_PROBABILITIES_STORM", "0.1"),
+            ("HOME", "/root"),
+        ]
+        .map(|(name, value)| (name.to_string(), value.to_string()));
+        let config = Config::default().with_env_overrides(vars, true).unwrap();
+        assert!(config.set_clipboard);
+        assert_eq!(config.probabilities[&WeatherOptions::LightRain], 0.2);
+        assert_eq!(config.weather_slots[&Sessions::Race], 2);
+        assert_eq!(config.max_weather_slots, Some(6));
+        assert_eq!(config.drift, Some(DriftConfig { momentum: 0.8 }));
+        assert_eq!(config.group_probabilities["storm"], 0.1);
+
+        let typo = [("WF_SET_CLIPBORD".to_string(), "true".to_string())];
+        assert!(matches!(
+            Config::default().with_env_overrides(typo.clone(), true),
+            Err(ForecastError::UnknownKeys(_))
+        ));
+        assert_eq!(
+            Config::default().with_env_overrides(typo, false).unwrap(),
+            Config::default()
+        );
+    }
+}
@@ -0,0 +1,222 @@
+use std::path::PathBuf;
+
+use crate::{
+    forecaster::{Sessions, WeatherOptions},
+    migration::CONFIG_VERSION,
+    plot::PlotSize,
+    unknown_keys::UnknownKey,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum ForecastError {
+    #[error("Failed to access '{}': {source}", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[cfg(feature = "yaml")]
+    #[error("Failed to parse config: {0}")]
+    Yaml(#[from] serde_yaml::Error),
+
+    #[cfg(feature = "toml")]
+    #[error("Failed to parse config: {0}")]
+    TomlParse(#[from] toml::de::Error),
+
+    #[cfg(feature = "toml")]
+    #[error("Failed to write config: {0}")]
+    TomlWrite(#[from] toml::ser::Error),
+
+    #[error("Failed to parse JSON: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("The `{feature}` feature is required for {what}")]
+    MissingFeature {
+        what: &'static str,
+        feature: &'static str,
+    },
+
+    #[cfg(feature = "sqlite")]
+    #[error("Database error: {0}")]
+    Database(#[from] rusqlite::Error),
+
+    #[cfg(feature = "ffi")]
+    #[error("Invalid string passed to the C interface: {0}")]
+    Ffi(String),
+
+    #[cfg(feature = "discord")]
+    #[error("Failed to post to the Discord webhook: {0}")]
+    Webhook(String),
+
+    #[cfg(feature = "mqtt")]
+    #[error("Failed to publish to the MQTT broker: {0}")]
+    Mqtt(String),
+
+    #[error("'{}' already exists, allow overwriting it or append to it instead", .0.display())]
+    OutputExists(PathBuf),
+
+    #[error("Failed to render template: {0}")]
+    Template(String),
+
+    #[error("Settings file does not contain the key '{0}'")]
+    MissingSettingsKey(String),
+
+    #[error("Invalid probability for {option}: {value}")]
+    InvalidProbability { option: String, value: f64 },
+
+    #[error("Invalid {name} temperature range: {min}°C to {max}°C")]
+    InvalidTemperatureRange {
+        name: &'static str,
+        min: f64,
+        max: f64,
+    },
+
+    #[error("Invalid {name} season modifier: {value}")]
+    InvalidSeasonModifier { name: &'static str, value: f64 },
+
+    #[error("Invalid transition weight from {from:?} to {to:?}: {value}")]
+    InvalidTransitionWeight {
+        from: WeatherOptions,
+        to: WeatherOptions,
+        value: f64,
+    },
+
+    #[error("Invalid coupling: {0}")]
+    InvalidCoupling(String),
+
+    #[error("Unknown session '{0}'")]
+    UnknownSession(String),
+
+    #[error("{session:?} is declared by multiple sessions: {}", names.join(", "))]
+    DuplicateSession {
+        session: Sessions,
+        names: Vec<String>,
+    },
+
+    #[error("Invalid maximum number of weather slots: {0}")]
+    InvalidMaxWeatherSlots(usize),
+
+    #[error("Unknown weather group '{0}'")]
+    UnknownGroup(String),
+
+    #[error("Invalid slot duration for group '{group}': {value}")]
+    InvalidSlotDuration { group: String, value: f64 },
+
+    #[error("{option:?} is part of multiple weather groups: {}", groups.join(", "))]
+    AmbiguousGroup {
+        option: WeatherOptions,
+        groups: Vec<String>,
+    },
+
+    #[error("Weather option '{0}' is defined multiple times")]
+    DuplicateWeather(String),
+
+    #[error("Custom weather option '{0}' needs a probability")]
+    MissingProbability(String),
+
+    #[error("Unknown profile '{name}', available profiles: {}", available.join(", "))]
+    UnknownProfile {
+        name: String,
+        available: Vec<String>,
+    },
+
+    #[error("Profile '{0}' has to be a map of config keys")]
+    InvalidProfile(String),
+
+    #[error("Track overrides '{}' have to be a map of config keys", .0.display())]
+    InvalidOverrides(PathBuf),
+
+    #[error("Invalid time of day '{0}', expected HH:MM")]
+    InvalidTimeOfDay(String),
+
+    #[error("Invalid altitude: {0} m")]
+    InvalidAltitude(f64),
+
+    #[error(
+        "Invalid plot size {}x{} at scale {}, charts need at least {}x{} and a positive scale",
+        .0.width, .0.height, .0.scale, PlotSize::MIN.0, PlotSize::MIN.1
+    )]
+    InvalidPlotSize(PlotSize),
+
+    #[error("Unknown weather '{0}'")]
+    UnknownWeather(String),
+
+    #[error("Invalid value for '{key}' in settings file: {value}")]
+    InvalidSettingsValue { key: String, value: String },
+
+    #[error("Could not reroll {0} to match the rain of the other sessions")]
+    RerollFailed(Sessions),
+
+    #[error(
+        "The rain chance of {session} cannot be capped at {}% without any dry weather",
+        max_rain_chance * 100.0
+    )]
+    RainChanceCapUnreachable {
+        session: Sessions,
+        max_rain_chance: f64,
+    },
+
+    #[error("{observed} observed categories but {expected} expected probabilities")]
+    MismatchedCategories { observed: usize, expected: usize },
+
+    #[error("{0} has no weather slots to export")]
+    EmptySession(Sessions),
+
+    #[error("Invalid date '{0}', expected YYYY-MM-DD")]
+    InvalidDate(String),
+
+    #[error("Invalid constraint '{0}', expected e.g. wet_race or thunderstorm_in_quali")]
+    InvalidConstraint(String),
+
+    #[error("Unknown circuit '{0}', the circuits command lists the known circuits")]
+    UnknownCircuit(String),
+
+    #[error("Invalid coordinates '{0}', expected <latitude>,<longitude> e.g. 50.437,5.971")]
+    InvalidCoordinates(String),
+
+    #[error("Invalid METAR report '{0}'")]
+    InvalidMetar(String),
+
+    #[error("Failed to get the live weather: {0}")]
+    LiveWeather(String),
+
+    #[error(
+        "No forecast satisfied the constraints within {attempts} attempts, violated: {}",
+        violations.join(", ")
+    )]
+    UnsatisfiableConstraints {
+        attempts: usize,
+        violations: Vec<String>,
+    },
+
+    #[error("No weather option has a probability greater than 0%")]
+    NoProbabilities,
+
+    #[error(
+        "{session:?} has to be dry because of its rain coupling to {leader:?}, but no dry weather \
+         option has a probability greater than 0%"
+    )]
+    NoDryWeather { session: Sessions, leader: Sessions },
+
+    #[error(
+        "Invalid config, {}",
+        .0.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ")
+    )]
+    UnknownKeys(Vec<UnknownKey>),
+
+    #[error(
+        "The config has version {0}, this version of the forecaster only supports up to version {CONFIG_VERSION}"
+    )]
+    UnsupportedConfigVersion(u32),
+}
+
+impl ForecastError {
+    #[cfg(feature = "fs")]
+    pub(crate) fn io(path: impl Into<PathBuf>, source: std::io::Error) -> Self {
+        Self::Io {
+            path: path.into(),
+            source,
+        }
+    }
+}
@@ -0,0 +1,21 @@
+use crate::forecaster::{Sessions, WeatherForecast};
+
+pub mod ac;
+pub mod acc;
+pub mod ams2;
+pub mod csv;
+pub mod iracing;
+
+/// Temperature used by games that need one if the forecast has no conditions, e.g. because it was
+/// restored.
+const DEFAULT_AMBIENT_TEMPERATURE: f64 = 22.0;
+
+/// Session a game with a single weather per event is based on, the race or else the last session.
+fn event_session(forecast: &WeatherForecast) -> Option<Sessions> {
+    let sessions = forecast.sessions();
+    if sessions.contains(&Sessions::Race) {
+        Some(Sessions::Race)
+    } else {
+        sessions.last().copied()
+    }
+}
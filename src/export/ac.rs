@@ -0,0 +1,174 @@
+use std::fmt::Write as _;
+
+use super::{DEFAULT_AMBIENT_TEMPERATURE, event_session};
+use crate::forecaster::{WeatherForecast, WeatherOptions};
+
+/// Road temperature above the ambient one used if the forecast has no conditions.
+const DEFAULT_ROAD_TEMPERATURE: f64 = 6.0;
+
+/// A `[WEATHER_x]` section of the `server_cfg.ini` of an Assetto Corsa dedicated server.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AcWeather {
+    /// Name of the weather's graphics, e.g. `3_clear`
+    pub graphics: &'static str,
+    /// Air temperature in °C
+    pub ambient: i64,
+    /// Temperature of the road relative to the air in °C
+    pub road: i64,
+    /// How much the air temperature varies in °C
+    pub variation_ambient: i64,
+    /// How much the road temperature varies in °C
+    pub variation_road: i64,
+}
+
+impl AcWeather {
+    /// One section per graphics of the race, or the last session of the forecast, with the
+    /// temperatures of its slots. The server picks one of the sections at random.
+    pub fn new(forecast: &WeatherForecast) -> Vec<Self> {
+        let Some(session) = event_session(forecast) else {
+            return Vec::new();
+        };
+        let options = &forecast.forecast[&session];
+        let conditions = forecast.conditions.get(&session);
+
+        let mut weathers: Vec<(&'static str, Vec<(f64, f64)>)> = Vec::new();
+        for (index, option) in options.iter().enumerate() {
+            let temperatures = conditions
+                .and_then(|conditions| conditions.get(index))
+                .map_or(
+                    (
+                        DEFAULT_AMBIENT_TEMPERATURE,
+                        DEFAULT_AMBIENT_TEMPERATURE + DEFAULT_ROAD_TEMPERATURE,
+                    ),
+                    |c| (c.ambient_temperature, c.track_temperature),
+                );
+            let graphics = graphics(*option);
+            match weathers.iter_mut().find(|(other, _)| *other == graphics) {
+                Some((_, slots)) => slots.push(temperatures),
+                None => weathers.push((graphics, vec![temperatures])),
+            }
+        }
+
+        weathers
+            .into_iter()
+            .map(|(graphics, temperatures)| {
+                let ambient: Vec<f64> = temperatures.iter().map(|(ambient, _)| *ambient).collect();
+                let road: Vec<f64> = temperatures
+                    .iter()
+                    .map(|(ambient, track)| track - ambient)
+                    .collect();
+                Self {
+                    graphics,
+                    ambient: mean(&ambient).round() as i64,
+                    road: mean(&road).round() as i64,
+                    variation_ambient: variation(&ambient),
+                    variation_road: variation(&road),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Renders the sections numbered from 0.
+pub fn server_cfg(forecast: &WeatherForecast) -> String {
+    let mut sections = String::new();
+    for (index, weather) in AcWeather::new(forecast).iter().enumerate() {
+        if index > 0 {
+            sections.push('\n');
+        }
+        let _ = writeln!(sections, "[WEATHER_{index}]");
+        let _ = writeln!(sections, "GRAPHICS={}", weather.graphics);
+        let _ = writeln!(sections, "BASE_TEMPERATURE_AMBIENT={}", weather.ambient);
+        let _ = writeln!(sections, "BASE_TEMPERATURE_ROAD={}", weather.road);
+        let _ = writeln!(sections, "VARIATION_AMBIENT={}", weather.variation_ambient);
+        let _ = writeln!(sections, "VARIATION_ROAD={}", weather.variation_road);
+    }
+    sections
+}
+
+/// The original game has no rain, wet weather gets its heaviest clouds or fog instead.
+fn graphics(option: WeatherOptions) -> &'static str {
+    match option {
+        WeatherOptions::Clear => "3_clear",
+        WeatherOptions::LightCloud | WeatherOptions::Hazy => "4_mid_clear",
+        WeatherOptions::MediumCloud | WeatherOptions::Random => "5_light_clouds",
+        WeatherOptions::HeavyCloud => "6_mid_clouds",
+        WeatherOptions::Overcast
+        | WeatherOptions::LightRain
+        | WeatherOptions::Rain
+        | WeatherOptions::Storm
+        | WeatherOptions::Thunderstorm => "7_heavy_clouds",
+        WeatherOptions::Foggy => "2_light_fog",
+        WeatherOptions::FogWithRain
+        | WeatherOptions::HeavyFog
+        | WeatherOptions::HeavyFogWithRain => "1_heavy_fog",
+    }
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+/// Half the range of the values, at least 1 °C.
+fn variation(values: &[f64]) -> i64 {
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    (((max - min) / 2.0).round() as i64).max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forecaster::{Sessions, SlotConditions};
+
+    #[test]
+    fn sections_per_graphics() {
+        let mut forecast = WeatherForecast::default();
+        forecast.forecast.insert(
+            Sessions::Race,
+            vec![
+                WeatherOptions::Clear,
+                WeatherOptions::Rain,
+                WeatherOptions::Overcast,
+            ],
+        );
+        let conditions = |ambient_temperature, track_temperature| SlotConditions {
+            ambient_temperature,
+            track_temperature,
+            humidity: 60.0,
+            dew_point: 10.0,
+            cloud_cover: 50.0,
+        };
+        forecast.conditions.insert(
+            Sessions::Race,
+            vec![
+                conditions(25.0, 40.0),
+                conditions(16.0, 18.0),
+                conditions(20.0, 26.0),
+            ],
+        );
+
+        assert_eq!(
+            server_cfg(&forecast),
+            "[WEATHER_0]\n\
+             GRAPHICS=3_clear\n\
+             BASE_TEMPERATURE_AMBIENT=25\n\
+             BASE_TEMPERATURE_ROAD=15\n\
+             VARIATION_AMBIENT=1\n\
+             VARIATION_ROAD=1\n\
+             \n\
+             [WEATHER_1]\n\
+             GRAPHICS=7_heavy_clouds\n\
+             BASE_TEMPERATURE_AMBIENT=18\n\
+             BASE_TEMPERATURE_ROAD=4\n\
+             VARIATION_AMBIENT=2\n\
+             VARIATION_ROAD=2\n"
+        );
+
+        forecast.conditions.clear();
+        let weather = AcWeather::new(&forecast);
+        assert_eq!(weather[1].ambient, 22);
+        assert_eq!(weather[1].road, 6);
+        assert!(AcWeather::new(&WeatherForecast::default()).is_empty());
+    }
+}
@@ -0,0 +1,149 @@
+use super::{DEFAULT_AMBIENT_TEMPERATURE, event_session};
+use crate::{
+    error::ForecastError,
+    forecaster::{Round, SlotConditions, WeatherForecast, WeatherOptions},
+};
+
+/// Weather of an Assetto Corsa Competizione server, the matching fields of its `event.json`.
+///
+/// ACC has a single weather per event that evolves on its own, so it is based on the race (or the
+/// last session of the forecast). The more the weather of its slots changes, the higher the
+/// randomness.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccWeather {
+    /// Air temperature in °C
+    pub ambient_temp: i64,
+    /// Cloud cover from `0.0` to `1.0`, the mean of the generated cloud cover if there is one
+    pub cloud_level: f64,
+    /// Rain from `0.0` (dry) to `1.0` (thunderstorm)
+    pub rain: f64,
+    /// How much the weather changes during the event from `0` (static) to `7`
+    pub weather_randomness: u8,
+}
+
+impl AccWeather {
+    /// `None` if the forecast has no sessions, an error if the session the weather is based on
+    /// has no slots.
+    pub fn new(forecast: &WeatherForecast) -> Result<Option<Self>, ForecastError> {
+        let Some(session) = event_session(forecast) else {
+            return Ok(None);
+        };
+        let options = &forecast.forecast[&session];
+        if options.is_empty() {
+            return Err(ForecastError::EmptySession(session));
+        }
+        let slots = options.len() as f64;
+
+        let conditions = forecast
+            .conditions
+            .get(&session)
+            .filter(|conditions| !conditions.is_empty());
+        let mean = |value: fn(&SlotConditions) -> f64| {
+            conditions.map(|conditions| {
+                conditions.iter().map(value).sum::<f64>() / conditions.len() as f64
+            })
+        };
+        let ambient_temperature = mean(|conditions| conditions.ambient_temperature)
+            .unwrap_or(DEFAULT_AMBIENT_TEMPERATURE);
+        let cloud_level = mean(|conditions| conditions.cloud_cover / 100.0).unwrap_or_else(|| {
+            options
+                .iter()
+                .map(|option| 1.0 - option.sunshine())
+                .sum::<f64>()
+                / slots
+        });
+        let rain = options
+            .iter()
+            .map(|option| rain_level(*option))
+            .sum::<f64>()
+            / slots;
+
+        let mut distinct = options.clone();
+        distinct.sort_by_key(|option| *option as usize);
+        distinct.dedup();
+
+        Ok(Some(Self {
+            ambient_temp: ambient_temperature.round() as i64,
+            cloud_level: cloud_level.round_to_decimal_place(1),
+            rain: rain.round_to_decimal_place(1),
+            weather_randomness: ((distinct.len() - 1) * 2).min(7) as u8,
+        }))
+    }
+}
+
+/// ACC's rain level of a weather option, `0.1` is drizzle and `0.5` and above a thunderstorm.
+fn rain_level(option: WeatherOptions) -> f64 {
+    match option {
+        WeatherOptions::LightRain => 0.2,
+        WeatherOptions::Rain | WeatherOptions::FogWithRain | WeatherOptions::HeavyFogWithRain => {
+            0.3
+        }
+        WeatherOptions::Storm => 0.5,
+        WeatherOptions::Thunderstorm => 0.6,
+        _ => 0.0,
+    }
+}
+
+impl std::fmt::Display for AccWeather {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, r#""ambientTemp": {},"#, self.ambient_temp)?;
+        writeln!(f, r#""cloudLevel": {},"#, self.cloud_level)?;
+        writeln!(f, r#""rain": {},"#, self.rain)?;
+        writeln!(f, r#""weatherRandomness": {},"#, self.weather_randomness)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forecaster::Sessions;
+
+    #[test]
+    fn race_is_converted() {
+        let mut forecast = WeatherForecast::default();
+        forecast.forecast.insert(
+            Sessions::Race,
+            vec![WeatherOptions::Overcast, WeatherOptions::Rain],
+        );
+        forecast
+            .forecast
+            .insert(Sessions::Qualifying, vec![WeatherOptions::Clear]);
+        let conditions = |ambient_temperature| SlotConditions {
+            ambient_temperature,
+            track_temperature: 30.0,
+            humidity: 80.0,
+            dew_point: 15.0,
+            cloud_cover: 90.0,
+        };
+        forecast
+            .conditions
+            .insert(Sessions::Race, vec![conditions(18.0), conditions(15.0)]);
+
+        let weather = AccWeather::new(&forecast).unwrap().unwrap();
+        assert_eq!(
+            weather,
+            AccWeather {
+                ambient_temp: 17,
+                cloud_level: 0.9,
+                rain: 0.2,
+                weather_randomness: 2,
+            }
+        );
+        assert_eq!(
+            weather.to_string(),
+            "\"ambientTemp\": 17,\n\"cloudLevel\": 0.9,\n\"rain\": 0.2,\n\"weatherRandomness\": 2,\n"
+        );
+
+        forecast.forecast.remove(&Sessions::Race);
+        let weather = AccWeather::new(&forecast).unwrap().unwrap();
+        assert_eq!(weather.rain, 0.0);
+        assert_eq!(weather.weather_randomness, 0);
+        assert_eq!(AccWeather::new(&WeatherForecast::default()).unwrap(), None);
+
+        let empty: WeatherForecast = serde_json::from_str(r#"{"forecast": {"Race": []}}"#).unwrap();
+        assert!(matches!(
+            AccWeather::new(&empty),
+            Err(ForecastError::EmptySession(Sessions::Race))
+        ));
+    }
+}
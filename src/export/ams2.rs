@@ -0,0 +1,141 @@
+use crate::forecaster::{Sessions, WeatherForecast, WeatherOptions};
+
+/// Automobilista 2 (and Project CARS 2) have at most four weather slots per session.
+const MAX_SLOTS: usize = 4;
+
+/// Weather of the sessions as attributes of an Automobilista 2 dedicated server.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ams2Weather {
+    pub sessions: Vec<Ams2Session>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ams2Session {
+    /// Prefix of the session's attributes, e.g. `Practice1`
+    pub key: &'static str,
+    /// Names of the game's weather conditions
+    pub slots: Vec<&'static str>,
+}
+
+impl Ams2Weather {
+    /// Sessions the game does not have, like a third practice, are left out. Sessions with more
+    /// than four slots are thinned out evenly.
+    pub fn new(forecast: &WeatherForecast) -> Self {
+        let has_sprint = forecast.contains(Sessions::Sprint);
+        let sessions = forecast
+            .sessions()
+            .into_iter()
+            .filter_map(|session| {
+                let key = match session {
+                    Sessions::Fp1 | Sessions::Practice => "Practice1",
+                    Sessions::Fp2 => "Practice2",
+                    Sessions::Fp3 => return None,
+                    Sessions::Qualifying => "Qualify",
+                    Sessions::Warmup => "Warmup",
+                    Sessions::Sprint => "Race1",
+                    Sessions::Race if has_sprint => "Race2",
+                    Sessions::Race => "Race1",
+                };
+                let options = &forecast.forecast[&session];
+                let slots = (0..options.len().min(MAX_SLOTS))
+                    .map(|slot| {
+                        let index = slot * options.len() / options.len().min(MAX_SLOTS);
+                        condition(options[index])
+                    })
+                    .collect();
+                Some(Ams2Session { key, slots })
+            })
+            .collect();
+
+        Self { sessions }
+    }
+}
+
+fn condition(option: WeatherOptions) -> &'static str {
+    match option {
+        WeatherOptions::Clear => "Clear",
+        WeatherOptions::LightCloud => "LightCloud",
+        WeatherOptions::MediumCloud => "MediumCloud",
+        WeatherOptions::HeavyCloud => "HeavyCloud",
+        WeatherOptions::Overcast => "Overcast",
+        WeatherOptions::LightRain => "LightRain",
+        WeatherOptions::Rain => "Rain",
+        WeatherOptions::Storm => "Storm",
+        WeatherOptions::Thunderstorm => "ThunderStorm",
+        WeatherOptions::Foggy => "Foggy",
+        WeatherOptions::FogWithRain => "FogWithRain",
+        WeatherOptions::HeavyFog => "HeavyFog",
+        WeatherOptions::HeavyFogWithRain => "HeavyFogWithRain",
+        WeatherOptions::Hazy => "Hazy",
+        WeatherOptions::Random => "Random",
+    }
+}
+
+impl std::fmt::Display for Ams2Weather {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for session in &self.sessions {
+            let key = session.key;
+            writeln!(f, r#""{key}WeatherSlots": {},"#, session.slots.len())?;
+            for (index, slot) in session.slots.iter().enumerate() {
+                writeln!(f, r#""{key}WeatherSlot{}": "{slot}","#, index + 1)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sessions_fit_the_game() {
+        let mut forecast = WeatherForecast::default();
+        forecast.forecast.insert(
+            Sessions::Race,
+            vec![
+                WeatherOptions::Clear,
+                WeatherOptions::LightCloud,
+                WeatherOptions::MediumCloud,
+                WeatherOptions::HeavyCloud,
+                WeatherOptions::Overcast,
+                WeatherOptions::LightRain,
+                WeatherOptions::Rain,
+                WeatherOptions::Thunderstorm,
+            ],
+        );
+        forecast
+            .forecast
+            .insert(Sessions::Fp3, vec![WeatherOptions::Clear]);
+        forecast
+            .forecast
+            .insert(Sessions::Qualifying, vec![WeatherOptions::Hazy]);
+
+        assert_eq!(
+            Ams2Weather::new(&forecast).to_string(),
+            "\"QualifyWeatherSlots\": 1,\n\
+             \"QualifyWeatherSlot1\": \"Hazy\",\n\
+             \"Race1WeatherSlots\": 4,\n\
+             \"Race1WeatherSlot1\": \"Clear\",\n\
+             \"Race1WeatherSlot2\": \"MediumCloud\",\n\
+             \"Race1WeatherSlot3\": \"Overcast\",\n\
+             \"Race1WeatherSlot4\": \"Rain\",\n"
+        );
+
+        forecast
+            .forecast
+            .insert(Sessions::Sprint, vec![WeatherOptions::Thunderstorm]);
+        let weather = Ams2Weather::new(&forecast);
+        assert_eq!(
+            weather.sessions.last().unwrap(),
+            &Ams2Session {
+                key: "Race2",
+                slots: vec!["Clear", "MediumCloud", "Overcast", "Rain"],
+            }
+        );
+        assert!(weather.sessions.contains(&Ams2Session {
+            key: "Race1",
+            slots: vec!["ThunderStorm"],
+        }));
+    }
+}
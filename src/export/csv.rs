@@ -0,0 +1,87 @@
+use std::fmt::Write as _;
+
+use crate::{forecaster::WeatherForecast, template::TemplateData};
+
+const HEADER: &str = "session,slot,weather,rain_intensity,ambient_temperature,track_temperature,humidity,cloud_cover";
+
+/// One row per slot of every session, slots are numbered from 1. Conditions the forecast does not
+/// have are left empty.
+pub fn csv(forecast: &WeatherForecast) -> String {
+    let data = TemplateData::new(forecast, None);
+    let mut csv = format!("{HEADER}\n");
+    for session in &data.sessions {
+        for slot in &session.slots {
+            let value =
+                |value: Option<f64>| value.map(|value| value.to_string()).unwrap_or_default();
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{},{},{},{}",
+                field(&session.name),
+                slot.number,
+                field(&slot.weather),
+                slot.rain_intensity,
+                value(slot.ambient_temperature),
+                value(slot.track_temperature),
+                value(slot.humidity),
+                value(slot.cloud_cover),
+            );
+        }
+    }
+    csv
+}
+
+/// Quotes a field if it contains a separator, quote or line break.
+fn field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        forecaster::{Sessions, SlotConditions, WeatherOptions},
+        sessions::SessionDefinition,
+    };
+
+    #[test]
+    fn one_row_per_slot() {
+        let mut forecast = WeatherForecast::default();
+        forecast.forecast.insert(
+            Sessions::Race,
+            vec![WeatherOptions::Clear, WeatherOptions::Storm],
+        );
+        forecast
+            .forecast
+            .insert(Sessions::Qualifying, vec![WeatherOptions::Overcast]);
+        forecast.conditions.insert(
+            Sessions::Qualifying,
+            vec![SlotConditions {
+                ambient_temperature: 18.25,
+                track_temperature: 24.0,
+                humidity: 71.4,
+                dew_point: 12.0,
+                cloud_cover: 94.6,
+            }],
+        );
+        forecast.session_definitions = vec![SessionDefinition {
+            name: "Race, \"Main\"".to_string(),
+            session: Sessions::Race,
+            weather_slots: None,
+            key_prefix: None,
+        }];
+
+        assert_eq!(
+            csv(&forecast),
+            format!(
+                "{HEADER}\n\
+                 \"Race, \"\"Main\"\"\",1,Clear,0,,,,\n\
+                 \"Race, \"\"Main\"\"\",2,Storm,3,,,,\n\
+                 Qualify,1,Overcast,0,18.3,24,71,95\n"
+            )
+        );
+    }
+}
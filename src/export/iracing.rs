@@ -0,0 +1,208 @@
+use serde::Serialize;
+
+use crate::{
+    error::ForecastError,
+    forecaster::{Round, WeatherForecast, WeatherOptions},
+};
+
+/// Weather of the sessions as iRacing's custom weather settings.
+///
+/// A session whose weather never changes gets static weather, the others a timeline with one
+/// entry per slot.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IRacingWeather {
+    pub sessions: Vec<IRacingSession>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IRacingSession {
+    pub session: String,
+    #[serde(rename = "type")]
+    pub weather_type: WeatherType,
+    /// Always `1`, temperatures are given in °C
+    pub temp_units: u8,
+    /// Weather at the start of the session
+    #[serde(flatten)]
+    pub weather: IRacingConditions,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub timeline: Vec<TimelineEntry>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WeatherType {
+    Static,
+    Timeline,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TimelineEntry {
+    /// Share of the session that has passed when the weather sets in
+    pub start: f64,
+    #[serde(flatten)]
+    pub weather: IRacingConditions,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IRacingConditions {
+    /// `0` clear, `1` partly cloudy, `2` mostly cloudy or `3` overcast
+    pub skies: u8,
+    /// Precipitation in percent
+    pub precip: u8,
+    /// Fog density in percent
+    pub fog: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temp_value: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rel_humidity: Option<u8>,
+}
+
+impl IRacingWeather {
+    /// Fails if a session has no slots, iRacing needs the weather at its start.
+    pub fn new(forecast: &WeatherForecast) -> Result<Self, ForecastError> {
+        let sessions = forecast
+            .sessions()
+            .into_iter()
+            .map(|session| {
+                let options = &forecast.forecast[&session];
+                if options.is_empty() {
+                    return Err(ForecastError::EmptySession(session));
+                }
+                let conditions = forecast.conditions.get(&session);
+                let timings = forecast.slot_timings.get(&session);
+                let mut timeline: Vec<TimelineEntry> = options
+                    .iter()
+                    .enumerate()
+                    .map(|(index, option)| {
+                        let conditions = conditions.and_then(|conditions| conditions.get(index));
+                        let start = timings.and_then(|timings| timings.get(index)).map_or_else(
+                            || (index as f64 / options.len() as f64).round_to_decimal_place(3),
+                            |timing| timing.start,
+                        );
+                        TimelineEntry {
+                            start,
+                            weather: IRacingConditions {
+                                skies: skies(*option),
+                                precip: precip(*option),
+                                fog: fog(*option),
+                                temp_value: conditions
+                                    .map(|c| c.ambient_temperature.round_to_decimal_place(1)),
+                                rel_humidity: conditions.map(|c| c.humidity.round() as u8),
+                            },
+                        }
+                    })
+                    .collect();
+
+                let weather = timeline[0].weather.clone();
+                let weather_type = if timeline.iter().all(|entry| entry.weather == weather) {
+                    timeline.clear();
+                    WeatherType::Static
+                } else {
+                    WeatherType::Timeline
+                };
+                Ok(IRacingSession {
+                    session: forecast.session_name(session),
+                    weather_type,
+                    temp_units: 1,
+                    weather,
+                    timeline,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { sessions })
+    }
+}
+
+fn skies(option: WeatherOptions) -> u8 {
+    match option.sunshine() {
+        sunshine if sunshine >= 0.9 => 0,
+        sunshine if sunshine >= 0.5 => 1,
+        sunshine if sunshine >= 0.2 => 2,
+        _ => 3,
+    }
+}
+
+fn precip(option: WeatherOptions) -> u8 {
+    match option {
+        WeatherOptions::LightRain => 25,
+        WeatherOptions::Rain | WeatherOptions::FogWithRain | WeatherOptions::HeavyFogWithRain => 50,
+        WeatherOptions::Storm => 75,
+        WeatherOptions::Thunderstorm => 100,
+        _ => 0,
+    }
+}
+
+fn fog(option: WeatherOptions) -> u8 {
+    match option {
+        WeatherOptions::Hazy => 10,
+        WeatherOptions::Foggy | WeatherOptions::FogWithRain => 30,
+        WeatherOptions::HeavyFog | WeatherOptions::HeavyFogWithRain => 70,
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forecaster::Sessions;
+
+    #[test]
+    fn changing_weather_gets_a_timeline() {
+        let mut forecast = WeatherForecast::default();
+        forecast.forecast.insert(
+            Sessions::Race,
+            vec![
+                WeatherOptions::Clear,
+                WeatherOptions::HeavyCloud,
+                WeatherOptions::Storm,
+                WeatherOptions::HeavyFogWithRain,
+            ],
+        );
+        forecast.forecast.insert(
+            Sessions::Qualifying,
+            vec![WeatherOptions::Overcast, WeatherOptions::Overcast],
+        );
+
+        let weather = IRacingWeather::new(&forecast).unwrap();
+        let qualifying = &weather.sessions[0];
+        assert_eq!(qualifying.weather_type, WeatherType::Static);
+        assert_eq!(qualifying.weather.skies, 3);
+        assert!(qualifying.timeline.is_empty());
+
+        let race = &weather.sessions[1];
+        assert_eq!(race.weather_type, WeatherType::Timeline);
+        let timeline: Vec<_> = race
+            .timeline
+            .iter()
+            .map(|entry| {
+                (
+                    entry.start,
+                    entry.weather.skies,
+                    entry.weather.precip,
+                    entry.weather.fog,
+                )
+            })
+            .collect();
+        assert_eq!(
+            timeline,
+            [
+                (0.0, 0, 0, 0),
+                (0.25, 2, 0, 0),
+                (0.5, 3, 75, 0),
+                (0.75, 3, 50, 70)
+            ]
+        );
+
+        let json = serde_json::to_value(&weather).unwrap();
+        assert_eq!(json["sessions"][1]["type"], "timeline");
+        assert_eq!(json["sessions"][1]["skies"], 0);
+        assert!(json["sessions"][1].get("temp_value").is_none());
+
+        forecast.forecast.insert(Sessions::Practice, Vec::new());
+        assert!(matches!(
+            IRacingWeather::new(&forecast),
+            Err(ForecastError::EmptySession(Sessions::Practice))
+        ));
+    }
+}
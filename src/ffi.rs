@@ -0,0 +1,182 @@
+use std::{
+    cell::RefCell,
+    ffi::{CStr, CString, c_char},
+    ptr,
+};
+
+use crate::{
+    config::{Config, ConfigFormat},
+    error::ForecastError,
+    forecaster::WeatherForecaster,
+    output::OutputFormat,
+    sessions::{self, SessionDefinition},
+};
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Forecaster handed out to C as an opaque pointer.
+pub struct FfiForecaster {
+    forecaster: WeatherForecaster,
+    sessions: Vec<SessionDefinition>,
+}
+
+/// Creates a forecaster from a config in JSON, a null `config` uses the default config. A seed of
+/// `0` seeds randomly, any other seed always produces the same forecasts.
+///
+/// Returns null if the config is invalid, see [`wf_last_error`]. The forecaster has to be freed
+/// with [`wf_forecaster_free`].
+///
+/// # Safety
+///
+/// `config` has to be null or a valid nul-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn wf_forecaster_new(config: *const c_char, seed: u64) -> *mut FfiForecaster {
+    match unsafe { create(config, seed) } {
+        Ok(forecaster) => Box::into_raw(Box::new(forecaster)),
+        Err(error) => fail(&error),
+    }
+}
+
+/// Generates a forecast for the comma separated `sessions`, selected like with `--sessions`, a
+/// null `sessions` selects the default ones. The forecast is returned as JSON, like
+/// `--format json`.
+///
+/// Returns null on errors, see [`wf_last_error`]. The string has to be freed with
+/// [`wf_string_free`].
+///
+/// # Safety
+///
+/// `forecaster` has to be created by [`wf_forecaster_new`] and not be freed yet, `sessions` has to
+/// be null or a valid nul-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn wf_forecaster_generate(
+    forecaster: *mut FfiForecaster,
+    sessions: *const c_char,
+) -> *mut c_char {
+    let Some(forecaster) = (unsafe { forecaster.as_mut() }) else {
+        return fail(&"forecaster is null");
+    };
+    match unsafe { generate(forecaster, sessions) }.map(CString::new) {
+        Ok(Ok(json)) => json.into_raw(),
+        Ok(Err(error)) => fail(&error),
+        Err(error) => fail(&error),
+    }
+}
+
+/// # Safety
+///
+/// `forecaster` has to be null or created by [`wf_forecaster_new`] and not be freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn wf_forecaster_free(forecaster: *mut FfiForecaster) {
+    if !forecaster.is_null() {
+        drop(unsafe { Box::from_raw(forecaster) });
+    }
+}
+
+/// # Safety
+///
+/// `string` has to be null or returned by this library and not be freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn wf_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        drop(unsafe { CString::from_raw(string) });
+    }
+}
+
+/// Message of the last error on the calling thread, null if there was none. The string belongs to
+/// the library and stays valid until the next error.
+#[unsafe(no_mangle)]
+pub extern "C" fn wf_last_error() -> *const c_char {
+    LAST_ERROR.with(|error| {
+        error
+            .borrow()
+            .as_ref()
+            .map_or(ptr::null(), |error| error.as_ptr())
+    })
+}
+
+/// # Safety
+///
+/// `config` has to be null or a valid nul-terminated string.
+unsafe fn create(config: *const c_char, seed: u64) -> Result<FfiForecaster, ForecastError> {
+    let config: Config = match unsafe { read_str(config) }? {
+        Some(config) => ConfigFormat::Json.parse(config)?,
+        None => Config::default(),
+    };
+    let sessions = config.sessions.clone();
+    let mut forecaster = WeatherForecaster::new(config)?;
+    if seed != 0 {
+        forecaster = forecaster.with_seed(seed);
+    }
+    Ok(FfiForecaster {
+        forecaster,
+        sessions,
+    })
+}
+
+/// # Safety
+///
+/// `sessions` has to be null or a valid nul-terminated string.
+unsafe fn generate(
+    forecaster: &mut FfiForecaster,
+    sessions: *const c_char,
+) -> Result<String, ForecastError> {
+    let names: Vec<String> = unsafe { read_str(sessions) }?
+        .into_iter()
+        .flat_map(|sessions| sessions.split(','))
+        .filter(|name| !name.trim().is_empty())
+        .map(str::to_string)
+        .collect();
+    let sessions = sessions::resolve(&forecaster.sessions, &names)?;
+    let forecast = forecaster
+        .forecaster
+        .generate_constrained_forecast(&sessions)?;
+    OutputFormat::Json.render(&forecast, None, None, false)
+}
+
+/// # Safety
+///
+/// `string` has to be null or a valid nul-terminated string.
+unsafe fn read_str<'a>(string: *const c_char) -> Result<Option<&'a str>, ForecastError> {
+    if string.is_null() {
+        return Ok(None);
+    }
+    unsafe { CStr::from_ptr(string) }
+        .to_str()
+        .map(Some)
+        .map_err(|error| ForecastError::Ffi(error.to_string()))
+}
+
+fn fail<T>(error: &dyn std::fmt::Display) -> *mut T {
+    let message = CString::new(error.to_string().replace('\0', "")).expect("nul bytes are removed");
+    LAST_ERROR.with(|error| *error.borrow_mut() = Some(message));
+    ptr::null_mut()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forecasts_over_the_c_interface() {
+        unsafe {
+            let forecaster = wf_forecaster_new(ptr::null(), 4);
+            assert!(!forecaster.is_null());
+            let json = wf_forecaster_generate(forecaster, c"qualifying, race".as_ptr());
+            let forecast: serde_json::Value =
+                serde_json::from_str(CStr::from_ptr(json).to_str().unwrap()).unwrap();
+            assert_eq!(forecast["sessions"].as_array().unwrap().len(), 2);
+            wf_string_free(json);
+
+            assert!(wf_forecaster_generate(forecaster, c"superpole".as_ptr()).is_null());
+            let error = CStr::from_ptr(wf_last_error()).to_str().unwrap();
+            assert!(error.contains("superpole"));
+            wf_forecaster_free(forecaster);
+
+            assert!(wf_forecaster_new(c"{".as_ptr(), 0).is_null());
+            assert!(!wf_last_error().is_null());
+        }
+    }
+}
@@ -1,13 +1,50 @@
-use rand::{Rng, rngs::ThreadRng, seq::SliceRandom};
-use std::{collections::HashMap, fmt::Debug};
+use anstyle::{AnsiColor, Style};
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::SliceRandom};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Debug,
+    fmt::Write as _,
+};
 use strum::IntoEnumIterator;
 
 use serde::{Deserialize, Serialize};
 use strum::EnumIter;
 
-use crate::config::Config;
+use crate::{
+    catalog::{CatalogEntry, WeatherCatalog},
+    config::Config,
+    constraints::Constraints,
+    coupling::{CouplingConfig, PreviewMatch, Role},
+    drift::{Drift, DriftConfig},
+    endurance::{HourlyForecast, HourlyWeather},
+    error::ForecastError,
+    groups::{BUILT_IN_GROUPS, BUILT_IN_SLOT_DURATIONS, WeatherGroups},
+    humidity,
+    realism::Realism,
+    sampling::{AliasTable, WeatherSampler},
+    sessions::{self, SessionDefinition},
+    temperature::TemperatureConfig,
+    time_of_day::{TimeOfDay, night_probability_modifier},
+    transitions::TransitionConfig,
+    variety::Variety,
+    weekend::{Regime, WeekendConfig},
+};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter, Serialize, Deserialize)]
+/// Weather of a slot, displayed as the identifier the game uses in its settings.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    EnumIter,
+    strum::Display,
+    strum::IntoStaticStr,
+    Serialize,
+    Deserialize,
+    schemars::JsonSchema,
+)]
 pub enum WeatherOptions {
     Clear,
     LightCloud,
@@ -26,6 +63,26 @@ pub enum WeatherOptions {
     Random,
 }
 
+/// Accepts the enum names and the in-game identifiers, ignoring case, spaces and punctuation, e.g.
+/// `LightCloud`, `light cloud` or `LIGHT_CLOUD`.
+impl std::str::FromStr for WeatherOptions {
+    type Err = ForecastError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized = normalize(s);
+        Self::iter()
+            .find(|option| normalize(option.into()) == normalized)
+            .ok_or_else(|| ForecastError::UnknownWeather(s.trim().to_string()))
+    }
+}
+
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|char| char.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
 impl WeatherOptions {
     pub fn get_default_probabiliy(&self) -> f64 {
         match self {
@@ -47,28 +104,13 @@ impl WeatherOptions {
         }
     }
 
-    pub fn get_group(&self) -> &[WeatherOptions] {
-        macro_rules! weather_groups {
-            ( $([$( $option:ident ),+]),+ ) => {
-                match self {
-                    $(
-                        $(WeatherOptions::$option )|+ => {
-                            &[$(WeatherOptions::$option),+]
-                        }
-                    ),+
-                }
-            };
-        }
-
-        weather_groups!(
-            [Clear, LightCloud],
-            [MediumCloud, HeavyCloud, Overcast],
-            [LightRain],
-            [Rain, FogWithRain, HeavyFogWithRain],
-            [Storm, Thunderstorm],
-            [Foggy, HeavyFog, Hazy],
-            [Random]
-        )
+    /// Built-in group of the option, see [`BUILT_IN_GROUPS`].
+    pub fn get_group(&self) -> &'static [WeatherOptions] {
+        BUILT_IN_GROUPS
+            .iter()
+            .find(|(_, options)| options.contains(self))
+            .map(|(_, options)| *options)
+            .expect("every option is part of a built-in group")
     }
 
     #[must_use]
@@ -85,6 +127,111 @@ impl WeatherOptions {
         }
     }
 
+    /// How much sunshine reaches the ground from `0.0` (none) to `1.0` (clear sky).
+    #[must_use]
+    pub fn sunshine(&self) -> f64 {
+        match self {
+            WeatherOptions::Clear => 1.0,
+            WeatherOptions::LightCloud => 0.8,
+            WeatherOptions::MediumCloud => 0.55,
+            WeatherOptions::HeavyCloud => 0.3,
+            WeatherOptions::Overcast => 0.15,
+            WeatherOptions::LightRain => 0.1,
+            WeatherOptions::Rain => 0.0,
+            WeatherOptions::Storm => 0.0,
+            WeatherOptions::Thunderstorm => 0.05,
+            WeatherOptions::Foggy => 0.2,
+            WeatherOptions::FogWithRain => 0.0,
+            WeatherOptions::HeavyFog => 0.1,
+            WeatherOptions::HeavyFogWithRain => 0.0,
+            WeatherOptions::Hazy => 0.7,
+            WeatherOptions::Random => 0.5,
+        }
+    }
+
+    /// Emoji showing the weather, e.g. in announcements.
+    #[must_use]
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            WeatherOptions::Clear => "☀️",
+            WeatherOptions::LightCloud => "🌤️",
+            WeatherOptions::MediumCloud => "⛅",
+            WeatherOptions::HeavyCloud => "🌥️",
+            WeatherOptions::Overcast => "☁️",
+            WeatherOptions::LightRain => "🌦️",
+            WeatherOptions::Rain => "🌧️",
+            WeatherOptions::Storm => "⛈️",
+            WeatherOptions::Thunderstorm => "🌩️",
+            WeatherOptions::Foggy => "🌫️",
+            WeatherOptions::FogWithRain => "🌫️",
+            WeatherOptions::HeavyFog => "🌫️",
+            WeatherOptions::HeavyFogWithRain => "🌫️",
+            WeatherOptions::Hazy => "🌁",
+            WeatherOptions::Random => "🎲",
+        }
+    }
+
+    /// Terminal style of the weather: blue for rain, grey for fog and haze, yellow for sunshine.
+    #[must_use]
+    pub fn style(&self) -> Style {
+        match self {
+            WeatherOptions::Storm | WeatherOptions::Thunderstorm => {
+                AnsiColor::Blue.on_default().bold()
+            }
+            option if option.rain_intensity() > 0 => AnsiColor::Blue.on_default(),
+            WeatherOptions::Foggy | WeatherOptions::HeavyFog | WeatherOptions::Hazy => {
+                AnsiColor::BrightBlack.on_default()
+            }
+            WeatherOptions::Clear | WeatherOptions::LightCloud => AnsiColor::Yellow.on_default(),
+            WeatherOptions::Random => AnsiColor::Magenta.on_default(),
+            _ => Style::new(),
+        }
+    }
+
+    /// Plausible range of the relative humidity in percent.
+    #[must_use]
+    pub fn humidity_range(&self) -> (f64, f64) {
+        match self {
+            WeatherOptions::Clear => (30.0, 55.0),
+            WeatherOptions::LightCloud => (35.0, 60.0),
+            WeatherOptions::MediumCloud => (45.0, 70.0),
+            WeatherOptions::HeavyCloud => (55.0, 80.0),
+            WeatherOptions::Overcast => (60.0, 85.0),
+            WeatherOptions::LightRain => (75.0, 90.0),
+            WeatherOptions::Rain => (85.0, 97.0),
+            WeatherOptions::Storm => (85.0, 98.0),
+            WeatherOptions::Thunderstorm => (75.0, 95.0),
+            WeatherOptions::Foggy => (90.0, 98.0),
+            WeatherOptions::FogWithRain => (93.0, 100.0),
+            WeatherOptions::HeavyFog => (95.0, 100.0),
+            WeatherOptions::HeavyFogWithRain => (96.0, 100.0),
+            WeatherOptions::Hazy => (40.0, 65.0),
+            WeatherOptions::Random => (30.0, 100.0),
+        }
+    }
+
+    /// Plausible range of the share of the sky covered by clouds in percent.
+    #[must_use]
+    pub fn cloud_cover_range(&self) -> (f64, f64) {
+        match self {
+            WeatherOptions::Clear => (0.0, 10.0),
+            WeatherOptions::LightCloud => (10.0, 30.0),
+            WeatherOptions::MediumCloud => (30.0, 60.0),
+            WeatherOptions::HeavyCloud => (60.0, 90.0),
+            WeatherOptions::Overcast => (90.0, 100.0),
+            WeatherOptions::LightRain => (60.0, 90.0),
+            WeatherOptions::Rain => (80.0, 100.0),
+            WeatherOptions::Storm => (90.0, 100.0),
+            WeatherOptions::Thunderstorm => (70.0, 100.0),
+            WeatherOptions::Foggy => (50.0, 90.0),
+            WeatherOptions::FogWithRain => (80.0, 100.0),
+            WeatherOptions::HeavyFog => (80.0, 100.0),
+            WeatherOptions::HeavyFogWithRain => (90.0, 100.0),
+            WeatherOptions::Hazy => (10.0, 40.0),
+            WeatherOptions::Random => (0.0, 100.0),
+        }
+    }
+
     pub fn get_default_probablities() -> HashMap<WeatherOptions, f64> {
         let mut map = HashMap::new();
 
@@ -100,6 +247,7 @@ impl WeatherOptions {
     Debug,
     Serialize,
     Deserialize,
+    schemars::JsonSchema,
     Clone,
     Copy,
     PartialEq,
@@ -107,48 +255,149 @@ impl WeatherOptions {
     Hash,
     PartialOrd,
     Ord,
-    clap::ValueEnum,
     EnumIter,
 )]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
 pub enum Sessions {
-    Practice = 1,
-    Qualifying = 2,
-    Race = 3,
+    #[serde(alias = "FP1")]
+    Fp1,
+    #[serde(alias = "FP2")]
+    Fp2,
+    #[serde(alias = "FP3")]
+    Fp3,
+    Practice,
+    Qualifying,
+    Sprint,
+    Warmup,
+    Race,
+}
+
+impl Sessions {
+    /// Number of weather slots of the session if the config does not set it.
+    pub fn default_weather_slots(&self) -> usize {
+        match self {
+            Sessions::Fp1 | Sessions::Fp2 | Sessions::Fp3 | Sessions::Practice => 4,
+            Sessions::Qualifying | Sessions::Sprint => 2,
+            Sessions::Warmup => 1,
+            Sessions::Race => 4,
+        }
+    }
 }
 
+/// Prefix of the keys of the session in the game's settings.
 impl std::fmt::Display for Sessions {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            Self::Fp1 => write!(f, "Practice1"),
+            Self::Fp2 => write!(f, "Practice2"),
+            Self::Fp3 => write!(f, "Practice3"),
             Self::Qualifying => write!(f, "Qualify"),
             other => write!(f, "{other:?}"),
         }
     }
 }
 
+/// Number of weather slots most games support per session, endurance events can raise the limit
+/// with `max_weather_slots`.
+pub const DEFAULT_MAX_WEATHER_SLOTS: usize = 4;
+
+/// Number of tries to reroll a session that fits the kept sessions before giving up.
+const MAX_REROLL_ATTEMPTS: usize = 10_000;
+
+/// Probability of every option at each recorded step, see
+/// [`WeatherForecaster::with_probability_history`].
+pub type ProbabilityHistory = HashMap<WeatherOptions, Vec<f64>>;
+
 #[derive(Debug)]
-pub struct WeatherForecaster {
-    probabilities: HashMap<WeatherOptions, f64>,
+pub struct WeatherForecaster<R: Rng = StdRng> {
+    catalog: WeatherCatalog,
+    /// Probability of every catalog entry
+    probabilities: Vec<f64>,
+    /// Alias table of `probabilities` to pick entries from
+    sampler: WeatherSampler,
+    /// Probabilities adjusted to the start time of a session
+    session_probabilities: HashMap<Sessions, WeatherSampler>,
+    /// Probabilities of a session conditioned on the entry of the previous slot, filled as slots
+    /// are generated
+    transition_probabilities: HashMap<(Sessions, usize), WeatherSampler>,
+    start_times: HashMap<Sessions, TimeOfDay>,
+    night_sessions: HashSet<Sessions>,
+    /// Catalog entry of the first slot of a session
+    start_weather: HashMap<Sessions, usize>,
+    max_rain_chance: HashMap<Sessions, f64>,
+    slot_start_times: bool,
+    track_wetness: bool,
+    slot_durations: HashMap<String, f64>,
+    session_lengths: HashMap<Sessions, u32>,
+    session_definitions: Vec<SessionDefinition>,
     weather_slots: HashMap<Sessions, usize>,
-    rng: ThreadRng,
+    transitions: Option<TransitionConfig>,
+    drift: Option<DriftConfig>,
+    /// Weather groups held back in `variety_mode`
+    variety: Option<Variety>,
+    realism: Realism,
+    coupling: CouplingConfig,
+    weekend: Option<WeekendConfig>,
+    temperature: TemperatureConfig,
+    constraints: Constraints,
+    /// Only recorded when enabled
+    probability_history: Option<ProbabilityHistory>,
+    rng: R,
 }
 
 impl Default for WeatherForecaster {
     fn default() -> Self {
-        Self::new(Config::default())
+        Self::new(Config::default()).expect("default config is valid")
     }
 }
 
 impl WeatherForecaster {
-    pub fn new(mut config: Config) -> Self {
+    pub fn new(config: Config) -> Result<Self, ForecastError> {
+        Self::with_rng(config, StdRng::from_os_rng())
+    }
+
+    /// Reseeds the random number generator so that the same seed and config always produce the
+    /// same forecast.
+    #[must_use]
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+}
+
+impl<R: Rng> WeatherForecaster<R> {
+    /// Creates a forecaster that draws all random numbers from the provided generator.
+    pub fn with_rng(mut config: Config, rng: R) -> Result<Self, ForecastError> {
         // sanatize proabilities
+        for (option, probability) in &config.probabilities {
+            if !probability.is_finite() || *probability < 0.0 {
+                return Err(ForecastError::InvalidProbability {
+                    option: format!("{option:?}"),
+                    value: *probability,
+                });
+            }
+        }
+
+        // explicit probabilities take precedence over the track preset, without either the
+        // built-in probabilities are used
+        let climate = config.find_circuit()?.and_then(|circuit| circuit.climate);
+        let preset = config.track.or(climate);
+        let geography = config.geography.or(preset.map(|track| track.geography()));
+        let mut probabilities = match preset {
+            Some(track) => track.get_probabilities(),
+            None if config.probabilities.is_empty() => WeatherOptions::get_default_probablities(),
+            None => HashMap::new(),
+        };
+        probabilities.extend(config.probabilities.drain());
+        config.probabilities = probabilities;
+
         let accumulated_probability: f64 = config.probabilities.values().sum();
         if accumulated_probability > 1.0 {
-            eprintln!(
-                "WARN: Your specified probabilites accumulate to {}%",
+            tracing::warn!(
+                "Your probabilites accumulate to {}%, they are normalized which might result in \
+                 unexpected probabilities",
                 (accumulated_probability * 100.0).round_to_decimal_place(2)
             );
-            eprintln!("        -> Automatically fixing probabilities by normalizing them");
-            eprintln!("        -> This might result in unexpected probabilities!");
         }
 
         let missing_entries = WeatherOptions::iter().len() - config.probabilities.len();
@@ -168,238 +417,2695 @@ impl WeatherForecaster {
             initial_probabilities.insert(entry, *probability);
         }
 
+        let groups = match config.groups.take() {
+            Some(groups) => WeatherGroups::new(groups)?,
+            None => WeatherGroups::default(),
+        };
+        let catalog = match config.catalog.take() {
+            Some(definitions) => WeatherCatalog::new(definitions, &groups)?,
+            None => WeatherCatalog::built_in(&groups),
+        };
+        let mut probabilities = Vec::with_capacity(catalog.len());
+        for entry in catalog.entries() {
+            let probability = entry
+                .probability
+                .unwrap_or_else(|| initial_probabilities[&entry.option]);
+            if !probability.is_finite() || probability < 0.0 {
+                return Err(ForecastError::InvalidProbability {
+                    option: entry.name.clone(),
+                    value: probability,
+                });
+            }
+            probabilities.push(probability);
+        }
+        catalog.weight_groups(&mut probabilities, &config.group_probabilities)?;
+
+        if let Some(season) = config.get_season() {
+            let modifier = season.get_modifier(&config.season_modifiers);
+            modifier.validate()?;
+            for (entry, probability) in catalog.entries().iter().zip(&mut probabilities) {
+                *probability *= modifier.factor(entry.option);
+            }
+        }
+
+        if let Some(geography) = geography {
+            geography.validate()?;
+            for (entry, probability) in catalog.entries().iter().zip(&mut probabilities) {
+                *probability *= geography.probability_modifier(entry.option);
+            }
+            config.temperature = geography.temperatures(&config.temperature);
+        }
+
+        if let Some(transitions) = &config.transitions {
+            transitions.validate()?;
+        }
+        if let Some(drift) = &config.drift {
+            drift.validate()?;
+        }
+        config.reintroduction.validate()?;
+        config.coupling.validate()?;
+        if let Some(weekend) = &config.weekend {
+            weekend.validate()?;
+        }
+        config.temperature.validate()?;
+        for (session, chance) in &config.max_rain_chance {
+            if !chance.is_finite() || !(0.0..=1.0).contains(chance) {
+                return Err(ForecastError::InvalidProbability {
+                    option: format!("max rain chance of {session:?}"),
+                    value: *chance,
+                });
+            }
+        }
+        for (group, duration) in &config.slot_durations {
+            if !catalog.entries().iter().any(|entry| entry.group == *group) {
+                return Err(ForecastError::UnknownGroup(group.clone()));
+            }
+            if !duration.is_finite() || *duration <= 0.0 {
+                return Err(ForecastError::InvalidSlotDuration {
+                    group: group.clone(),
+                    value: *duration,
+                });
+            }
+        }
+        config.constraints.validate(&catalog)?;
+        let mut start_weather = HashMap::new();
+        for (session, option) in &config.start_weather {
+            let entry = catalog
+                .position(*option)
+                .ok_or_else(|| ForecastError::UnknownWeather(format!("{option:?}")))?;
+            start_weather.insert(*session, entry);
+        }
+
+        sessions::validate(&config.sessions)?;
+        for definition in &config.sessions {
+            if let Some(slots) = definition.weather_slots {
+                config.weather_slots.insert(definition.session, slots);
+            }
+        }
+
         // sanitize weather slots
-        let default_config = Config::default();
-        for (session, slots) in default_config.weather_slots.into_iter() {
-            let entry = config.weather_slots.entry(session).or_insert(slots);
-            *entry = (*entry).clamp(1, 4);
+        let max_weather_slots = config
+            .max_weather_slots
+            .unwrap_or(DEFAULT_MAX_WEATHER_SLOTS);
+        if max_weather_slots == 0 {
+            return Err(ForecastError::InvalidMaxWeatherSlots(max_weather_slots));
+        }
+        for session in Sessions::iter() {
+            let entry = config
+                .weather_slots
+                .entry(session)
+                .or_insert_with(|| session.default_weather_slots());
+            *entry = (*entry).clamp(1, max_weather_slots);
         }
 
+        // rebuilt once the probabilities are normalized
+        let sampler = WeatherSampler::new(&catalog, probabilities.clone());
         let mut forecaster = Self {
-            probabilities: initial_probabilities,
+            catalog,
+            probabilities,
+            sampler,
+            session_probabilities: HashMap::new(),
+            transition_probabilities: HashMap::new(),
+            start_times: config.start_times,
+            night_sessions: config.night_sessions,
+            start_weather,
+            max_rain_chance: config.max_rain_chance,
+            slot_start_times: config.slot_start_times,
+            track_wetness: config.track_wetness,
+            slot_durations: config.slot_durations,
+            session_lengths: config.session_lengths,
+            session_definitions: config.sessions,
             weather_slots: config.weather_slots,
-            rng: rand::rng(),
+            transitions: config.transitions,
+            drift: config.drift,
+            variety: config
+                .variety_mode
+                .then(|| Variety::new(&config.reintroduction)),
+            realism: config.realism,
+            coupling: config.coupling,
+            weekend: config.weekend,
+            temperature: config.temperature,
+            constraints: config.constraints,
+            probability_history: None,
+            rng,
         };
-        forecaster.normalize_probabilities();
-        forecaster.print_probabilities();
-        forecaster
+        forecaster.normalize_probabilities()?;
+        forecaster.update_session_probabilities()?;
+        tracing::trace!(
+            "Using the following probabilities to generate a random weather forecast:\n{}",
+            forecaster.probability_table(None)
+        );
+        Ok(forecaster)
+    }
+
+    /// Table of the probability of every catalog entry in percent, either in `session` or
+    /// without the adjustments of any session.
+    pub fn probability_table(&self, session: Option<Sessions>) -> String {
+        self.format_probability_table(session, false)
+    }
+
+    /// [`Self::probability_table`] with the weather colored for terminals.
+    pub fn colored_probability_table(&self, session: Option<Sessions>) -> String {
+        self.format_probability_table(session, true)
     }
 
-    pub fn print_probabilities(&self) {
-        let max_length_option = WeatherOptions::iter()
-            .map(|option| format!("{option:?}").len())
+    fn format_probability_table(&self, session: Option<Sessions>, colored: bool) -> String {
+        let max_length_option = self
+            .catalog
+            .entries()
+            .iter()
+            .map(|entry| entry.name.len())
             .max()
             .unwrap()
             .max("Weather".len());
 
-        println!("Using the following probabilities to generate a random weather forecast:");
-        println!();
-        println!("{:<len$} : Probability", "Weather", len = max_length_option);
-        println!("{:-<len$} : -----------", "", len = max_length_option);
-        for option in WeatherOptions::iter() {
-            let probability =
-                (*self.probabilities.get(&option).unwrap() * 100.0).round_to_decimal_place(2);
-            println!(
-                "{:<len$} : {probability}%",
-                format!("{option:?}"),
+        let mut table = String::new();
+        let _ = writeln!(
+            table,
+            "{:<len$} : Probability",
+            "Weather",
+            len = max_length_option
+        );
+        let _ = writeln!(
+            table,
+            "{:-<len$} : -----------",
+            "",
+            len = max_length_option
+        );
+        let probabilities: Vec<_> = match session {
+            Some(session) => self.session_probabilities(session).collect(),
+            None => self.probabilities().collect(),
+        };
+        for (entry, probability) in probabilities {
+            let probability = (probability * 100.0).round_to_decimal_place(2);
+            let style = if colored {
+                entry.option.style()
+            } else {
+                Style::new()
+            };
+            let _ = writeln!(
+                table,
+                "{style}{:<len$}{style:#} : {probability}%",
+                entry.name,
                 len = max_length_option
             );
         }
-        println!();
+        table
     }
 
-    fn generate_weather_option(&mut self, might_rain: bool) -> WeatherOptions {
-        loop {
-            let next_option: f64 = self.rng.random();
-            let mut current_value = 0.0;
-            let mut selected = WeatherOptions::Clear;
-            for option in WeatherOptions::iter() {
-                current_value += self.probabilities.get(&option).unwrap();
-                if current_value > next_option {
-                    selected = option;
-                    break;
-                }
-            }
-            if might_rain || selected.rain_intensity() == 0 {
-                return selected;
-            }
+    /// Starts recording the probabilities every time the weather of a slot is picked, as well as
+    /// after every change of the probabilities. The current probabilities are the first step.
+    #[must_use]
+    pub fn with_probability_history(mut self) -> Self {
+        let mut history = Some(ProbabilityHistory::new());
+        Self::record_probabilities(&mut history, &self.catalog, &self.probabilities);
+        self.probability_history = history;
+        self
+    }
+
+    /// The recorded probabilities, `None` if they are not recorded.
+    pub fn probability_history(&self) -> Option<&ProbabilityHistory> {
+        self.probability_history.as_ref()
+    }
+
+    fn record_probabilities(
+        history: &mut Option<ProbabilityHistory>,
+        catalog: &WeatherCatalog,
+        probabilities: &[f64],
+    ) {
+        let Some(history) = history else {
+            return;
+        };
+        let mut step: HashMap<WeatherOptions, f64> =
+            WeatherOptions::iter().map(|option| (option, 0.0)).collect();
+        for (entry, probability) in catalog.entries().iter().zip(probabilities) {
+            *step.entry(entry.option).or_default() += probability;
+        }
+        for (option, probability) in step {
+            history.entry(option).or_default().push(probability);
         }
     }
 
-    pub fn generate_weather_option_in_group(
-        &mut self,
-        weather_option: WeatherOptions,
-    ) -> WeatherOptions {
-        let group = weather_option.get_group();
-        let mut option;
-        loop {
-            option = self.generate_weather_option(true);
-            if group.contains(&option) {
-                break;
+    /// Weather groups with the options of their catalog entries, in the order of the catalog.
+    pub fn groups(&self) -> Vec<(&str, Vec<WeatherOptions>)> {
+        let mut groups: Vec<(&str, Vec<WeatherOptions>)> = Vec::new();
+        for entry in self.catalog.entries() {
+            match groups.iter_mut().find(|(group, _)| *group == entry.group) {
+                Some((_, options)) if options.contains(&entry.option) => {}
+                Some((_, options)) => options.push(entry.option),
+                None => groups.push((&entry.group, vec![entry.option])),
             }
         }
-        option
+        groups
     }
 
-    pub fn generate_forecast(&mut self, sessions: &[Sessions]) -> WeatherForecast {
-        let mut forecast = WeatherForecast::default();
+    /// Every catalog entry with its probability, in the order of the catalog.
+    pub fn probabilities(&self) -> impl Iterator<Item = (&CatalogEntry, f64)> {
+        self.catalog
+            .entries()
+            .iter()
+            .zip(self.probabilities.iter().copied())
+    }
 
-        // race
-        if sessions.contains(&Sessions::Race) {
-            forecast.forecast.insert(
-                Sessions::Race,
-                self.generate_single_session_forecast(
-                    *self.weather_slots.get(&Sessions::Race).unwrap(),
-                    true,
-                ),
-            );
+    /// Every catalog entry with its probability in `session`, adjusted to the start time, night
+    /// and rain cap of the session if it has any.
+    pub fn session_probabilities(
+        &self,
+        session: Sessions,
+    ) -> impl Iterator<Item = (&CatalogEntry, f64)> {
+        let probabilities = self
+            .session_probabilities
+            .get(&session)
+            .map_or(self.probabilities.as_slice(), |sampler| {
+                sampler.probabilities()
+            });
+        self.catalog
+            .entries()
+            .iter()
+            .zip(probabilities.iter().copied())
+    }
+
+    /// Whether the probabilities of `session` differ from the ones of the other sessions.
+    pub fn has_session_probabilities(&self, session: Sessions) -> bool {
+        self.session_probabilities.contains_key(&session)
+    }
+
+    /// Summed up probability of all catalog entries based on the given option.
+    pub fn get_probability(&self, option: WeatherOptions) -> f64 {
+        self.catalog
+            .entries()
+            .iter()
+            .zip(&self.probabilities)
+            .filter(|(entry, _)| entry.option == option)
+            .map(|(_, probability)| probability)
+            .sum()
+    }
+
+    /// Changes the summed up probability of `option` while keeping the ratios between all other
+    /// catalog entries.
+    pub fn set_probability(
+        &mut self,
+        option: WeatherOptions,
+        probability: f64,
+    ) -> Result<(), ForecastError> {
+        if !probability.is_finite() || !(0.0..=1.0).contains(&probability) {
+            return Err(ForecastError::InvalidProbability {
+                option: format!("{option:?}"),
+                value: probability,
+            });
         }
-        let race_rain = forecast
-            .forecast
-            .get(&Sessions::Race)
-            .map(|options| {
-                options
-                    .iter()
-                    .max_by_key(|option| option.rain_intensity())
-                    .unwrap()
+        let matching = self
+            .catalog
+            .entries()
+            .iter()
+            .filter(|entry| entry.option == option)
+            .count();
+        if matching == 0 {
+            return Err(ForecastError::UnknownWeather(format!("{option:?}")));
+        }
+
+        let current = self.get_probability(option);
+        let others = 1.0 - current;
+        let other_count = self.catalog.len() - matching;
+        for (entry, entry_probability) in self.catalog.entries().iter().zip(&mut self.probabilities)
+        {
+            *entry_probability = match (entry.option == option, current > 0.0, others > 0.0) {
+                (true, true, _) => *entry_probability * probability / current,
+                (true, false, _) => probability / matching as f64,
+                (false, _, true) => *entry_probability * (1.0 - probability) / others,
+                (false, _, false) => (1.0 - probability) / other_count as f64,
+            };
+        }
+        self.normalize_probabilities()?;
+        self.update_session_probabilities()?;
+        Self::record_probabilities(
+            &mut self.probability_history,
+            &self.catalog,
+            &self.probabilities,
+        );
+        Ok(())
+    }
+
+    /// Rebuilds the alias tables after the probabilities changed.
+    fn update_session_probabilities(&mut self) -> Result<(), ForecastError> {
+        self.sampler = WeatherSampler::new(&self.catalog, self.probabilities.clone());
+        self.transition_probabilities.clear();
+        self.session_probabilities = Sessions::iter()
+            .filter_map(|session| {
+                let start_time = self.start_times.get(&session);
+                let max_rain_chance = self.max_rain_chance.get(&session);
+                let night = self.night_sessions.contains(&session);
+                if start_time.is_none() && max_rain_chance.is_none() && !night {
+                    return None;
+                }
+
+                let mut probabilities = match start_time {
+                    Some(start_time) => self.get_probabilities_at(*start_time),
+                    None => self.probabilities.clone(),
+                };
+                if night {
+                    self.darken(&mut probabilities);
+                }
+                if let Some(max_rain_chance) = max_rain_chance
+                    && let Err(error) =
+                        self.cap_rain_chance(session, &mut probabilities, *max_rain_chance)
+                {
+                    return Some(Err(error));
+                }
+                Some(Ok((
+                    session,
+                    WeatherSampler::new(&self.catalog, probabilities),
+                )))
             })
-            .filter(|option| option.rain_intensity() > 0)
-            .copied();
-
-        // quali
-        if sessions.contains(&Sessions::Qualifying) {
-            let quali = self.generate_single_session_forecast(
-                *self.weather_slots.get(&Sessions::Qualifying).unwrap(),
-                race_rain.is_some(),
-            );
+            .collect::<Result<_, _>>()?;
+        Ok(())
+    }
 
-            forecast.forecast.insert(Sessions::Qualifying, quali);
+    /// Scales the wet entries down so that the chance of a slot being wet does not exceed
+    /// `max_rain_chance`, the dry entries make up for the difference. Fails if there is no dry
+    /// weather to make up for it.
+    fn cap_rain_chance(
+        &self,
+        session: Sessions,
+        probabilities: &mut [f64],
+        max_rain_chance: f64,
+    ) -> Result<(), ForecastError> {
+        let is_wet = |index: usize| self.catalog[index].rain_intensity > 0;
+        let wet: f64 = (0..probabilities.len())
+            .filter(|index| is_wet(*index))
+            .map(|index| probabilities[index])
+            .sum();
+        let dry = 1.0 - wet;
+        if wet <= max_rain_chance {
+            return Ok(());
+        }
+        if dry <= 0.0 {
+            return Err(ForecastError::RainChanceCapUnreachable {
+                session,
+                max_rain_chance,
+            });
         }
 
-        // practice
-        let practice_rain = race_rain.map(|option| self.generate_weather_option_in_group(option));
-        if sessions.contains(&Sessions::Practice) {
-            let mut practice = self.generate_single_session_forecast(
-                *self.weather_slots.get(&Sessions::Practice).unwrap(),
-                race_rain.is_some(),
-            );
-            if let Some(practice_rain) = practice_rain {
-                *practice.last_mut().unwrap() = practice_rain;
-                practice.shuffle(&mut self.rng);
+        for (index, probability) in probabilities.iter_mut().enumerate() {
+            *probability *= if is_wet(index) {
+                max_rain_chance / wet
+            } else {
+                (1.0 - max_rain_chance) / dry
+            };
+        }
+        Ok(())
+    }
+
+    /// Adjusts the probabilities to a night session, see [`night_probability_modifier`].
+    fn darken(&self, probabilities: &mut [f64]) {
+        for (entry, probability) in self.catalog.entries().iter().zip(probabilities.iter_mut()) {
+            *probability *= night_probability_modifier(entry.option);
+        }
+        let sum: f64 = probabilities.iter().sum();
+        if sum > 0.0 {
+            for probability in probabilities {
+                *probability /= sum;
             }
-            forecast.forecast.insert(Sessions::Practice, practice);
         }
+    }
 
-        forecast
+    fn get_probabilities_at(&self, time: TimeOfDay) -> Vec<f64> {
+        let mut probabilities: Vec<_> = self
+            .catalog
+            .entries()
+            .iter()
+            .zip(&self.probabilities)
+            .map(|(entry, probability)| probability * time.probability_modifier(entry.option))
+            .collect();
+        let sum: f64 = probabilities.iter().sum();
+        for probability in &mut probabilities {
+            *probability /= sum;
+        }
+        probabilities
     }
 
-    fn generate_single_session_forecast(
+    #[cfg(test)]
+    fn generate_weather_option(&mut self) -> WeatherOptions {
+        let index = self.sampler.sample(&mut self.rng);
+        self.catalog[index].option
+    }
+
+    /// Picks the weather of the next slot of a session, taking the previous slot into account
+    /// if transitions, drift or realism are enabled. `varied` replaces the probabilities of the
+    /// session in `variety_mode`. Entries in `excluded` are guaranteed to remain
+    /// reachable from the returned distribution, so callers rejecting them do not loop forever.
+    /// Fails if the slot has to be dry but there is no dry weather.
+    fn generate_session_entry(
         &mut self,
-        weather_slots: usize,
+        session: Sessions,
+        previous: Option<usize>,
+        varied: Option<&WeatherSampler>,
+        drift: Option<&Drift>,
+        excluded: &[usize],
         might_rain: bool,
-    ) -> Vec<WeatherOptions> {
-        if self.get_available_weather_options(might_rain) >= weather_slots {
-            let mut options = Vec::new();
-            while options.len() < weather_slots {
-                let option = self.generate_weather_option(might_rain);
-                if !options.contains(&option) {
-                    options.push(option);
+    ) -> Result<usize, ForecastError> {
+        let sampler = varied.unwrap_or_else(|| {
+            self.session_probabilities
+                .get(&session)
+                .unwrap_or(&self.sampler)
+        });
+
+        if let Some(previous) = previous.filter(|_| self.chains_slots()) {
+            let condition = |probabilities: &[f64]| {
+                let probabilities = match &self.transitions {
+                    Some(transitions) => {
+                        transitions.condition(&self.catalog, probabilities, previous)
+                    }
+                    None => probabilities.to_vec(),
+                };
+                WeatherSampler::new(
+                    &self.catalog,
+                    self.realism.filter(&self.catalog, probabilities, previous),
+                )
+            };
+            // drifted probabilities depend on all previous slots and varied ones on the previous
+            // sessions, so neither can be cached
+            let uncached;
+            let conditioned = match drift {
+                Some(drift) => {
+                    uncached = condition(&drift.condition(&self.catalog, sampler.probabilities()));
+                    &uncached
+                }
+                None if varied.is_some() => {
+                    uncached = condition(sampler.probabilities());
+                    &uncached
+                }
+                None => self
+                    .transition_probabilities
+                    .entry((session, previous))
+                    .or_insert_with(|| condition(sampler.probabilities())),
+            };
+            let reachable: f64 = conditioned
+                .probabilities()
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| {
+                    (might_rain || self.catalog[*index].rain_intensity == 0)
+                        && !excluded.contains(index)
+                })
+                .map(|(_, probability)| probability)
+                .sum();
+            if reachable > 0.0 {
+                Self::record_probabilities(
+                    &mut self.probability_history,
+                    &self.catalog,
+                    conditioned.probabilities(),
+                );
+                if let Some(entry) = Self::pick_entry(conditioned, &mut self.rng, might_rain) {
+                    return Ok(entry);
                 }
             }
-            options
-        } else {
-            (0..weather_slots)
-                .map(|_| self.generate_weather_option(might_rain))
-                .collect()
         }
+
+        Self::record_probabilities(
+            &mut self.probability_history,
+            &self.catalog,
+            sampler.probabilities(),
+        );
+        Self::pick_entry(sampler, &mut self.rng, might_rain).ok_or(ForecastError::NoDryWeather {
+            session,
+            leader: self.coupling.leader,
+        })
     }
 
-    fn get_available_weather_options(&self, with_rain: bool) -> usize {
-        self.probabilities
-            .iter()
-            .filter(|(option, probability)| {
-                (option.rain_intensity() == 0 || with_rain) && **probability > 0.0
-            })
-            .count()
+    /// Whether the weather of a slot depends on the previous slot.
+    fn chains_slots(&self) -> bool {
+        self.transitions.is_some() || self.drift.is_some() || self.realism != Realism::Off
     }
 
-    fn normalize_probabilities(&mut self) {
-        let sum: f64 = self.probabilities.values().sum();
-        assert!(sum <= 1.0);
-        let factor = 1.0 / sum;
-        for probability in self.probabilities.values_mut() {
-            *probability *= factor;
+    /// Picks any entry of `sampler` or only a dry one if the slot must not have rain.
+    fn pick_entry(sampler: &WeatherSampler, rng: &mut R, might_rain: bool) -> Option<usize> {
+        if might_rain {
+            Some(sampler.sample(rng))
+        } else {
+            sampler.sample_dry(rng)
         }
     }
-}
 
-#[derive(Debug, Clone, Default)]
-pub struct WeatherForecast {
-    forecast: HashMap<Sessions, Vec<WeatherOptions>>,
-}
+    pub fn generate_weather_option_in_group(
+        &mut self,
+        weather_option: WeatherOptions,
+    ) -> WeatherOptions {
+        let index = self
+            .catalog
+            .position(weather_option)
+            .expect("option is part of the catalog");
+        let index = self.generate_entry_in_group(index);
+        self.catalog[index].option
+    }
 
-impl std::fmt::Display for WeatherForecast {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for session in Sessions::iter() {
-            if let Some(forecast) = self.forecast.get(&session) {
-                writeln!(f, r#""{session}WeatherSlots": {},"#, forecast.len())?;
-                for (index, option) in forecast.iter().enumerate() {
-                    writeln!(f, r#""{session}WeatherSlot{}": "{option:?}","#, index + 1)?;
+    /// Picks an entry of the group of the entry at `index` with the probabilities of the config.
+    /// If the config rules out the whole group, e.g. for a kept session or a METAR, the entry
+    /// itself is returned.
+    fn generate_entry_in_group(&mut self, index: usize) -> usize {
+        let group = &self.catalog[index].group;
+        let probabilities: Vec<f64> = self
+            .catalog
+            .entries()
+            .iter()
+            .zip(self.sampler.probabilities())
+            .map(|(entry, probability)| {
+                if entry.group == *group {
+                    *probability
+                } else {
+                    0.0
                 }
-                writeln!(f)?;
-            }
+            })
+            .collect();
+        if probabilities.iter().sum::<f64>() <= 0.0 {
+            return index;
         }
-        Ok(())
+        AliasTable::new(probabilities).sample(&mut self.rng)
     }
-}
-
-trait Round {
-    fn round_to_decimal_place(&self, decimal_places: i32) -> Self;
-}
 
-impl Round for f64 {
-    fn round_to_decimal_place(&self, decimal_places: i32) -> Self {
-        let factor = 10.0_f64.powi(decimal_places);
-        (self * factor).round() / factor
+    /// Generates the weather of `sessions`. Fails if a session has to be dry because of the rain
+    /// coupling but no dry weather is possible.
+    pub fn generate_forecast(
+        &mut self,
+        sessions: &[Sessions],
+    ) -> Result<WeatherForecast, ForecastError> {
+        let forecast = self.generate_sessions(sessions)?;
+        self.record_variety(&forecast, sessions)?;
+        Ok(forecast)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use std::collections::HashMap;
+    /// Generates a forecast like [`Self::generate_forecast`] without remembering its weather
+    /// groups for `variety_mode`, e.g. for candidates the constraints may still reject.
+    fn generate_sessions(
+        &mut self,
+        sessions: &[Sessions],
+    ) -> Result<WeatherForecast, ForecastError> {
+        if let Some(weekend) = self.weekend.clone() {
+            let regimes = weekend.regimes(&mut self.rng);
+            let mut entries = HashMap::new();
+            for session in Sessions::iter().filter(|session| sessions.contains(session)) {
+                let regime = regimes[&weekend.day(session)];
+                entries.insert(session, self.generate_session_in_regime(session, regime)?);
+            }
+            return Ok(self.build_forecast(entries));
+        }
 
-    use float_cmp::assert_approx_eq;
-    use strum::IntoEnumIterator;
+        let leader = self.coupling.leader;
+        let mut entries = HashMap::new();
 
-    use super::*;
+        // the session the others follow, the race by default
+        if sessions.contains(&leader) {
+            entries.insert(leader, self.generate_single_session_forecast(leader, true)?);
+        }
+        let leader_rain = entries
+            .get(&leader)
+            .and_then(|entries| self.heaviest_rain(entries));
 
-    #[test]
-    fn sane_probabilities() {
-        let mut sum = 0.0;
-        for option in WeatherOptions::iter() {
-            sum += option.get_default_probabiliy();
+        // the other sessions backwards from the race
+        for session in Sessions::iter()
+            .rev()
+            .filter(|session| sessions.contains(session) && *session != leader)
+        {
+            let session_entries = self.generate_coupled_session(session, leader_rain)?;
+            entries.insert(session, session_entries);
         }
-        assert_approx_eq!(f64, sum, 1.0, epsilon = 0.0001);
+
+        Ok(self.build_forecast(entries))
     }
 
-    #[test]
-    fn options_picked_correctly() {
-        const NUMBER_OF_PICKS: usize = 100_000_000;
-        let mut picked_times: HashMap<WeatherOptions, usize> =
-            WeatherOptions::iter().map(|option| (option, 0)).collect();
+    /// Generates the weather of every hour of an endurance race starting at `start`. Fog, haze
+    /// and thunderstorms follow the time of day like for sessions with a start time, the hours
+    /// chain like slots and the temperatures drop at night.
+    pub fn generate_hourly(&mut self, hours: usize, start: TimeOfDay) -> HourlyForecast {
+        let warmth = self.rng.random();
+        let mut drift = self.drift.as_ref().map(DriftConfig::start);
+        let mut forecast = HourlyForecast::default();
+        for hour in 0..hours {
+            let time = start.add_minutes(hour as u32 * 60);
+            let mut probabilities = self.get_probabilities_at(time);
+            if let Some(drift) = &drift {
+                probabilities = drift.condition(&self.catalog, &probabilities);
+            }
+            if let Some(previous) = forecast.entries.last().copied() {
+                if let Some(transitions) = &self.transitions {
+                    probabilities = transitions.condition(&self.catalog, &probabilities, previous);
+                }
+                probabilities = self.realism.filter(&self.catalog, probabilities, previous);
+            }
+            let entry = WeatherSampler::new(&self.catalog, probabilities).sample(&mut self.rng);
+            if let Some(drift) = &mut drift {
+                drift.advance(&self.catalog[entry]);
+            }
 
-        let mut forecaster = WeatherForecaster::default();
+            let weather = self.catalog[entry].option;
+            let (ambient_temperature, track_temperature) = self
+                .temperature
+                .generate_temperatures_in_daylight(warmth, weather, time.daylight(), &mut self.rng);
+            forecast.entries.push(entry);
+            forecast.hours.push(HourlyWeather {
+                time,
+                weather,
+                ambient_temperature,
+                track_temperature,
+            });
+        }
+        forecast
+    }
 
-        for _ in 0..NUMBER_OF_PICKS {
-            let option = forecaster.generate_weather_option(true);
-            *picked_times.get_mut(&option).unwrap() += 1;
+    /// Compresses an hourly forecast into the weather slots of the session, so it fits into the
+    /// game's settings. The slots keep the average temperatures of their hours.
+    pub fn compress_hourly(
+        &mut self,
+        hourly: &HourlyForecast,
+        session: Sessions,
+    ) -> WeatherForecast {
+        let slots = self.weather_slots[&session];
+        let entries = hourly.compress(&self.catalog, slots);
+        let mut forecast = self.build_forecast(HashMap::from([(session, entries)]));
+        if let Some(conditions) = forecast.conditions.get_mut(&session) {
+            for (conditions, hours) in conditions.iter_mut().zip(hourly.slot_hours(slots)) {
+                let hours = &hourly.hours[hours];
+                let average = |temperature: fn(&HourlyWeather) -> f64| {
+                    (hours.iter().map(temperature).sum::<f64>() / hours.len() as f64)
+                        .round_to_decimal_place(1)
+                };
+                conditions.ambient_temperature = average(|hour| hour.ambient_temperature);
+                conditions.track_temperature = average(|hour| hour.track_temperature);
+                conditions.dew_point =
+                    humidity::dew_point(conditions.ambient_temperature, conditions.humidity);
+            }
         }
+        forecast
+    }
 
-        for (option, picks) in picked_times {
-            let real_probability = picks as f64 / NUMBER_OF_PICKS as f64;
-            let actual_probability = option.get_default_probabiliy();
-            println!("{option:?} should be {real_probability} and is {actual_probability}");
-            assert_approx_eq!(f64, real_probability, actual_probability, epsilon = 0.0005);
+    /// Generates forecasts until one satisfies the constraints of the config.
+    pub fn generate_constrained_forecast(
+        &mut self,
+        sessions: &[Sessions],
+    ) -> Result<WeatherForecast, ForecastError> {
+        let forecast =
+            self.satisfy_constraints(|forecaster| forecaster.generate_sessions(sessions))?;
+        self.record_variety(&forecast, sessions)?;
+        Ok(forecast)
+    }
+
+    /// Generates `session` again while keeping all other sessions of `forecast`.
+    ///
+    /// The rain coupling of [`Self::generate_forecast`] is respected: the other sessions follow
+    /// the rain of the kept leader (the race by default), and a rerolled leader is drawn until its
+    /// rain fits the kept sessions. The result satisfies the constraints of the config.
+    pub fn reroll(
+        &mut self,
+        forecast: &WeatherForecast,
+        session: Sessions,
+    ) -> Result<WeatherForecast, ForecastError> {
+        let rerolled =
+            self.satisfy_constraints(|forecaster| forecaster.reroll_session(forecast, session))?;
+        self.record_variety(&rerolled, &[session])?;
+        Ok(rerolled)
+    }
+
+    /// Remembers the weather groups `sessions` of an accepted forecast used, so `variety_mode`
+    /// holds them back in the next forecasts. Rejected candidates must not be recorded, otherwise
+    /// groups come back before the user saw anything else.
+    fn record_variety(
+        &mut self,
+        forecast: &WeatherForecast,
+        sessions: &[Sessions],
+    ) -> Result<(), ForecastError> {
+        if self.variety.is_none() {
+            return Ok(());
+        }
+        for session in sessions
+            .iter()
+            .filter(|session| forecast.forecast.contains_key(session))
+        {
+            let entries = self.forecast_entries(forecast, *session)?;
+            if let Some(variety) = &mut self.variety {
+                variety.record(*session, &self.catalog, &entries);
+            }
         }
+        Ok(())
+    }
+
+    fn satisfy_constraints(
+        &mut self,
+        mut generate: impl FnMut(&mut Self) -> Result<WeatherForecast, ForecastError>,
+    ) -> Result<WeatherForecast, ForecastError> {
+        if self.constraints.is_empty() {
+            return generate(self);
+        }
+
+        let mut violations: Vec<(String, usize)> = Vec::new();
+        for _ in 0..self.constraints.max_attempts {
+            let forecast = generate(self)?;
+            let mut slots = HashMap::new();
+            for session in forecast.forecast.keys() {
+                let entries = self.forecast_entries(&forecast, *session)?;
+                slots.insert(
+                    *session,
+                    entries.iter().map(|index| &self.catalog[*index]).collect(),
+                );
+            }
+
+            let failed = self.constraints.violations(&slots);
+            if failed.is_empty() {
+                return Ok(forecast);
+            }
+            tracing::debug!("Rejected a forecast violating {}", failed.join(", "));
+            for description in failed {
+                match violations
+                    .iter_mut()
+                    .find(|(existing, _)| *existing == description)
+                {
+                    Some((_, count)) => *count += 1,
+                    None => violations.push((description, 1)),
+                }
+            }
+        }
+
+        Err(ForecastError::UnsatisfiableConstraints {
+            attempts: self.constraints.max_attempts,
+            violations: violations
+                .into_iter()
+                .map(|(description, count)| format!("{description} ({count}x)"))
+                .collect(),
+        })
+    }
+
+    fn reroll_session(
+        &mut self,
+        forecast: &WeatherForecast,
+        session: Sessions,
+    ) -> Result<WeatherForecast, ForecastError> {
+        let mut kept = HashMap::new();
+        for other in Sessions::iter().filter(|other| *other != session) {
+            if forecast.forecast.contains_key(&other) {
+                kept.insert(other, self.forecast_entries(forecast, other)?);
+            }
+        }
+
+        let leader_rain = kept
+            .get(&self.coupling.leader)
+            .and_then(|leader| self.heaviest_rain(leader));
+        let entries = if let Some(weekend) = self.weekend.clone() {
+            // the kept sessions of the same day tell its regime
+            let day = weekend.day(session);
+            let mut same_day = kept
+                .iter()
+                .filter(|(other, _)| weekend.day(**other) == day)
+                .peekable();
+            let regime = if same_day.peek().is_none() {
+                weekend.regimes(&mut self.rng)[&day]
+            } else if same_day.any(|(_, entries)| self.heaviest_rain(entries).is_some()) {
+                Regime::Wet
+            } else {
+                Regime::Dry
+            };
+            self.generate_session_in_regime(session, regime)?
+        } else if session == self.coupling.leader {
+            self.reroll_leader(&kept)?
+        } else {
+            self.generate_coupled_session(session, leader_rain)?
+        };
+
+        let rerolled = self.build_forecast(HashMap::from([(session, entries)]));
+        Ok(forecast.clone().with_session(session, &rerolled))
+    }
+
+    /// Restores a forecast from the game strings of its weather slots, e.g. read from a settings
+    /// file.
+    pub fn restore_forecast(
+        &self,
+        game_strings: HashMap<Sessions, Vec<String>>,
+    ) -> Result<WeatherForecast, ForecastError> {
+        let mut forecast = WeatherForecast {
+            session_definitions: self.session_definitions.clone(),
+            ..WeatherForecast::default()
+        };
+        for (session, game_strings) in game_strings {
+            let options = game_strings
+                .iter()
+                .map(|game_string| {
+                    self.catalog
+                        .entries()
+                        .iter()
+                        .find(|entry| entry.game_string == *game_string)
+                        .map(|entry| entry.option)
+                        .ok_or_else(|| ForecastError::UnknownWeather(game_string.clone()))
+                })
+                .collect::<Result<_, _>>()?;
+            forecast.forecast.insert(session, options);
+            forecast.game_strings.insert(session, game_strings);
+        }
+        Ok(forecast)
+    }
+
+    fn reroll_leader(
+        &mut self,
+        kept: &HashMap<Sessions, Vec<usize>>,
+    ) -> Result<Vec<usize>, ForecastError> {
+        let leader = self.coupling.leader;
+        // previews get checked separately, they have to preview the rain of the leader
+        let follower_rain = kept
+            .iter()
+            .filter(|(session, _)| self.coupling.role(**session) == Role::Follower)
+            .any(|(_, entries)| self.heaviest_rain(entries).is_some());
+
+        for _ in 0..MAX_REROLL_ATTEMPTS {
+            let entries = self.generate_single_session_forecast(leader, true)?;
+            if !self.coupling.is_strict() {
+                return Ok(entries);
+            }
+
+            let leader_rain = self.heaviest_rain(&entries);
+            let fits_previews = kept
+                .iter()
+                .filter(|(session, _)| self.coupling.role(**session) == Role::Preview)
+                .all(|(_, preview)| match leader_rain {
+                    Some(leader_rain) => preview
+                        .iter()
+                        .any(|index| self.previews_rain(*index, leader_rain)),
+                    None => self.heaviest_rain(preview).is_none(),
+                });
+            if fits_previews && (leader_rain.is_some() || !follower_rain) {
+                return Ok(entries);
+            }
+        }
+        Err(ForecastError::RerollFailed(leader))
+    }
+
+    /// Catalog entries of the weather slots of a session of `forecast`.
+    fn forecast_entries(
+        &self,
+        forecast: &WeatherForecast,
+        session: Sessions,
+    ) -> Result<Vec<usize>, ForecastError> {
+        (0..forecast.forecast[&session].len())
+            .map(|index| {
+                let game_string = forecast.game_string(session, index);
+                self.catalog
+                    .entries()
+                    .iter()
+                    .position(|entry| entry.game_string == game_string)
+                    .or_else(|| self.catalog.position(forecast.forecast[&session][index]))
+                    .ok_or(ForecastError::UnknownWeather(game_string))
+            })
+            .collect()
+    }
+
+    /// The entry with the most intense rain, if any of `entries` is wet.
+    fn heaviest_rain(&self, entries: &[usize]) -> Option<usize> {
+        entries
+            .iter()
+            .max_by_key(|index| self.catalog[**index].rain_intensity)
+            .filter(|index| self.catalog[**index].rain_intensity > 0)
+            .copied()
+    }
+
+    /// Generates a session without rain on a dry day and with rain in at least one slot on a wet
+    /// day, as far as the probabilities allow any rain.
+    fn generate_session_in_regime(
+        &mut self,
+        session: Sessions,
+        regime: Regime,
+    ) -> Result<Vec<usize>, ForecastError> {
+        if regime == Regime::Dry {
+            return self.generate_single_session_forecast(session, false);
+        }
+        let rain_available = self.get_available_weather_options(&self.probabilities, true)
+            > self.get_available_weather_options(&self.probabilities, false);
+        let mut entries = self.generate_single_session_forecast(session, true)?;
+        for _ in 1..MAX_REROLL_ATTEMPTS {
+            if !rain_available || self.heaviest_rain(&entries).is_some() {
+                break;
+            }
+            entries = self.generate_single_session_forecast(session, true)?;
+        }
+        Ok(entries)
+    }
+
+    /// Generates a session other than the leader according to its role in the coupling.
+    fn generate_coupled_session(
+        &mut self,
+        session: Sessions,
+        leader_rain: Option<usize>,
+    ) -> Result<Vec<usize>, ForecastError> {
+        let role = self.coupling.role(session);
+        // only draw when needed so a full strength does not change the forecasts of a seed
+        let coupled =
+            self.coupling.strength >= 1.0 || self.rng.random::<f64>() < self.coupling.strength;
+        match role {
+            Role::Preview if coupled => self.generate_preview(session, leader_rain),
+            Role::Follower if coupled => {
+                self.generate_single_session_forecast(session, leader_rain.is_some())
+            }
+            _ => self.generate_single_session_forecast(session, true),
+        }
+    }
+
+    /// Generates a session that ends up with rain like the heaviest rain of the leader.
+    fn generate_preview(
+        &mut self,
+        session: Sessions,
+        leader_rain: Option<usize>,
+    ) -> Result<Vec<usize>, ForecastError> {
+        let preview_rain = leader_rain.map(|index| match self.coupling.preview_match {
+            PreviewMatch::Group => self.generate_entry_in_group(index),
+            PreviewMatch::Weather => index,
+        });
+        let mut preview = self.generate_single_session_forecast(session, leader_rain.is_some())?;
+        if let Some(preview_rain) = preview_rain {
+            *preview.last_mut().unwrap() = preview_rain;
+            // shuffling would break up the chain of transitions or move the start weather
+            if !self.chains_slots() {
+                let start = usize::from(self.start_weather.contains_key(&session));
+                preview[start..].shuffle(&mut self.rng);
+            }
+        }
+        Ok(preview)
+    }
+
+    /// Whether the entry at `index` is rain like `leader_rain` for a preview.
+    fn previews_rain(&self, index: usize, leader_rain: usize) -> bool {
+        match self.coupling.preview_match {
+            PreviewMatch::Group => self.catalog[index].group == self.catalog[leader_rain].group,
+            PreviewMatch::Weather => index == leader_rain,
+        }
+    }
+
+    fn build_forecast(&mut self, entries: HashMap<Sessions, Vec<usize>>) -> WeatherForecast {
+        let slot_durations: HashMap<Sessions, Vec<f64>> = entries
+            .iter()
+            .map(|(session, entries)| {
+                let durations = entries
+                    .iter()
+                    .map(|index| self.slot_duration(&self.catalog[*index].group))
+                    .collect();
+                (*session, durations)
+            })
+            .collect();
+
+        let mut forecast = WeatherForecast {
+            session_definitions: self.session_definitions.clone(),
+            ..WeatherForecast::default()
+        };
+        for (session, entries) in entries {
+            forecast.forecast.insert(
+                session,
+                entries
+                    .iter()
+                    .map(|index| self.catalog[*index].option)
+                    .collect(),
+            );
+            forecast.game_strings.insert(
+                session,
+                entries
+                    .iter()
+                    .map(|index| self.catalog[*index].game_string.clone())
+                    .collect(),
+            );
+        }
+
+        // temperatures and humidity
+        let warmth = self.rng.random();
+        for session in Sessions::iter() {
+            let Some(options) = forecast.forecast.get(&session) else {
+                continue;
+            };
+            let daylight = if self.night_sessions.contains(&session) {
+                0.0
+            } else {
+                1.0
+            };
+            let conditions = options
+                .iter()
+                .map(|option| self.generate_slot_conditions(warmth, *option, daylight))
+                .collect();
+            forecast.conditions.insert(session, conditions);
+        }
+
+        if self.slot_start_times {
+            for session in Sessions::iter() {
+                let Some(durations) = slot_durations.get(&session) else {
+                    continue;
+                };
+                let timings = self.generate_slot_timings(session, durations);
+                forecast.slot_timings.insert(session, timings);
+            }
+        }
+
+        if self.track_wetness {
+            forecast.track_wetness = forecast.starting_wetness().into_iter().collect();
+        }
+
+        forecast
+    }
+
+    /// Relative duration of the slots of `group`.
+    fn slot_duration(&self, group: &str) -> f64 {
+        self.slot_durations
+            .get(group)
+            .copied()
+            .or_else(|| {
+                BUILT_IN_SLOT_DURATIONS
+                    .iter()
+                    .find(|(name, _)| *name == group)
+                    .map(|(_, duration)| *duration)
+            })
+            .unwrap_or(1.0)
+    }
+
+    /// Splits the session into slots of random length. The relative duration of the group of a
+    /// slot gets varied by up to 50% in both directions.
+    fn generate_slot_timings(&mut self, session: Sessions, durations: &[f64]) -> Vec<SlotTiming> {
+        let lengths: Vec<f64> = durations
+            .iter()
+            .map(|duration| duration * (0.5 + self.rng.random::<f64>()))
+            .collect();
+        let total: f64 = lengths.iter().sum();
+        let clock = self
+            .start_times
+            .get(&session)
+            .zip(self.session_lengths.get(&session));
+
+        let mut elapsed = 0.0;
+        lengths
+            .iter()
+            .map(|length| {
+                let start = (elapsed / total).round_to_decimal_place(3);
+                elapsed += length;
+                SlotTiming {
+                    start,
+                    duration: (length / total).round_to_decimal_place(3),
+                    time: clock.map(|(start_time, minutes)| {
+                        start_time.add_minutes((start * f64::from(*minutes)).round() as u32)
+                    }),
+                }
+            })
+            .collect()
+    }
+
+    /// Temperatures, humidity and clouds of a slot, `daylight` is `0.0` in night sessions.
+    fn generate_slot_conditions(
+        &mut self,
+        warmth: f64,
+        option: WeatherOptions,
+        daylight: f64,
+    ) -> SlotConditions {
+        let (ambient_temperature, track_temperature) = self
+            .temperature
+            .generate_temperatures_in_daylight(warmth, option, daylight, &mut self.rng);
+        let humidity = humidity::generate_humidity(option, &mut self.rng);
+        let (min, max) = option.cloud_cover_range();
+
+        SlotConditions {
+            ambient_temperature,
+            track_temperature,
+            humidity,
+            dew_point: humidity::dew_point(ambient_temperature, humidity),
+            cloud_cover: self.rng.random_range(min..=max).round_to_decimal_place(0),
+        }
+    }
+
+    /// Generates the catalog entries of all weather slots of a session.
+    fn generate_single_session_forecast(
+        &mut self,
+        session: Sessions,
+        might_rain: bool,
+    ) -> Result<Vec<usize>, ForecastError> {
+        let weather_slots = *self.weather_slots.get(&session).unwrap();
+        // variety_mode holds back the weather groups of the previous sessions of this kind
+        let varied = self
+            .variety
+            .as_ref()
+            .and_then(|variety| {
+                let sampler = self
+                    .session_probabilities
+                    .get(&session)
+                    .unwrap_or(&self.sampler);
+                variety.probabilities(session, &self.catalog, sampler.probabilities(), might_rain)
+            })
+            .map(|probabilities| WeatherSampler::new(&self.catalog, probabilities));
+        let available = self.get_available_weather_options(
+            varied
+                .as_ref()
+                .map_or(self.probabilities.as_slice(), WeatherSampler::probabilities),
+            might_rain,
+        );
+
+        let mut entries = Vec::new();
+        if let Some(start) = self.start_weather.get(&session)
+            && (might_rain || self.catalog[*start].rain_intensity == 0)
+        {
+            entries.push(*start);
+        }
+        let mut drift = self.drift.as_ref().map(DriftConfig::start);
+        if let (Some(drift), Some(start)) = (&mut drift, entries.first()) {
+            drift.advance(&self.catalog[*start]);
+        }
+        while entries.len() < weather_slots {
+            // every slot gets different weather if there are enough options, otherwise (e.g. in
+            // endurance races with many slots) at least neighbouring slots differ
+            let mut excluded = if available >= weather_slots {
+                entries.as_slice()
+            } else if available > 1 {
+                &entries[entries.len().saturating_sub(1)..]
+            } else {
+                &[]
+            };
+            // realism may not leave a way out, weather repeats rather than changing implausibly
+            if let Some(previous) = entries.last().copied() {
+                let mut fallbacks = [&entries[entries.len() - 1..], &[]].into_iter();
+                while !self.has_plausible_successor(
+                    session,
+                    previous,
+                    varied.as_ref(),
+                    excluded,
+                    might_rain,
+                ) && let Some(fallback) = fallbacks.next()
+                {
+                    excluded = fallback;
+                }
+            }
+            let entry = self.generate_session_entry(
+                session,
+                entries.last().copied(),
+                varied.as_ref(),
+                drift.as_ref(),
+                excluded,
+                might_rain,
+            )?;
+            if !excluded.contains(&entry) {
+                entries.push(entry);
+                if let Some(drift) = &mut drift {
+                    drift.advance(&self.catalog[entry]);
+                }
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Whether realism allows any entry of `session` apart from the `excluded` ones to follow
+    /// `previous`, e.g. no haze is left to follow clear sky at night.
+    fn has_plausible_successor(
+        &self,
+        session: Sessions,
+        previous: usize,
+        varied: Option<&WeatherSampler>,
+        excluded: &[usize],
+        with_rain: bool,
+    ) -> bool {
+        let previous = self.catalog[previous].option;
+        let sampler = varied.unwrap_or_else(|| {
+            self.session_probabilities
+                .get(&session)
+                .unwrap_or(&self.sampler)
+        });
+        self.catalog
+            .entries()
+            .iter()
+            .zip(sampler.probabilities())
+            .enumerate()
+            .any(|(index, (entry, probability))| {
+                (entry.rain_intensity == 0 || with_rain)
+                    && *probability > 0.0
+                    && !excluded.contains(&index)
+                    && self.realism.allows(previous, entry.option)
+            })
+    }
+
+    fn get_available_weather_options(&self, probabilities: &[f64], with_rain: bool) -> usize {
+        self.catalog
+            .entries()
+            .iter()
+            .zip(probabilities)
+            .filter(|(entry, probability)| {
+                (entry.rain_intensity == 0 || with_rain) && **probability > 0.0
+            })
+            .count()
+    }
+
+    fn normalize_probabilities(&mut self) -> Result<(), ForecastError> {
+        let sum: f64 = self.probabilities.iter().sum();
+        if sum <= 0.0 {
+            return Err(ForecastError::NoProbabilities);
+        }
+        let factor = 1.0 / sum;
+        for probability in &mut self.probabilities {
+            *probability *= factor;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WeatherForecast {
+    pub(crate) forecast: HashMap<Sessions, Vec<WeatherOptions>>,
+    /// Game strings of the catalog entries, missing for forecasts not created by a forecaster
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub(crate) game_strings: HashMap<Sessions, Vec<String>>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub(crate) conditions: HashMap<Sessions, Vec<SlotConditions>>,
+    /// Start and duration of every weather slot, only generated when enabled in the config
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub(crate) slot_timings: HashMap<Sessions, Vec<SlotTiming>>,
+    /// Wetness of the track at the start of every session, only generated when enabled in the
+    /// config
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub(crate) track_wetness: HashMap<Sessions, f64>,
+    /// Sessions declared in the config, determine the order and names in the output
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) session_definitions: Vec<SessionDefinition>,
+}
+
+impl WeatherForecast {
+    /// Weather of the slots of every generated session.
+    pub fn forecast(&self) -> &HashMap<Sessions, Vec<WeatherOptions>> {
+        &self.forecast
+    }
+
+    /// Whether the forecast has weather for `session`.
+    pub fn contains(&self, session: Sessions) -> bool {
+        self.forecast.contains_key(&session)
+    }
+
+    /// Weather of the slots of the session, `None` if it was not generated.
+    pub fn slots(&self, session: Sessions) -> Option<&[WeatherOptions]> {
+        self.forecast.get(&session).map(Vec::as_slice)
+    }
+
+    /// Sessions with the weather of their slots, in the order of [`Self::sessions`].
+    pub fn iter(&self) -> impl Iterator<Item = (Sessions, &[WeatherOptions])> {
+        self.sessions()
+            .into_iter()
+            .map(|session| (session, self.forecast[&session].as_slice()))
+    }
+
+    /// Weather of the slots of every session, without the conditions and timings.
+    pub fn into_inner(self) -> HashMap<Sessions, Vec<WeatherOptions>> {
+        self.forecast
+    }
+
+    /// The forecast with `session` replaced by the one of `other`, including its conditions and
+    /// timings. Nothing changes if `other` does not have the session.
+    #[must_use]
+    pub fn with_session(mut self, session: Sessions, other: &WeatherForecast) -> Self {
+        let Some(options) = other.forecast.get(&session) else {
+            return self;
+        };
+        self.forecast.insert(session, options.clone());
+        // whatever belonged to the replaced slots does not fit the new ones
+        replace_session(&mut self.game_strings, &other.game_strings, session);
+        replace_session(&mut self.conditions, &other.conditions, session);
+        replace_session(&mut self.slot_timings, &other.slot_timings, session);
+        if let Some(definition) = other
+            .session_definitions
+            .iter()
+            .find(|definition| definition.session == session)
+            && !self
+                .session_definitions
+                .iter()
+                .any(|existing| existing.session == session)
+        {
+            self.session_definitions.push(definition.clone());
+        }
+        // later sessions start on a track the new weather left behind
+        if !self.track_wetness.is_empty() || !other.track_wetness.is_empty() {
+            self.track_wetness = self.starting_wetness().into_iter().collect();
+        }
+        self
+    }
+
+    /// Takes over every session of `other`, e.g. a freshly rerolled qualifying into a saved
+    /// forecast of the whole weekend.
+    pub fn merge(&mut self, other: &WeatherForecast) {
+        for session in other.sessions() {
+            *self = std::mem::take(self).with_session(session, other);
+        }
+    }
+
+    /// Sessions of the forecast, declared ones first in their declared order.
+    pub fn sessions(&self) -> Vec<Sessions> {
+        let declared = self
+            .session_definitions
+            .iter()
+            .map(|definition| definition.session);
+        let mut sessions: Vec<_> = declared.filter(|session| self.contains(*session)).collect();
+        for session in Sessions::iter() {
+            if self.contains(session) && !sessions.contains(&session) {
+                sessions.push(session);
+            }
+        }
+        sessions
+    }
+
+    /// Prefix of the keys of `session` in the game's settings.
+    pub(crate) fn key_prefix(&self, session: Sessions) -> String {
+        sessions::key_prefix(&self.session_definitions, session)
+    }
+
+    /// Name of `session` for humans.
+    pub(crate) fn session_name(&self, session: Sessions) -> String {
+        self.session_definitions
+            .iter()
+            .find(|definition| definition.session == session)
+            .map(|definition| definition.name.clone())
+            .unwrap_or_else(|| session.to_string())
+    }
+
+    /// Value written into the game's settings for the given slot.
+    pub(crate) fn game_string(&self, session: Sessions, index: usize) -> String {
+        self.game_strings
+            .get(&session)
+            .and_then(|game_strings| game_strings.get(index))
+            .cloned()
+            .unwrap_or_else(|| self.forecast[&session][index].to_string())
+    }
+
+    /// Lists the generated conditions of every weather slot in a human readable form.
+    pub fn conditions_report(&self) -> ConditionsReport<'_> {
+        ConditionsReport(self)
+    }
+}
+
+fn replace_session<T: Clone>(
+    target: &mut HashMap<Sessions, T>,
+    source: &HashMap<Sessions, T>,
+    session: Sessions,
+) {
+    match source.get(&session) {
+        Some(value) => target.insert(session, value.clone()),
+        None => target.remove(&session),
+    };
+}
+
+/// Physical conditions generated alongside the weather of a slot.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SlotConditions {
+    /// Air temperature in °C
+    pub ambient_temperature: f64,
+    /// Track surface temperature in °C
+    pub track_temperature: f64,
+    /// Relative humidity in percent
+    pub humidity: f64,
+    /// Dew point in °C
+    pub dew_point: f64,
+    /// Share of the sky covered by clouds in percent, missing in archives of older versions
+    #[serde(default)]
+    pub cloud_cover: f64,
+}
+
+/// When a weather slot begins and how long it lasts.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SlotTiming {
+    /// Share of the session that has passed when the slot begins, from `0.0` to `1.0`
+    pub start: f64,
+    /// Share of the session the slot lasts
+    pub duration: f64,
+    /// Clock time the slot begins, only known if the start time and length of the session are
+    /// configured
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time: Option<TimeOfDay>,
+}
+
+pub struct ConditionsReport<'a>(&'a WeatherForecast);
+
+impl std::fmt::Display for ConditionsReport<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (session, wetness) in self.0.starting_wetness() {
+            let name = self.0.session_name(session);
+            if wetness >= 0.05 {
+                writeln!(
+                    f,
+                    "{name} starts on a damp track, {:.0}% wet",
+                    wetness * 100.0
+                )?;
+            }
+            let Some(conditions) = self.0.conditions.get(&session) else {
+                continue;
+            };
+            for (index, conditions) in conditions.iter().enumerate() {
+                writeln!(
+                    f,
+                    "{:<18} {:<16} ambient {:>5.1}°C, track {:>5.1}°C, humidity {:>3.0}%, dew point {:>5.1}°C, clouds {:>3.0}%",
+                    format!("{name} slot {}:", index + 1),
+                    self.0.game_string(session, index),
+                    conditions.ambient_temperature,
+                    conditions.track_temperature,
+                    conditions.humidity,
+                    conditions.dew_point,
+                    conditions.cloud_cover,
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The alternate flag (`{:#}`) colors the weather for terminals.
+impl std::fmt::Display for WeatherForecast {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for session in self.sessions() {
+            let forecast = &self.forecast[&session];
+            let key = self.key_prefix(session);
+            writeln!(f, r#""{key}WeatherSlots": {},"#, forecast.len())?;
+            for (index, option) in forecast.iter().enumerate() {
+                let style = if f.alternate() {
+                    option.style()
+                } else {
+                    Style::new()
+                };
+                writeln!(
+                    f,
+                    r#""{key}WeatherSlot{}": "{style}{}{style:#}","#,
+                    index + 1,
+                    self.game_string(session, index)
+                )?;
+            }
+            if let Some(wetness) = self.track_wetness.get(&session) {
+                writeln!(
+                    f,
+                    r#""{key}TrackWetness": {},"#,
+                    wetness.round_to_decimal_place(2)
+                )?;
+            }
+            let timings = self.slot_timings.get(&session).into_iter().flatten();
+            for (index, timing) in timings.enumerate() {
+                writeln!(
+                    f,
+                    r#""{key}WeatherSlot{}Start": {},"#,
+                    index + 1,
+                    timing.start
+                )?;
+                writeln!(
+                    f,
+                    r#""{key}WeatherSlot{}Duration": {},"#,
+                    index + 1,
+                    timing.duration
+                )?;
+                if let Some(time) = timing.time {
+                    writeln!(f, r#""{key}WeatherSlot{}StartTime": "{time}","#, index + 1)?;
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+pub(crate) trait Round {
+    fn round_to_decimal_place(&self, decimal_places: i32) -> Self;
+}
+
+impl Round for f64 {
+    fn round_to_decimal_place(&self, decimal_places: i32) -> Self {
+        let factor = 10.0_f64.powi(decimal_places);
+        (self * factor).round() / factor
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use float_cmp::assert_approx_eq;
+    use strum::IntoEnumIterator;
+
+    use super::*;
+    use crate::{
+        catalog::WeatherDefinition,
+        presets::TrackPreset,
+        season::{Month, Season},
+        variety::ReintroductionConfig,
+    };
+
+    #[test]
+    fn sane_probabilities() {
+        let mut sum = 0.0;
+        for option in WeatherOptions::iter() {
+            sum += option.get_default_probabiliy();
+        }
+        assert_approx_eq!(f64, sum, 1.0, epsilon = 0.0001);
+    }
+
+    #[test]
+    fn options_picked_correctly() {
+        const NUMBER_OF_PICKS: usize = 100_000_000;
+        let mut picked_times: HashMap<WeatherOptions, usize> =
+            WeatherOptions::iter().map(|option| (option, 0)).collect();
+
+        let mut forecaster = WeatherForecaster::default();
+
+        for _ in 0..NUMBER_OF_PICKS {
+            let option = forecaster.generate_weather_option();
+            *picked_times.get_mut(&option).unwrap() += 1;
+        }
+
+        for (option, picks) in picked_times {
+            let real_probability = picks as f64 / NUMBER_OF_PICKS as f64;
+            let actual_probability = option.get_default_probabiliy();
+            println!("{option:?} should be {real_probability} and is {actual_probability}");
+            assert_approx_eq!(f64, real_probability, actual_probability, epsilon = 0.0005);
+        }
+    }
+
+    #[test]
+    fn invalid_probabilities_are_rejected() {
+        let mut config = Config::default();
+        config.probabilities.insert(WeatherOptions::Rain, -0.1);
+        assert!(matches!(
+            WeatherForecaster::new(config),
+            Err(ForecastError::InvalidProbability { .. })
+        ));
+
+        let mut config = Config {
+            probabilities: WeatherOptions::get_default_probablities(),
+            ..Config::default()
+        };
+        config.probabilities.values_mut().for_each(|p| *p = 0.0);
+        assert!(matches!(
+            WeatherForecaster::new(config),
+            Err(ForecastError::NoProbabilities)
+        ));
+    }
+
+    #[test]
+    fn explicit_probabilities_override_track_preset() {
+        let config = Config {
+            track: Some(TrackPreset::Sakhir),
+            probabilities: [(WeatherOptions::Rain, 0.5)].into_iter().collect(),
+            ..Config::default()
+        };
+        let forecaster = WeatherForecaster::new(config).unwrap();
+        let sakhir = TrackPreset::Sakhir.get_probabilities();
+        let sum = 0.5 + sakhir.values().sum::<f64>() - sakhir[&WeatherOptions::Rain];
+        assert_approx_eq!(
+            f64,
+            forecaster.get_probability(WeatherOptions::Rain),
+            0.5 / sum,
+            epsilon = 0.0001
+        );
+        assert_approx_eq!(
+            f64,
+            forecaster.get_probability(WeatherOptions::Clear),
+            sakhir[&WeatherOptions::Clear] / sum,
+            epsilon = 0.0001
+        );
+
+        // the default config sets no probabilities, so the preset is not overridden
+        let preset = WeatherForecaster::new(Config {
+            track: Some(TrackPreset::Sakhir),
+            ..Config::default()
+        })
+        .unwrap();
+        let defaults = WeatherForecaster::default();
+        assert!(
+            preset.get_probability(WeatherOptions::Rain)
+                < defaults.get_probability(WeatherOptions::Rain) / 2.0
+        );
+    }
+
+    #[test]
+    fn start_time_modulates_session_probabilities() {
+        let config = Config {
+            start_times: [(Sessions::Practice, "06:00".parse().unwrap())]
+                .into_iter()
+                .collect(),
+            ..Config::default()
+        };
+        let forecaster = WeatherForecaster::new(config).unwrap();
+        let practice = forecaster.session_probabilities[&Sessions::Practice].probabilities();
+        assert!(
+            practice[forecaster
+                .catalog
+                .position(WeatherOptions::HeavyFog)
+                .unwrap()]
+                > forecaster.get_probability(WeatherOptions::HeavyFog)
+        );
+        assert!(
+            practice[forecaster
+                .catalog
+                .position(WeatherOptions::Thunderstorm)
+                .unwrap()]
+                < forecaster.get_probability(WeatherOptions::Thunderstorm)
+        );
+        assert_approx_eq!(f64, practice.iter().sum::<f64>(), 1.0, epsilon = 0.0001);
+        assert!(
+            !forecaster
+                .session_probabilities
+                .contains_key(&Sessions::Race)
+        );
+    }
+
+    #[test]
+    fn custom_rng_is_used() {
+        let sessions = [Sessions::Race];
+        let first = WeatherForecaster::with_rng(Config::default(), StdRng::seed_from_u64(7))
+            .unwrap()
+            .generate_forecast(&sessions)
+            .unwrap();
+        let second = WeatherForecaster::default()
+            .with_seed(7)
+            .generate_forecast(&sessions)
+            .unwrap();
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn same_seed_same_forecast() {
+        let sessions = [Sessions::Practice, Sessions::Qualifying, Sessions::Race];
+        let first = WeatherForecaster::default()
+            .with_seed(42)
+            .generate_forecast(&sessions)
+            .unwrap();
+        let second = WeatherForecaster::default()
+            .with_seed(42)
+            .generate_forecast(&sessions)
+            .unwrap();
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn season_scales_probabilities() {
+        let config = Config {
+            month: Some(Month::July),
+            ..Config::default()
+        };
+        let summer = WeatherForecaster::new(config).unwrap();
+        let config = Config {
+            season: Some(Season::Autumn),
+            month: Some(Month::July),
+            ..Config::default()
+        };
+        let autumn = WeatherForecaster::new(config).unwrap();
+
+        let rain = WeatherOptions::Rain;
+        let storm = WeatherOptions::Thunderstorm;
+        assert!(summer.get_probability(rain) < autumn.get_probability(rain));
+        assert!(summer.get_probability(storm) > autumn.get_probability(storm));
+    }
+
+    #[test]
+    fn transitions_follow_the_matrix() {
+        // once it is dry it stays dry
+        let matrix = WeatherOptions::iter()
+            .filter(|from| from.rain_intensity() == 0)
+            .map(|from| {
+                let row = WeatherOptions::iter()
+                    .filter(|to| to.rain_intensity() > 0)
+                    .map(|to| (to, 0.0))
+                    .collect();
+                (from, row)
+            })
+            .collect();
+        let config = Config {
+            transitions: Some(TransitionConfig { matrix }),
+            ..Config::default()
+        };
+        let mut forecaster = WeatherForecaster::new(config).unwrap().with_seed(3);
+
+        for _ in 0..1000 {
+            let forecast = forecaster.generate_forecast(&[Sessions::Race]).unwrap();
+            for slots in forecast.forecast[&Sessions::Race].windows(2) {
+                assert!(slots[0].rain_intensity() > 0 || slots[1].rain_intensity() == 0);
+            }
+        }
+    }
+
+    #[test]
+    fn drift_makes_changes_gradual() {
+        let average_change = |drift| {
+            let config = Config {
+                drift,
+                ..Config::default()
+            };
+            let mut forecaster = WeatherForecaster::new(config).unwrap().with_seed(8);
+            let mut change = 0;
+            for _ in 0..500 {
+                let forecast = forecaster.generate_forecast(&[Sessions::Race]).unwrap();
+                for slots in forecast.forecast[&Sessions::Race].windows(2) {
+                    change += slots[0]
+                        .rain_intensity()
+                        .abs_diff(slots[1].rain_intensity());
+                }
+            }
+            change as f64 / 1500.0
+        };
+        assert!(average_change(Some(DriftConfig { momentum: 1.0 })) < 0.5 * average_change(None));
+
+        let config = Config {
+            drift: Some(DriftConfig { momentum: -0.1 }),
+            ..Config::default()
+        };
+        assert!(matches!(
+            WeatherForecaster::new(config),
+            Err(ForecastError::InvalidProbability { .. })
+        ));
+    }
+
+    #[test]
+    fn variety_mode_holds_back_the_previous_weather() {
+        let config = Config {
+            variety_mode: true,
+            reintroduction: ReintroductionConfig { factors: vec![0.0] },
+            ..Config::default()
+        };
+        let mut forecaster = WeatherForecaster::new(config).unwrap().with_seed(4);
+        let groups = WeatherGroups::default();
+        let mut previous: Vec<&str> = Vec::new();
+        for _ in 0..50 {
+            let forecast = forecaster.generate_forecast(&[Sessions::Race]).unwrap();
+            let used: Vec<&str> = forecast.forecast[&Sessions::Race]
+                .iter()
+                .map(|option| groups.get_group_name(*option))
+                .collect();
+            assert!(used.iter().all(|group| !previous.contains(group)));
+            previous = used;
+        }
+
+        let config = Config {
+            reintroduction: ReintroductionConfig {
+                factors: vec![-1.0],
+            },
+            ..Config::default()
+        };
+        assert!(WeatherForecaster::new(config).is_err());
+    }
+
+    #[test]
+    fn variety_mode_only_remembers_accepted_forecasts() {
+        let mut config = Config {
+            variety_mode: true,
+            reintroduction: ReintroductionConfig {
+                factors: vec![0.0, 0.0],
+            },
+            constraints: serde_json::from_str(r#"{"forbid": ["clear_practice"]}"#).unwrap(),
+            coupling: CouplingConfig {
+                enabled: false,
+                ..CouplingConfig::default()
+            },
+            ..Config::default()
+        };
+        config.weather_slots.insert(Sessions::Race, 1);
+        let mut forecaster = WeatherForecaster::new(config).unwrap().with_seed(6);
+        let groups = WeatherGroups::default();
+        let mut previous: Vec<&str> = Vec::new();
+        for _ in 0..50 {
+            let forecast = forecaster
+                .generate_constrained_forecast(&[Sessions::Practice, Sessions::Race])
+                .unwrap();
+            let group = groups.get_group_name(forecast.forecast[&Sessions::Race][0]);
+            assert!(!previous.contains(&group));
+            previous.insert(0, group);
+            previous.truncate(2);
+        }
+    }
+
+    #[test]
+    fn sessions_share_the_regime_of_their_day() {
+        let wet = |forecast: &WeatherForecast, session| {
+            forecast.forecast[&session]
+                .iter()
+                .any(|option| option.rain_intensity() > 0)
+        };
+        let weekend = |wet_chance, persistence| Config {
+            weekend: Some(WeekendConfig {
+                wet_chance,
+                persistence,
+                ..WeekendConfig::default()
+            }),
+            ..Config::default()
+        };
+        let sessions = [
+            Sessions::Practice,
+            Sessions::Fp3,
+            Sessions::Qualifying,
+            Sessions::Race,
+        ];
+
+        let mut forecaster = WeatherForecaster::new(weekend(1.0, 0.0))
+            .unwrap()
+            .with_seed(1);
+        let forecast = forecaster.generate_forecast(&sessions).unwrap();
+        assert!(sessions.iter().all(|session| wet(&forecast, *session)));
+
+        let mut forecaster = WeatherForecaster::new(weekend(0.5, 1.0))
+            .unwrap()
+            .with_seed(1);
+        for _ in 0..20 {
+            let forecast = forecaster.generate_forecast(&sessions).unwrap();
+            let race = wet(&forecast, Sessions::Race);
+            assert!(
+                sessions
+                    .iter()
+                    .all(|session| wet(&forecast, *session) == race)
+            );
+        }
+
+        let mut forecaster = WeatherForecaster::new(weekend(0.5, 0.0))
+            .unwrap()
+            .with_seed(1);
+        for _ in 0..20 {
+            let forecast = forecaster.generate_forecast(&sessions).unwrap();
+            let saturday = wet(&forecast, Sessions::Fp3);
+            assert_eq!(wet(&forecast, Sessions::Qualifying), saturday);
+            let rerolled = forecaster.reroll(&forecast, Sessions::Qualifying).unwrap();
+            assert_eq!(wet(&rerolled, Sessions::Qualifying), saturday);
+        }
+    }
+
+    #[test]
+    fn strict_realism_only_allows_plausible_changes() {
+        let config = Config {
+            realism: Realism::Strict,
+            ..Config::default()
+        };
+        let mut forecaster = WeatherForecaster::new(config).unwrap().with_seed(5);
+
+        for _ in 0..1000 {
+            let forecast = forecaster.generate_forecast(&[Sessions::Race]).unwrap();
+            for slots in forecast.forecast[&Sessions::Race].windows(2) {
+                assert!(Realism::Strict.allows(slots[0], slots[1]));
+            }
+        }
+    }
+
+    #[test]
+    fn sessions_begin_with_their_start_weather() {
+        let config = Config {
+            start_weather: [(Sessions::Race, WeatherOptions::Storm)]
+                .into_iter()
+                .collect(),
+            ..Config::default()
+        };
+        let mut forecaster = WeatherForecaster::new(config).unwrap().with_seed(2);
+        for _ in 0..100 {
+            let forecast = forecaster
+                .generate_forecast(&[Sessions::Qualifying, Sessions::Race])
+                .unwrap();
+            assert_eq!(forecast.forecast[&Sessions::Race][0], WeatherOptions::Storm);
+        }
+    }
+
+    #[test]
+    fn previews_of_an_impossible_start_weather_repeat_it() {
+        let mut config = Config {
+            start_weather: [(Sessions::Race, WeatherOptions::Thunderstorm)]
+                .into_iter()
+                .collect(),
+            transitions: Some(TransitionConfig::default()),
+            probabilities: WeatherOptions::get_default_probablities(),
+            ..Config::default()
+        };
+        config.probabilities.insert(WeatherOptions::Storm, 0.0);
+        config
+            .probabilities
+            .insert(WeatherOptions::Thunderstorm, 0.0);
+        let mut forecaster = WeatherForecaster::new(config).unwrap().with_seed(4);
+        for _ in 0..20 {
+            let forecast = forecaster
+                .generate_forecast(&[Sessions::Practice, Sessions::Race])
+                .unwrap();
+            assert!(forecast.forecast[&Sessions::Practice].contains(&WeatherOptions::Thunderstorm));
+        }
+    }
+
+    #[test]
+    fn night_sessions_are_foggy_and_cold() {
+        let config = Config {
+            night_sessions: [Sessions::Race].into_iter().collect(),
+            realism: Realism::Strict,
+            ..Config::default()
+        };
+        let mut forecaster = WeatherForecaster::new(config).unwrap().with_seed(4);
+        let fog = |session| {
+            forecaster
+                .session_probabilities(session)
+                .filter(|(entry, _)| entry.option == WeatherOptions::Foggy)
+                .map(|(_, probability)| probability)
+                .sum::<f64>()
+        };
+        assert!(fog(Sessions::Race) > fog(Sessions::Qualifying));
+
+        let (mut night, mut day) = (0.0, 0.0);
+        for _ in 0..100 {
+            let forecast = forecaster
+                .generate_forecast(&[Sessions::Qualifying, Sessions::Race])
+                .unwrap();
+            assert!(!forecast.forecast[&Sessions::Race].contains(&WeatherOptions::Hazy));
+            let ambient = |session| {
+                forecast.conditions[&session]
+                    .iter()
+                    .map(|conditions| conditions.ambient_temperature)
+                    .sum::<f64>()
+                    / forecast.conditions[&session].len() as f64
+            };
+            night += ambient(Sessions::Race);
+            day += ambient(Sessions::Qualifying);
+        }
+        assert!(night < day);
+    }
+
+    #[test]
+    fn practice_rain_follows_custom_groups() {
+        let config = Config {
+            probabilities: [(WeatherOptions::Rain, 1.0), (WeatherOptions::Clear, 1.0)]
+                .into_iter()
+                .collect(),
+            groups: Some(
+                [(
+                    "mixed".to_string(),
+                    vec![WeatherOptions::Rain, WeatherOptions::Clear],
+                )]
+                .into_iter()
+                .collect(),
+            ),
+            ..Config::default()
+        };
+        let mut forecaster = WeatherForecaster::new(config).unwrap().with_seed(1);
+        let picks: Vec<_> = (0..100)
+            .map(|_| forecaster.generate_weather_option_in_group(WeatherOptions::Rain))
+            .collect();
+        assert!(picks.contains(&WeatherOptions::Clear));
+        assert!(picks.contains(&WeatherOptions::Rain));
+    }
+
+    #[test]
+    fn custom_catalog_is_used() {
+        let config = Config {
+            catalog: Some(vec![
+                WeatherDefinition {
+                    name: "Clear".to_string(),
+                    probability: Some(0.5),
+                    group: None,
+                    rain_intensity: None,
+                    game_string: None,
+                    like: None,
+                },
+                WeatherDefinition {
+                    name: "Sandstorm".to_string(),
+                    probability: Some(0.5),
+                    group: Some("desert".to_string()),
+                    rain_intensity: None,
+                    game_string: Some("SAND".to_string()),
+                    like: Some(WeatherOptions::Hazy),
+                },
+            ]),
+            ..Config::default()
+        };
+        let mut forecaster = WeatherForecaster::new(config).unwrap().with_seed(0);
+        let forecast = forecaster
+            .generate_forecast(&[Sessions::Qualifying])
+            .unwrap();
+
+        let mut game_strings = forecast.game_strings[&Sessions::Qualifying].clone();
+        game_strings.sort();
+        assert_eq!(game_strings, ["Clear", "SAND"]);
+        assert!(forecast.to_string().contains(r#""SAND""#));
+    }
+
+    #[test]
+    fn reroll_keeps_other_sessions_and_rain_coupling() {
+        let sessions = [Sessions::Practice, Sessions::Qualifying, Sessions::Race];
+        for seed in 0..50 {
+            let mut forecaster = WeatherForecaster::default().with_seed(seed);
+            let forecast = forecaster.generate_forecast(&sessions).unwrap();
+
+            for session in sessions {
+                let rerolled = forecaster.reroll(&forecast, session).unwrap();
+                for other in sessions.iter().filter(|other| **other != session) {
+                    assert_eq!(rerolled.forecast[other], forecast.forecast[other]);
+                    assert_eq!(rerolled.conditions[other], forecast.conditions[other]);
+                }
+
+                let is_wet = |session| {
+                    rerolled.forecast[&session]
+                        .iter()
+                        .any(|option: &WeatherOptions| option.rain_intensity() > 0)
+                };
+                assert_eq!(is_wet(Sessions::Practice), is_wet(Sessions::Race));
+                assert!(!is_wet(Sessions::Qualifying) || is_wet(Sessions::Race));
+            }
+        }
+    }
+
+    #[test]
+    fn reroll_falls_back_to_the_rain_of_a_kept_session() {
+        let mut forecast = WeatherForecast::default();
+        forecast
+            .forecast
+            .insert(Sessions::Practice, vec![WeatherOptions::Clear]);
+        forecast.forecast.insert(
+            Sessions::Race,
+            vec![WeatherOptions::Clear, WeatherOptions::Storm],
+        );
+        let mut config = Config {
+            probabilities: WeatherOptions::get_default_probablities(),
+            ..Config::default()
+        };
+        config.probabilities.insert(WeatherOptions::Storm, 0.0);
+        config
+            .probabilities
+            .insert(WeatherOptions::Thunderstorm, 0.0);
+        let mut forecaster = WeatherForecaster::new(config).unwrap().with_seed(5);
+
+        let rerolled = forecaster.reroll(&forecast, Sessions::Practice).unwrap();
+        assert!(rerolled.forecast[&Sessions::Practice].contains(&WeatherOptions::Storm));
+        assert_eq!(
+            rerolled.forecast[&Sessions::Race],
+            forecast.forecast[&Sessions::Race]
+        );
+    }
+
+    #[test]
+    fn set_probability_keeps_ratios() {
+        let mut forecaster = WeatherForecaster::default();
+        let clear = forecaster.get_probability(WeatherOptions::Clear);
+        let hazy = forecaster.get_probability(WeatherOptions::Hazy);
+
+        forecaster
+            .set_probability(WeatherOptions::Rain, 0.5)
+            .unwrap();
+        assert_approx_eq!(
+            f64,
+            forecaster.get_probability(WeatherOptions::Rain),
+            0.5,
+            epsilon = 1e-9
+        );
+        assert_approx_eq!(
+            f64,
+            forecaster.get_probability(WeatherOptions::Clear)
+                / forecaster.get_probability(WeatherOptions::Hazy),
+            clear / hazy,
+            epsilon = 1e-9
+        );
+        assert!(
+            forecaster
+                .set_probability(WeatherOptions::Rain, 1.5)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn constraints_are_satisfied() {
+        let sessions = [Sessions::Practice, Sessions::Qualifying, Sessions::Race];
+        let config = Config {
+            constraints: serde_json::from_value(serde_json::json!({
+                "require": ["wet_race"],
+                "forbid": ["thunderstorm_in_quali", "dry_practice"],
+                "at_least_one": ["fog"],
+            }))
+            .unwrap(),
+            ..Config::default()
+        };
+        let mut forecaster = WeatherForecaster::new(config).unwrap().with_seed(4);
+        for _ in 0..20 {
+            let forecast = forecaster.generate_constrained_forecast(&sessions).unwrap();
+            let options = |session| forecast.forecast[&session].iter();
+            assert!(options(Sessions::Race).any(|option| option.rain_intensity() > 0));
+            assert!(options(Sessions::Practice).any(|option| option.rain_intensity() > 0));
+            assert!(
+                !options(Sessions::Qualifying)
+                    .any(|option| *option == WeatherOptions::Thunderstorm)
+            );
+            assert!(
+                sessions
+                    .iter()
+                    .flat_map(|session| options(*session))
+                    .any(|option| {
+                        [
+                            WeatherOptions::Foggy,
+                            WeatherOptions::HeavyFog,
+                            WeatherOptions::Hazy,
+                        ]
+                        .contains(option)
+                    })
+            );
+        }
+
+        let config = Config {
+            constraints: serde_json::from_str(r#"{"require": ["wet_race", "dry_race"]}"#).unwrap(),
+            ..Config::default()
+        };
+        let error = WeatherForecaster::new(config)
+            .unwrap()
+            .generate_constrained_forecast(&sessions)
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            ForecastError::UnsatisfiableConstraints { attempts: 1000, .. }
+        ));
+
+        let config = Config {
+            constraints: serde_json::from_str(r#"{"require": ["snow"]}"#).unwrap(),
+            ..Config::default()
+        };
+        assert!(WeatherForecaster::new(config).is_err());
+    }
+
+    #[test]
+    fn rain_chance_is_capped_per_session() {
+        let config = Config {
+            probabilities: [(WeatherOptions::Rain, 0.5), (WeatherOptions::Clear, 0.5)]
+                .into_iter()
+                .collect(),
+            max_rain_chance: [(Sessions::Qualifying, 0.1)].into_iter().collect(),
+            ..Config::default()
+        };
+        let forecaster = WeatherForecaster::new(config).unwrap();
+        let quali = forecaster.session_probabilities[&Sessions::Qualifying].probabilities();
+        let rain = forecaster.catalog.position(WeatherOptions::Rain).unwrap();
+        let clear = forecaster.catalog.position(WeatherOptions::Clear).unwrap();
+        assert_approx_eq!(f64, quali[rain], 0.1, epsilon = 1e-9);
+        assert_approx_eq!(f64, quali[clear], 0.9, epsilon = 1e-9);
+        assert!(
+            !forecaster
+                .session_probabilities
+                .contains_key(&Sessions::Race)
+        );
+        assert!(forecaster.has_session_probabilities(Sessions::Qualifying));
+        let (_, race_rain) = forecaster
+            .session_probabilities(Sessions::Race)
+            .nth(rain)
+            .unwrap();
+        assert_approx_eq!(f64, race_rain, 0.5, epsilon = 1e-9);
+        assert!(
+            forecaster
+                .probability_table(Some(Sessions::Qualifying))
+                .lines()
+                .any(|line| line.starts_with("Rain ") && line.ends_with(": 10%"))
+        );
+
+        let config = Config {
+            max_rain_chance: [(Sessions::Race, 1.5)].into_iter().collect(),
+            ..Config::default()
+        };
+        assert!(WeatherForecaster::new(config).is_err());
+
+        let config = Config {
+            probabilities: [(WeatherOptions::Rain, 1.0)].into_iter().collect(),
+            max_rain_chance: [(Sessions::Race, 0.2)].into_iter().collect(),
+            ..Config::default()
+        };
+        assert!(matches!(
+            WeatherForecaster::new(config),
+            Err(ForecastError::RainChanceCapUnreachable {
+                session: Sessions::Race,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn slot_timings_are_generated() {
+        let config = Config {
+            slot_start_times: true,
+            start_times: [(Sessions::Race, "14:00".parse().unwrap())]
+                .into_iter()
+                .collect(),
+            session_lengths: [(Sessions::Race, 60)].into_iter().collect(),
+            ..Config::default()
+        };
+        let mut forecaster = WeatherForecaster::new(config).unwrap().with_seed(4);
+        let forecast = forecaster
+            .generate_forecast(&[Sessions::Qualifying, Sessions::Race])
+            .unwrap();
+
+        let race = &forecast.slot_timings[&Sessions::Race];
+        assert_eq!(race.len(), 4);
+        assert_eq!(race[0].start, 0.0);
+        assert_eq!(race[0].time, Some("14:00".parse().unwrap()));
+        assert!(race.windows(2).all(|pair| pair[0].start < pair[1].start));
+        assert!(race.iter().all(|timing| timing.start < 1.0));
+        assert!(
+            forecast.slot_timings[&Sessions::Qualifying]
+                .iter()
+                .all(|timing| timing.time.is_none())
+        );
+
+        let printed = forecast.to_string();
+        assert!(printed.contains(r#""RaceWeatherSlot1Start": 0,"#));
+        assert!(printed.contains(r#""RaceWeatherSlot1StartTime": "14:00","#));
+        assert!(!printed.contains("QualifyingWeatherSlot1StartTime"));
+
+        let forecast = WeatherForecaster::default()
+            .with_seed(4)
+            .generate_forecast(&[Sessions::Race])
+            .unwrap();
+        assert!(forecast.slot_timings.is_empty());
+    }
+
+    #[test]
+    fn slot_durations_follow_the_groups() {
+        let config = Config {
+            slot_start_times: true,
+            slot_durations: [("storm".to_string(), 0.1), ("dry".to_string(), 10.0)]
+                .into_iter()
+                .collect(),
+            ..Config::default()
+        };
+        let mut forecaster = WeatherForecaster::new(config).unwrap().with_seed(5);
+        for _ in 0..50 {
+            let forecast = forecaster.generate_forecast(&[Sessions::Race]).unwrap();
+            let options = &forecast.forecast[&Sessions::Race];
+            let timings = &forecast.slot_timings[&Sessions::Race];
+            let total: f64 = timings.iter().map(|timing| timing.duration).sum();
+            assert!((total - 1.0).abs() < 0.01);
+
+            for (index, timing) in timings.iter().enumerate() {
+                let end = timings.get(index + 1).map_or(1.0, |next| next.start);
+                assert!((timing.start + timing.duration - end).abs() < 0.01);
+            }
+            let storm = options
+                .iter()
+                .position(|option| option.get_group() == WeatherOptions::Storm.get_group());
+            let dry = options
+                .iter()
+                .position(|option| option.get_group() == WeatherOptions::Clear.get_group());
+            if let (Some(storm), Some(dry)) = (storm, dry) {
+                assert!(timings[storm].duration < timings[dry].duration);
+            }
+        }
+
+        let config = Config {
+            slot_durations: [("sunshine".to_string(), 1.0)].into_iter().collect(),
+            ..Config::default()
+        };
+        assert!(matches!(
+            WeatherForecaster::new(config),
+            Err(ForecastError::UnknownGroup(_))
+        ));
+        let config = Config {
+            slot_durations: [("storm".to_string(), 0.0)].into_iter().collect(),
+            ..Config::default()
+        };
+        assert!(matches!(
+            WeatherForecaster::new(config),
+            Err(ForecastError::InvalidSlotDuration { .. })
+        ));
+    }
+
+    #[test]
+    fn endurance_races_get_more_slots() {
+        let config = Config {
+            weather_slots: [(Sessions::Race, 24)].into_iter().collect(),
+            ..Config::default()
+        };
+        let mut forecaster = WeatherForecaster::new(config.clone()).unwrap().with_seed(6);
+        assert_eq!(
+            forecaster
+                .generate_forecast(&[Sessions::Race])
+                .unwrap()
+                .forecast[&Sessions::Race]
+                .len(),
+            DEFAULT_MAX_WEATHER_SLOTS
+        );
+
+        let config = Config {
+            max_weather_slots: Some(24),
+            ..config
+        };
+        let mut forecaster = WeatherForecaster::new(config.clone()).unwrap().with_seed(6);
+        for _ in 0..20 {
+            let forecast = forecaster.generate_forecast(&[Sessions::Race]).unwrap();
+            let race = &forecast.forecast[&Sessions::Race];
+            assert_eq!(race.len(), 24);
+            assert!(race.windows(2).all(|pair| pair[0] != pair[1]));
+        }
+
+        let config = Config {
+            max_weather_slots: Some(0),
+            ..config
+        };
+        assert!(matches!(
+            WeatherForecaster::new(config),
+            Err(ForecastError::InvalidMaxWeatherSlots(0))
+        ));
+    }
+
+    #[test]
+    fn weekend_sessions_follow_the_race() {
+        let sessions: Vec<_> = Sessions::iter().collect();
+        let mut forecaster = WeatherForecaster::default().with_seed(7);
+        for _ in 0..100 {
+            let forecast = forecaster.generate_forecast(&sessions).unwrap();
+            for session in &sessions {
+                assert_eq!(
+                    forecast.forecast[session].len(),
+                    session.default_weather_slots()
+                );
+            }
+            let wet = |session| {
+                forecast.forecast[&session]
+                    .iter()
+                    .any(|option| option.rain_intensity() > 0)
+            };
+            if !wet(Sessions::Race) {
+                assert!(sessions.iter().all(|session| !wet(*session)));
+            }
+        }
+
+        let forecast = forecaster
+            .generate_forecast(&[Sessions::Fp2, Sessions::Warmup])
+            .unwrap();
+        let printed = forecast.to_string();
+        assert!(printed.contains(r#""Practice2WeatherSlots": 4,"#));
+        assert!(printed.contains(r#""WarmupWeatherSlots": 1,"#));
+        assert!(!printed.contains("RaceWeatherSlots"));
+    }
+
+    #[test]
+    fn declared_sessions_set_order_and_keys() {
+        let config = Config {
+            sessions: serde_json::from_value(serde_json::json!([
+                {"name": "Race 1", "session": "Sprint", "key_prefix": "RaceOne", "weather_slots": 3},
+                {"name": "Race 2", "session": "Race", "key_prefix": "RaceTwo"},
+            ]))
+            .unwrap(),
+            ..Config::default()
+        };
+        let mut forecaster = WeatherForecaster::new(config.clone()).unwrap().with_seed(8);
+        let forecast = forecaster
+            .generate_forecast(&[Sessions::Race, Sessions::Sprint])
+            .unwrap();
+        assert_eq!(forecast.sessions(), [Sessions::Sprint, Sessions::Race]);
+        assert_eq!(forecast.forecast[&Sessions::Sprint].len(), 3);
+
+        let printed = forecast.to_string();
+        let first = printed.find(r#""RaceOneWeatherSlots": 3,"#).unwrap();
+        let second = printed.find(r#""RaceTwoWeatherSlots": 4,"#).unwrap();
+        assert!(first < second);
+        assert!(
+            forecast
+                .conditions_report()
+                .to_string()
+                .starts_with("Race 1 slot 1:")
+        );
+
+        let game_strings = crate::apply::read_forecast(&printed, &config.sessions).unwrap();
+        assert_eq!(
+            forecaster.restore_forecast(game_strings).unwrap().forecast,
+            forecast.forecast
+        );
+    }
+
+    #[test]
+    fn coupling_can_be_configured() {
+        let wet = |forecast: &WeatherForecast, session| {
+            forecast.forecast[&session]
+                .iter()
+                .any(|option| option.rain_intensity() > 0)
+        };
+        let sessions = [Sessions::Practice, Sessions::Qualifying, Sessions::Race];
+        let probabilities: HashMap<_, _> = [(WeatherOptions::Rain, 0.3)].into_iter().collect();
+
+        let config = Config {
+            probabilities: probabilities.clone(),
+            coupling: CouplingConfig {
+                enabled: false,
+                ..CouplingConfig::default()
+            },
+            ..Config::default()
+        };
+        let mut forecaster = WeatherForecaster::new(config).unwrap().with_seed(9);
+        let independent = (0..1000)
+            .map(|_| forecaster.generate_forecast(&sessions).unwrap())
+            .any(|forecast| {
+                !wet(&forecast, Sessions::Race) && wet(&forecast, Sessions::Qualifying)
+            });
+        assert!(independent);
+
+        let config = Config {
+            probabilities,
+            coupling: CouplingConfig {
+                leader: Sessions::Qualifying,
+                previews: vec![Sessions::Race],
+                preview_match: PreviewMatch::Weather,
+                ..CouplingConfig::default()
+            },
+            ..Config::default()
+        };
+        let mut forecaster = WeatherForecaster::new(config).unwrap().with_seed(9);
+        for _ in 0..100 {
+            let forecast = forecaster.generate_forecast(&sessions).unwrap();
+            let quali = &forecast.forecast[&Sessions::Qualifying];
+            match quali.iter().max_by_key(|option| option.rain_intensity()) {
+                Some(rain) if rain.rain_intensity() > 0 => {
+                    assert!(forecast.forecast[&Sessions::Race].contains(rain));
+                }
+                _ => {
+                    assert!(!wet(&forecast, Sessions::Race));
+                    assert!(!wet(&forecast, Sessions::Practice));
+                }
+            }
+            let rerolled = forecaster.reroll(&forecast, Sessions::Qualifying).unwrap();
+            assert_eq!(
+                rerolled.forecast[&Sessions::Race],
+                forecast.forecast[&Sessions::Race]
+            );
+        }
+    }
+
+    #[test]
+    fn forecast_round_trips_through_serde() {
+        let config = Config {
+            slot_start_times: true,
+            sessions: serde_json::from_str(r#"[{"name": "Feature", "session": "Race"}]"#).unwrap(),
+            ..Config::default()
+        };
+        let mut forecaster = WeatherForecaster::new(config).unwrap().with_seed(10);
+        let forecast = forecaster
+            .generate_forecast(&[Sessions::Qualifying, Sessions::Race])
+            .unwrap();
+
+        let json = serde_json::to_string(&forecast).unwrap();
+        assert_eq!(
+            serde_json::from_str::<WeatherForecast>(&json).unwrap(),
+            forecast
+        );
+        #[cfg(feature = "yaml")]
+        {
+            let yaml = serde_yaml::to_string(&forecast).unwrap();
+            assert_eq!(
+                serde_yaml::from_str::<WeatherForecast>(&yaml).unwrap(),
+                forecast
+            );
+        }
+
+        let minimal: WeatherForecast =
+            serde_json::from_str(r#"{"forecast": {"Race": ["Clear", "Rain"]}}"#).unwrap();
+        assert_eq!(
+            minimal.forecast()[&Sessions::Race],
+            [WeatherOptions::Clear, WeatherOptions::Rain]
+        );
+        assert_eq!(minimal.game_string(Sessions::Race, 1), "Rain");
+    }
+
+    #[test]
+    fn probability_history_is_recorded() {
+        let mut forecaster = WeatherForecaster::default().with_seed(1);
+        forecaster.generate_forecast(&[Sessions::Race]).unwrap();
+        assert!(forecaster.probability_history().is_none());
+
+        let mut forecaster = forecaster.with_probability_history();
+        forecaster.generate_forecast(&[Sessions::Race]).unwrap();
+        forecaster
+            .set_probability(WeatherOptions::Clear, 0.5)
+            .unwrap();
+        let history = forecaster.probability_history().unwrap();
+        assert_eq!(history.len(), WeatherOptions::iter().len());
+        let clear = &history[&WeatherOptions::Clear];
+        // at least one step per slot, more if a slot had to be picked again
+        assert!(clear.len() >= 6);
+        assert_approx_eq!(f64, clear[0], 2.4 / 14.0, epsilon = 1e-9);
+        assert_approx_eq!(f64, *clear.last().unwrap(), 0.5, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn dry_sessions_pick_from_dry_weather_only() {
+        let probabilities: HashMap<WeatherOptions, f64> = WeatherOptions::iter()
+            .map(|option| {
+                let probability = match option {
+                    WeatherOptions::Clear => 0.001,
+                    option if option.rain_intensity() > 0 => 1.0,
+                    _ => 0.0,
+                };
+                (option, probability)
+            })
+            .collect();
+        let config = Config {
+            probabilities,
+            ..Config::default()
+        };
+        let mut forecaster = WeatherForecaster::new(config).unwrap().with_seed(2);
+        let forecast = forecaster
+            .generate_forecast(&[Sessions::Qualifying])
+            .unwrap();
+        assert!(
+            forecast.forecast[&Sessions::Qualifying]
+                .iter()
+                .all(|option| *option == WeatherOptions::Clear)
+        );
+
+        forecaster
+            .set_probability(WeatherOptions::Clear, 0.0)
+            .unwrap();
+        assert!(matches!(
+            forecaster.generate_forecast(&[Sessions::Qualifying]),
+            Err(ForecastError::NoDryWeather {
+                session: Sessions::Qualifying,
+                leader: Sessions::Race,
+            })
+        ));
+        assert!(forecaster.generate_forecast(&[Sessions::Race]).is_ok());
+    }
+
+    #[test]
+    fn cloud_cover_matches_the_weather() {
+        let forecast = WeatherForecaster::new(Config::default())
+            .unwrap()
+            .with_seed(3)
+            .generate_forecast(&[Sessions::Race])
+            .unwrap();
+        for (option, conditions) in forecast.forecast[&Sessions::Race]
+            .iter()
+            .zip(&forecast.conditions[&Sessions::Race])
+        {
+            let (min, max) = option.cloud_cover_range();
+            assert!((min..=max).contains(&conditions.cloud_cover));
+        }
+    }
+
+    #[test]
+    fn weather_is_colored_on_request() {
+        let mut forecast = WeatherForecast::default();
+        forecast.forecast.insert(
+            Sessions::Race,
+            vec![WeatherOptions::Clear, WeatherOptions::Rain],
+        );
+        assert!(!forecast.to_string().contains('\x1b'));
+        let colored = format!("{forecast:#}");
+        let rain = WeatherOptions::Rain.style();
+        assert!(colored.contains(&format!(r#""RaceWeatherSlot2": "{rain}Rain{rain:#}","#)));
+
+        let forecaster = WeatherForecaster::new(Config::default()).unwrap();
+        assert!(!forecaster.probability_table(None).contains('\x1b'));
+        assert!(forecaster.colored_probability_table(None).contains('\x1b'));
+    }
+
+    #[test]
+    fn forecast_can_be_read() {
+        let forecast = WeatherForecaster::new(Config::default())
+            .unwrap()
+            .with_seed(2)
+            .generate_forecast(&[Sessions::Race, Sessions::Qualifying])
+            .unwrap();
+        assert_eq!(forecast.sessions(), [Sessions::Qualifying, Sessions::Race]);
+        assert_eq!(forecast.slots(Sessions::Race).unwrap().len(), 4);
+        assert_eq!(forecast.slots(Sessions::Practice), None);
+        let iterated: Vec<_> = forecast.iter().map(|(session, _)| session).collect();
+        assert_eq!(iterated, forecast.sessions());
+        let race = forecast.slots(Sessions::Race).unwrap().to_vec();
+        assert_eq!(forecast.into_inner()[&Sessions::Race], race);
+    }
+
+    #[test]
+    fn forecasts_can_be_combined() {
+        let config = Config {
+            track_wetness: true,
+            ..Config::default()
+        };
+        let mut forecaster = WeatherForecaster::new(config).unwrap().with_seed(6);
+        let saved = forecaster
+            .generate_forecast(&[Sessions::Qualifying, Sessions::Race])
+            .unwrap();
+        let mut qualifying = forecaster
+            .generate_forecast(&[Sessions::Qualifying])
+            .unwrap();
+        qualifying
+            .forecast
+            .insert(Sessions::Qualifying, vec![WeatherOptions::Storm]);
+        qualifying.conditions.remove(&Sessions::Qualifying);
+
+        let combined = saved
+            .clone()
+            .with_session(Sessions::Qualifying, &qualifying);
+        assert_eq!(
+            combined.forecast[&Sessions::Race],
+            saved.forecast[&Sessions::Race]
+        );
+        assert_eq!(
+            combined.forecast[&Sessions::Qualifying],
+            [WeatherOptions::Storm]
+        );
+        assert!(!combined.conditions.contains_key(&Sessions::Qualifying));
+        assert!(combined.track_wetness[&Sessions::Race] > 0.0);
+        assert_eq!(
+            saved.clone().with_session(Sessions::Practice, &qualifying),
+            saved
+        );
+
+        let mut merged = saved;
+        merged.merge(&qualifying);
+        assert_eq!(merged, combined);
+    }
+
+    #[test]
+    fn weather_options_round_trip_through_strings() {
+        for option in WeatherOptions::iter() {
+            assert_eq!(option.to_string(), format!("{option:?}"));
+            assert_eq!(
+                option.to_string().parse::<WeatherOptions>().unwrap(),
+                option
+            );
+        }
+        assert_eq!(
+            "heavy fog with rain".parse::<WeatherOptions>().unwrap(),
+            WeatherOptions::HeavyFogWithRain
+        );
+        assert_eq!(
+            "LIGHT_CLOUD".parse::<WeatherOptions>().unwrap(),
+            WeatherOptions::LightCloud
+        );
+        assert!(matches!(
+            "Snow".parse::<WeatherOptions>(),
+            Err(ForecastError::UnknownWeather(name)) if name == "Snow"
+        ));
+    }
+
+    #[test]
+    fn endurance_races_are_compressed_into_slots() {
+        let mut forecaster = WeatherForecaster::default().with_seed(6);
+        let hourly = forecaster.generate_hourly(24, "14:00".parse().unwrap());
+        assert_eq!(hourly.hours.len(), 24);
+        assert_eq!(hourly.hours[10].time.to_string(), "00:00");
+        let day: f64 = hourly.hours[..4]
+            .iter()
+            .map(|hour| hour.track_temperature)
+            .sum();
+        let night: f64 = hourly.hours[10..14]
+            .iter()
+            .map(|hour| hour.track_temperature)
+            .sum();
+        assert!(night < day);
+
+        let forecast = forecaster.compress_hourly(&hourly, Sessions::Race);
+        let slots = &forecast.forecast[&Sessions::Race];
+        assert_eq!(slots.len(), 4);
+        for (slot, hours) in slots.iter().zip(hourly.hours.chunks(6)) {
+            assert!(hours.iter().any(|hour| hour.weather == *slot));
+        }
+        let average = hourly.hours[..6]
+            .iter()
+            .map(|hour| hour.ambient_temperature)
+            .sum::<f64>()
+            / 6.0;
+        assert_approx_eq!(
+            f64,
+            forecast.conditions[&Sessions::Race][0].ambient_temperature,
+            average,
+            epsilon = 0.05
+        );
     }
 }
@@ -0,0 +1,113 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::ForecastError,
+    forecaster::WeatherOptions,
+    temperature::{TemperatureConfig, TemperatureRange},
+};
+
+/// Drop of the temperature per metre of altitude in °C.
+const LAPSE_RATE: f64 = 0.0065;
+
+/// Where a track lies, shaping its weather on top of the climate.
+///
+/// Hills and mountains catch low clouds and set off thunderstorms while the air gets colder, the
+/// sea brings fog but evens out the temperatures and calms the afternoon storms.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct Geography {
+    /// Height of the track above sea level in metres
+    #[serde(default)]
+    pub altitude: f64,
+    /// Whether the track lies at the sea, otherwise it is inland
+    #[serde(default)]
+    pub coastal: bool,
+}
+
+impl Geography {
+    pub fn validate(&self) -> Result<(), ForecastError> {
+        if !self.altitude.is_finite() || !(-500.0..=9000.0).contains(&self.altitude) {
+            return Err(ForecastError::InvalidAltitude(self.altitude));
+        }
+        Ok(())
+    }
+
+    /// Factor the probability of `option` gets multiplied with at this location.
+    pub fn probability_modifier(&self, option: WeatherOptions) -> f64 {
+        let kilometres = self.altitude.max(0.0) / 1000.0;
+        match option {
+            WeatherOptions::Foggy
+            | WeatherOptions::FogWithRain
+            | WeatherOptions::HeavyFog
+            | WeatherOptions::HeavyFogWithRain => {
+                (1.0 + 0.5 * kilometres) * if self.coastal { 1.5 } else { 1.0 }
+            }
+            WeatherOptions::Storm | WeatherOptions::Thunderstorm => {
+                (1.0 + 0.4 * kilometres) * if self.coastal { 0.8 } else { 1.0 }
+            }
+            _ => 1.0,
+        }
+    }
+
+    /// The temperature ranges at this location, colder the higher the track lies and narrower at
+    /// the coast.
+    pub fn temperatures(&self, temperature: &TemperatureConfig) -> TemperatureConfig {
+        let adjust = |range: TemperatureRange| {
+            let shift = -LAPSE_RATE * self.altitude;
+            let narrowing = if self.coastal {
+                0.1 * (range.max - range.min)
+            } else {
+                0.0
+            };
+            TemperatureRange {
+                min: range.min + shift + narrowing,
+                max: range.max + shift - narrowing,
+            }
+        };
+        TemperatureConfig {
+            ambient: adjust(temperature.ambient),
+            track: adjust(temperature.track),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::assert_approx_eq;
+
+    use super::*;
+
+    #[test]
+    fn mountains_are_stormy_and_cold() {
+        let mountains = Geography {
+            altitude: 800.0,
+            coastal: false,
+        };
+        let coast = Geography {
+            altitude: 0.0,
+            coastal: true,
+        };
+        assert!(mountains.probability_modifier(WeatherOptions::Thunderstorm) > 1.0);
+        assert!(coast.probability_modifier(WeatherOptions::Thunderstorm) < 1.0);
+        assert!(coast.probability_modifier(WeatherOptions::HeavyFog) > 1.0);
+        assert_eq!(
+            Geography::default().probability_modifier(WeatherOptions::Storm),
+            1.0
+        );
+
+        let temperature = TemperatureConfig::default();
+        let cold = mountains.temperatures(&temperature);
+        assert_approx_eq!(f64, cold.ambient.min, 10.0 - 5.2);
+        assert_approx_eq!(f64, cold.track.max, 52.0 - 5.2);
+        let mild = coast.temperatures(&temperature);
+        assert_approx_eq!(f64, mild.ambient.min, 12.2);
+        assert_approx_eq!(f64, mild.ambient.max, 29.8);
+        assert!(
+            Geography {
+                altitude: f64::NAN,
+                coastal: false
+            }
+            .validate()
+            .is_err()
+        );
+    }
+}
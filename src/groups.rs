@@ -0,0 +1,156 @@
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+
+use crate::{error::ForecastError, forecaster::WeatherOptions};
+
+/// Built-in grouping of similar weather options.
+pub const BUILT_IN_GROUPS: &[(&str, &[WeatherOptions])] = &[
+    ("dry", &[WeatherOptions::Clear, WeatherOptions::LightCloud]),
+    (
+        "cloudy",
+        &[
+            WeatherOptions::MediumCloud,
+            WeatherOptions::HeavyCloud,
+            WeatherOptions::Overcast,
+        ],
+    ),
+    ("drizzle", &[WeatherOptions::LightRain]),
+    (
+        "wet",
+        &[
+            WeatherOptions::Rain,
+            WeatherOptions::FogWithRain,
+            WeatherOptions::HeavyFogWithRain,
+        ],
+    ),
+    (
+        "storm",
+        &[WeatherOptions::Storm, WeatherOptions::Thunderstorm],
+    ),
+    (
+        "fog",
+        &[
+            WeatherOptions::Foggy,
+            WeatherOptions::HeavyFog,
+            WeatherOptions::Hazy,
+        ],
+    ),
+    ("random", &[WeatherOptions::Random]),
+];
+
+/// Built-in relative duration of the weather slots of each group, storms pass quickly while
+/// clouds and fog linger. Groups without an entry last `1.0`.
+pub const BUILT_IN_SLOT_DURATIONS: &[(&str, f64)] = &[
+    ("dry", 1.2),
+    ("cloudy", 1.5),
+    ("drizzle", 0.8),
+    ("wet", 0.8),
+    ("storm", 0.4),
+    ("fog", 1.2),
+];
+
+/// Named groups of similar weather options. Sessions that should have similar weather (e.g.
+/// practice following a wet race) pick their weather from the same group.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct WeatherGroups(IndexMap<String, Vec<WeatherOptions>>);
+
+impl Default for WeatherGroups {
+    fn default() -> Self {
+        Self(
+            BUILT_IN_GROUPS
+                .iter()
+                .map(|(name, options)| (name.to_string(), options.to_vec()))
+                .collect(),
+        )
+    }
+}
+
+impl WeatherGroups {
+    /// Creates the groups from the config. Options that are not part of any group form a group of
+    /// their own.
+    pub fn new(groups: IndexMap<String, Vec<WeatherOptions>>) -> Result<Self, ForecastError> {
+        let mut groups = Self(groups);
+        for option in WeatherOptions::iter() {
+            let containing: Vec<_> = groups
+                .0
+                .iter()
+                .filter(|(_, options)| options.contains(&option))
+                .map(|(name, _)| name.clone())
+                .collect();
+            match containing.len() {
+                0 => {
+                    groups.0.insert(format!("{option:?}"), vec![option]);
+                }
+                1 => {}
+                _ => {
+                    return Err(ForecastError::AmbiguousGroup {
+                        option,
+                        groups: containing,
+                    });
+                }
+            }
+        }
+        Ok(groups)
+    }
+
+    pub fn get_group(&self, option: WeatherOptions) -> &[WeatherOptions] {
+        self.get_group_entry(option).1
+    }
+
+    pub fn get_group_name(&self, option: WeatherOptions) -> &str {
+        self.get_group_entry(option).0
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &[WeatherOptions])> {
+        self.0
+            .iter()
+            .map(|(name, options)| (name.as_str(), options.as_slice()))
+    }
+
+    fn get_group_entry(&self, option: WeatherOptions) -> (&str, &[WeatherOptions]) {
+        self.iter()
+            .find(|(_, options)| options.contains(&option))
+            .expect("every option is part of a group")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ungrouped_options_form_their_own_group() {
+        let groups = WeatherGroups::new(
+            [(
+                "wet".to_string(),
+                vec![WeatherOptions::LightRain, WeatherOptions::Rain],
+            )]
+            .into_iter()
+            .collect(),
+        )
+        .unwrap();
+        assert_eq!(
+            groups.get_group(WeatherOptions::Rain),
+            [WeatherOptions::LightRain, WeatherOptions::Rain]
+        );
+        assert_eq!(groups.get_group_name(WeatherOptions::Rain), "wet");
+        assert_eq!(
+            groups.get_group(WeatherOptions::Clear),
+            [WeatherOptions::Clear]
+        );
+    }
+
+    #[test]
+    fn options_must_not_be_in_multiple_groups() {
+        let groups = [
+            ("a".to_string(), vec![WeatherOptions::Rain]),
+            ("b".to_string(), vec![WeatherOptions::Rain]),
+        ];
+        assert!(matches!(
+            WeatherGroups::new(groups.into_iter().collect()),
+            Err(ForecastError::AmbiguousGroup { .. })
+        ));
+    }
+}
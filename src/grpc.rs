@@ -0,0 +1,355 @@
+use std::{
+    net::SocketAddr,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use serde::{Deserialize, de::IntoDeserializer};
+use tonic::{Request, Response, Status};
+
+use crate::{
+    config::Config,
+    error::ForecastError,
+    forecaster::{WeatherForecast, WeatherForecaster},
+    history::{History, HistoryEntry},
+    output::OutputFormat,
+    sessions,
+    simulate::Simulation,
+    template,
+};
+
+/// Code generated from `proto/weather_forecaster.proto`.
+pub mod proto {
+    tonic::include_proto!("weather_forecaster");
+}
+
+use proto::weather_forecaster_server::{self, WeatherForecasterServer};
+
+/// Weekends simulated if a request does not ask for a number.
+const DEFAULT_RUNS: u64 = 1000;
+/// Most weekends a single request may simulate.
+const MAX_RUNS: u64 = 1_000_000;
+
+/// The forecaster as a gRPC service, for league backends that prefer gRPC over REST. Every
+/// request gets its own forecaster seeded with the seed of the request, so forecasts can be
+/// reproduced just like on the command line.
+pub struct ForecastService {
+    config: Config,
+    template: Option<String>,
+    /// Locked while appending, so concurrent requests get the ids of their own forecasts
+    history: Option<Arc<Mutex<History>>>,
+}
+
+impl ForecastService {
+    pub fn new(config: Config) -> Result<Self, ForecastError> {
+        // fail on invalid configs right away rather than on every request
+        WeatherForecaster::new(config.clone())?;
+        Ok(Self {
+            template: config.template.as_deref().map(template::load).transpose()?,
+            history: config
+                .history
+                .history()
+                .map(|history| Arc::new(Mutex::new(history))),
+            config,
+        })
+    }
+
+    fn render(
+        &self,
+        forecast: &WeatherForecast,
+        seed: u64,
+        format: &str,
+    ) -> Result<String, Status> {
+        let format = if format.is_empty() {
+            OutputFormat::Json
+        } else {
+            OutputFormat::deserialize(format.to_lowercase().into_deserializer()).map_err(
+                |error: serde::de::value::Error| Status::invalid_argument(error.to_string()),
+            )?
+        };
+        format
+            .render(
+                forecast,
+                self.template.as_deref(),
+                Some(seed),
+                self.config.emoji,
+            )
+            .map_err(status)
+    }
+}
+
+/// Runs generating forecasts and accessing files off the async executor.
+async fn blocking<T: Send + 'static>(
+    work: impl FnOnce() -> Result<T, ForecastError> + Send + 'static,
+) -> Result<T, Status> {
+    tokio::task::spawn_blocking(work)
+        .await
+        .map_err(|error| Status::internal(error.to_string()))?
+        .map_err(status)
+}
+
+/// Serves the service on `address` until the server fails.
+pub async fn serve(
+    address: SocketAddr,
+    service: ForecastService,
+) -> Result<(), tonic::transport::Error> {
+    tonic::transport::Server::builder()
+        .add_service(WeatherForecasterServer::new(service))
+        .serve(address)
+        .await
+}
+
+#[tonic::async_trait]
+impl weather_forecaster_server::WeatherForecaster for ForecastService {
+    async fn generate_forecast(
+        &self,
+        request: Request<proto::GenerateForecastRequest>,
+    ) -> Result<Response<proto::Forecast>, Status> {
+        let request = request.into_inner();
+        let sessions =
+            sessions::resolve(&self.config.sessions, &request.sessions).map_err(status)?;
+        let seed = request.seed.unwrap_or_else(rand::random);
+        let config = self.config.clone();
+        let history = self.history.clone();
+        let (forecast, id) = blocking(move || {
+            let forecast = WeatherForecaster::new(config)?
+                .with_seed(seed)
+                .generate_constrained_forecast(&sessions)?;
+            let id = match history {
+                Some(history) => Some(
+                    history
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .append(&HistoryEntry::new(seed, &forecast))? as u64,
+                ),
+                None => None,
+            };
+            Ok((forecast, id))
+        })
+        .await?;
+        Ok(Response::new(proto::Forecast {
+            seed,
+            id,
+            rendered: self.render(&forecast, seed, &request.format)?,
+        }))
+    }
+
+    async fn get_forecast(
+        &self,
+        request: Request<proto::GetForecastRequest>,
+    ) -> Result<Response<proto::Forecast>, Status> {
+        let request = request.into_inner();
+        let history = self
+            .history
+            .clone()
+            .ok_or_else(|| Status::failed_precondition("The history is disabled"))?;
+        let config = self.config.clone();
+        let found = blocking(move || {
+            let history = history.lock().unwrap_or_else(PoisonError::into_inner);
+            let entry = match request.id {
+                Some(id) => usize::try_from(id)
+                    .ok()
+                    .map(|id| history.get(id))
+                    .transpose()?
+                    .flatten()
+                    .map(|entry| (id, entry)),
+                None => {
+                    let mut entries = history.entries()?;
+                    let id = entries.len() as u64;
+                    entries.pop().map(|entry| (id, entry))
+                }
+            };
+            entry
+                .map(|(id, entry)| {
+                    let forecast = entry.restore(&WeatherForecaster::new(config)?)?;
+                    Ok((id, entry.seed, forecast))
+                })
+                .transpose()
+        })
+        .await?;
+        let Some((id, seed, forecast)) = found else {
+            return Err(Status::not_found("There is no such forecast"));
+        };
+
+        Ok(Response::new(proto::Forecast {
+            seed,
+            id: Some(id),
+            rendered: self.render(&forecast, seed, &request.format)?,
+        }))
+    }
+
+    async fn simulate(
+        &self,
+        request: Request<proto::SimulateRequest>,
+    ) -> Result<Response<proto::SimulateResponse>, Status> {
+        let request = request.into_inner();
+        let runs = match request.runs {
+            0 => DEFAULT_RUNS,
+            runs if runs > MAX_RUNS => {
+                return Err(Status::invalid_argument(format!(
+                    "At most {MAX_RUNS} weekends can be simulated"
+                )));
+            }
+            runs => runs,
+        };
+        let sessions =
+            sessions::resolve(&self.config.sessions, &request.sessions).map_err(status)?;
+        let seed = request.seed.unwrap_or_else(rand::random);
+        let config = self.config.clone();
+        let simulation =
+            blocking(move || Simulation::run_parallel(&config, &sessions, runs as usize, seed))
+                .await?;
+
+        let sessions = simulation
+            .counts
+            .keys()
+            .map(|session| proto::SessionStatistics {
+                session: session.to_string(),
+                wet_rate: simulation.wet_rate(*session),
+                average_rain_intensity: simulation.average_rain_intensity(*session),
+                shares: simulation
+                    .options()
+                    .into_iter()
+                    .map(|option| (option.to_string(), simulation.share(*session, option)))
+                    .collect(),
+            })
+            .collect();
+        Ok(Response::new(proto::SimulateResponse { runs, sessions }))
+    }
+}
+
+/// Failures reading or writing files are on the server, everything else on the request.
+fn status(error: ForecastError) -> Status {
+    match error {
+        ForecastError::Io { .. } => Status::internal(error.to_string()),
+        error => Status::invalid_argument(error.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::assert_approx_eq;
+
+    use super::*;
+    use crate::history::HistoryConfig;
+    use weather_forecaster_server::WeatherForecaster as _;
+
+    #[test]
+    fn generated_forecasts_can_be_fetched_again() {
+        let path = std::env::temp_dir().join(format!(
+            "weather_forecaster_grpc_{}.jsonl",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let config = Config {
+            history: HistoryConfig {
+                enabled: true,
+                file: Some(path.clone()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let service = ForecastService::new(config).unwrap();
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+
+        let generated = runtime
+            .block_on(
+                service.generate_forecast(Request::new(proto::GenerateForecastRequest {
+                    sessions: vec!["race".to_string()],
+                    seed: Some(7),
+                    format: "game".to_string(),
+                })),
+            )
+            .unwrap()
+            .into_inner();
+        assert_eq!(generated.id, Some(1));
+        let fetched = runtime
+            .block_on(
+                service.get_forecast(Request::new(proto::GetForecastRequest {
+                    id: None,
+                    format: "game".to_string(),
+                })),
+            )
+            .unwrap()
+            .into_inner();
+        assert_eq!(fetched, generated);
+        let missing = runtime.block_on(service.get_forecast(Request::new(
+            proto::GetForecastRequest {
+                id: Some(2),
+                format: String::new(),
+            },
+        )));
+        assert_eq!(missing.unwrap_err().code(), tonic::Code::NotFound);
+
+        let simulation = runtime
+            .block_on(service.simulate(Request::new(proto::SimulateRequest {
+                sessions: vec!["race".to_string()],
+                runs: 50,
+                seed: Some(7),
+            })))
+            .unwrap()
+            .into_inner();
+        assert_eq!(simulation.sessions.len(), 1);
+        let shares: f64 = simulation.sessions[0].shares.values().sum();
+        assert_approx_eq!(f64, shares, 1.0, epsilon = 1e-9);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn concurrent_forecasts_get_their_own_ids() {
+        let path = std::env::temp_dir().join(format!(
+            "weather_forecaster_grpc_concurrent_{}.jsonl",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let config = Config {
+            history: HistoryConfig {
+                enabled: true,
+                file: Some(path.clone()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let service = Arc::new(ForecastService::new(config).unwrap());
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+
+        let generated = runtime.block_on(async {
+            let requests: Vec<_> = (0..8)
+                .map(|seed| {
+                    let service = service.clone();
+                    tokio::spawn(async move {
+                        service
+                            .generate_forecast(Request::new(proto::GenerateForecastRequest {
+                                sessions: vec!["race".to_string()],
+                                seed: Some(seed),
+                                format: "game".to_string(),
+                            }))
+                            .await
+                            .unwrap()
+                            .into_inner()
+                    })
+                })
+                .collect();
+            let mut generated = Vec::new();
+            for request in requests {
+                generated.push(request.await.unwrap());
+            }
+            generated
+        });
+        let mut ids: Vec<_> = generated.iter().map(|forecast| forecast.id).collect();
+        ids.sort();
+        assert_eq!(ids, (1..=8).map(Some).collect::<Vec<_>>());
+        for forecast in generated {
+            let fetched = runtime
+                .block_on(
+                    service.get_forecast(Request::new(proto::GetForecastRequest {
+                        id: forecast.id,
+                        format: "game".to_string(),
+                    })),
+                )
+                .unwrap()
+                .into_inner();
+            assert_eq!(fetched, forecast);
+        }
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,231 @@
+use eframe::egui;
+use strum::IntoEnumIterator;
+
+use crate::{
+    config::Config,
+    forecaster::{Sessions, WeatherForecast, WeatherForecaster, WeatherOptions},
+    output::OutputFormat,
+    sessions, template,
+};
+
+/// Width of the bars of the probability chart in points.
+const CHART_WIDTH: f32 = 160.0;
+
+/// Opens a desktop window to tweak the probabilities, pick the sessions, generate forecasts and
+/// copy or export them, e.g. for league admins who do not use a terminal.
+pub fn run(config: Config) -> eframe::Result {
+    let options = eframe::NativeOptions {
+        viewport: egui::ViewportBuilder::default().with_inner_size([960.0, 640.0]),
+        ..Default::default()
+    };
+    eframe::run_native(
+        "Weather Forecaster",
+        options,
+        Box::new(|_| Ok(Box::new(App::new(config)?))),
+    )
+}
+
+struct App {
+    forecaster: WeatherForecaster,
+    /// Every session that can be generated and whether it is selected
+    sessions: Vec<(Sessions, String, bool)>,
+    forecast: Option<WeatherForecast>,
+    template: Option<String>,
+    format: OutputFormat,
+    emoji: bool,
+    export_path: String,
+    status: String,
+}
+
+impl App {
+    fn new(config: Config) -> Result<Self, crate::error::ForecastError> {
+        let selected = sessions::resolve(&config.sessions, &[])?;
+        let sessions = if config.sessions.is_empty() {
+            Sessions::iter()
+                .map(|session| (session, session.to_string(), selected.contains(&session)))
+                .collect()
+        } else {
+            config
+                .sessions
+                .iter()
+                .map(|definition| (definition.session, definition.name.clone(), true))
+                .collect()
+        };
+        let template = config.template.as_deref().map(template::load).transpose()?;
+        let export_path = config
+            .output
+            .file
+            .as_ref()
+            .map_or("forecast.txt".to_string(), |path| {
+                path.display().to_string()
+            });
+
+        Ok(Self {
+            sessions,
+            forecast: None,
+            template,
+            format: config.output.format,
+            emoji: config.emoji,
+            export_path,
+            status: String::new(),
+            forecaster: WeatherForecaster::new(config)?,
+        })
+    }
+
+    fn selected_sessions(&self) -> Vec<Sessions> {
+        self.sessions
+            .iter()
+            .filter(|(_, _, selected)| *selected)
+            .map(|(session, _, _)| *session)
+            .collect()
+    }
+
+    fn generate(&mut self) {
+        match self
+            .forecaster
+            .generate_constrained_forecast(&self.selected_sessions())
+        {
+            Ok(forecast) => {
+                self.forecast = Some(forecast);
+                self.status.clear();
+            }
+            Err(error) => self.status = error.to_string(),
+        }
+    }
+
+    /// The forecast in the selected format, `None` with the reason in the status otherwise.
+    fn render(&mut self) -> Option<String> {
+        let forecast = self.forecast.as_ref()?;
+        match self
+            .format
+            .render(forecast, self.template.as_deref(), None, self.emoji)
+        {
+            Ok(rendered) => Some(rendered),
+            Err(error) => {
+                self.status = error.to_string();
+                None
+            }
+        }
+    }
+
+    fn probability_sliders(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Probabilities");
+        egui::Grid::new("probabilities").show(ui, |ui| {
+            for option in WeatherOptions::iter() {
+                let mut probability = self.forecaster.get_probability(option);
+                ui.label(option.to_string());
+                let slider = egui::Slider::new(&mut probability, 0.0..=1.0).show_value(true);
+                if ui.add(slider).changed()
+                    && let Err(error) = self.forecaster.set_probability(option, probability)
+                {
+                    self.status = error.to_string();
+                }
+                ui.end_row();
+            }
+        });
+    }
+
+    /// Bar chart of the probabilities the sliders set.
+    fn probability_chart(&self, ui: &mut egui::Ui) {
+        let probabilities: Vec<(WeatherOptions, f64)> = WeatherOptions::iter()
+            .map(|option| (option, self.forecaster.get_probability(option)))
+            .collect();
+        let highest = probabilities
+            .iter()
+            .map(|(_, probability)| *probability)
+            .fold(0.0, f64::max);
+        let color = ui.visuals().selection.bg_fill;
+        egui::Grid::new("chart").show(ui, |ui| {
+            for (option, probability) in probabilities {
+                ui.label(option.to_string());
+                let height = ui.spacing().interact_size.y * 0.6;
+                let (rect, _) =
+                    ui.allocate_exact_size(egui::vec2(CHART_WIDTH, height), egui::Sense::hover());
+                let fraction = if highest > 0.0 {
+                    probability / highest
+                } else {
+                    0.0
+                };
+                let bar = egui::Rect::from_min_size(
+                    rect.min,
+                    egui::vec2(CHART_WIDTH * fraction as f32, height),
+                );
+                ui.painter().rect_filled(bar, 2.0, color);
+                ui.label(format!("{:.1}%", probability * 100.0));
+                ui.end_row();
+            }
+        });
+    }
+
+    fn controls(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal_wrapped(|ui| {
+            for (_, name, selected) in &mut self.sessions {
+                ui.checkbox(selected, name.as_str());
+            }
+        });
+        ui.horizontal(|ui| {
+            if ui.button("Generate").clicked() {
+                self.generate();
+            }
+            egui::ComboBox::from_id_salt("format")
+                .selected_text(format!("{:?}", self.format))
+                .show_ui(ui, |ui| {
+                    for format in OutputFormat::iter() {
+                        ui.selectable_value(&mut self.format, format, format!("{format:?}"));
+                    }
+                });
+            let has_forecast = self.forecast.is_some();
+            if ui
+                .add_enabled(has_forecast, egui::Button::new("Copy"))
+                .clicked()
+                && let Some(rendered) = self.render()
+            {
+                ui.ctx().copy_text(rendered);
+                self.status = "Copied the forecast to the clipboard".to_string();
+            }
+            ui.text_edit_singleline(&mut self.export_path);
+            if ui
+                .add_enabled(has_forecast, egui::Button::new("Export"))
+                .clicked()
+                && let Some(rendered) = self.render()
+            {
+                self.status = match std::fs::write(&self.export_path, rendered) {
+                    Ok(()) => format!("Exported the forecast to {}", self.export_path),
+                    Err(error) => format!("Failed to export to {}: {error}", self.export_path),
+                };
+            }
+        });
+    }
+}
+
+impl eframe::App for App {
+    fn ui(&mut self, ui: &mut egui::Ui, _frame: &mut eframe::Frame) {
+        egui::Panel::left("probabilities").show(ui, |ui| {
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                self.probability_sliders(ui);
+                ui.separator();
+                self.probability_chart(ui);
+            });
+        });
+        egui::Panel::bottom("status").show(ui, |ui| {
+            ui.label(self.status.as_str());
+        });
+        egui::CentralPanel::default().show(ui, |ui| {
+            self.controls(ui);
+            ui.separator();
+            egui::ScrollArea::vertical().show(ui, |ui| match &self.forecast {
+                Some(forecast) => {
+                    ui.monospace(format!(
+                        "{forecast}\n{}\n{}{}",
+                        forecast.conditions_report(),
+                        forecast.summary(),
+                        forecast.narrative()
+                    ));
+                }
+                None => {
+                    ui.label("Pick the sessions and press Generate");
+                }
+            });
+        });
+    }
+}
@@ -0,0 +1,255 @@
+use std::{
+    collections::BTreeMap,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+#[cfg(feature = "fs")]
+use std::{
+    io::{BufRead, BufReader, Write},
+    path::Path,
+};
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::ForecastError,
+    forecaster::{Sessions, SlotConditions, SlotTiming, WeatherForecast, WeatherForecaster},
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(default)]
+pub struct HistoryConfig {
+    /// Append every forecast to the history archive
+    pub enabled: bool,
+    /// Archive file, defaults to `history.jsonl` in the data directory of the user
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<PathBuf>,
+    /// SQLite database to additionally store the forecasts and their configs in, requires the
+    /// `sqlite` feature
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub database: Option<PathBuf>,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            file: None,
+            database: None,
+        }
+    }
+}
+
+#[cfg(feature = "fs")]
+impl HistoryConfig {
+    /// The archive to write to, `None` if the history is disabled or there is no data directory.
+    pub fn history(&self) -> Option<History> {
+        if !self.enabled {
+            return None;
+        }
+        self.file
+            .clone()
+            .or_else(History::default_path)
+            .map(History::new)
+    }
+}
+
+/// A single archived forecast.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// Seconds since the unix epoch
+    pub timestamp: u64,
+    pub seed: u64,
+    /// Game strings of the weather slots of every generated session
+    pub sessions: BTreeMap<Sessions, Vec<String>>,
+    /// Temperatures and humidity of the weather slots, missing in archives of older versions
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub conditions: BTreeMap<Sessions, Vec<SlotConditions>>,
+    /// Starts and durations of the weather slots, only present if they were generated
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub slot_timings: BTreeMap<Sessions, Vec<SlotTiming>>,
+    /// Sessions rerolled after the forecast was generated, the seed alone does not reproduce
+    /// the forecast if there are any
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rerolled: Vec<Sessions>,
+}
+
+impl HistoryEntry {
+    pub fn new(seed: u64, forecast: &WeatherForecast) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default();
+        let sessions = forecast
+            .forecast
+            .iter()
+            .map(|(session, options)| {
+                let game_strings = (0..options.len())
+                    .map(|index| forecast.game_string(*session, index))
+                    .collect();
+                (*session, game_strings)
+            })
+            .collect();
+
+        Self {
+            timestamp,
+            seed,
+            sessions,
+            conditions: forecast.conditions.clone().into_iter().collect(),
+            slot_timings: forecast.slot_timings.clone().into_iter().collect(),
+            rerolled: Vec::new(),
+        }
+    }
+
+    /// Records the sessions that were rerolled after generating the forecast.
+    pub fn with_rerolled(mut self, rerolled: &[Sessions]) -> Self {
+        self.rerolled = rerolled.to_vec();
+        self
+    }
+
+    /// Rebuilds the archived forecast. The forecaster has to know all archived game strings.
+    pub fn restore<R: Rng>(
+        &self,
+        forecaster: &WeatherForecaster<R>,
+    ) -> Result<WeatherForecast, ForecastError> {
+        let mut forecast =
+            forecaster.restore_forecast(self.sessions.clone().into_iter().collect())?;
+        forecast.conditions = self.conditions.clone().into_iter().collect();
+        forecast.slot_timings = self.slot_timings.clone().into_iter().collect();
+        Ok(forecast)
+    }
+}
+
+/// Archive of generated forecasts, stored as one JSON object per line. Entries are identified
+/// by their 1-based position in the archive.
+#[cfg(feature = "fs")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct History {
+    path: PathBuf,
+}
+
+#[cfg(feature = "fs")]
+impl History {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("weather_forecaster").join("history.jsonl"))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Appends the entry and returns its id. Appends of several threads have to be serialized,
+    /// otherwise they might get the same id.
+    pub fn append(&self, entry: &HistoryEntry) -> Result<usize, ForecastError> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent).map_err(|error| ForecastError::io(parent, error))?;
+        }
+
+        let mut line = serde_json::to_string(entry)?;
+        line.push('\n');
+        std::fs::OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| {
+                let mut entries = 0;
+                for existing in BufReader::new(&file).lines() {
+                    if !existing?.trim().is_empty() {
+                        entries += 1;
+                    }
+                }
+                file.write_all(line.as_bytes())?;
+                Ok(entries + 1)
+            })
+            .map_err(|error| ForecastError::io(&self.path, error))
+    }
+
+    /// The archived forecast with the given id.
+    pub fn get(&self, id: usize) -> Result<Option<HistoryEntry>, ForecastError> {
+        let Some(index) = id.checked_sub(1) else {
+            return Ok(None);
+        };
+        Ok(self.entries()?.into_iter().nth(index))
+    }
+
+    /// All archived forecasts, oldest first. A missing archive is empty.
+    pub fn entries(&self) -> Result<Vec<HistoryEntry>, ForecastError> {
+        let content = match std::fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(error) => return Err(ForecastError::io(&self.path, error)),
+        };
+        content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| Ok(serde_json::from_str(line)?))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn entries_are_appended() {
+        let path = std::env::temp_dir().join(format!(
+            "weather_forecaster_history_{}.jsonl",
+            std::process::id()
+        ));
+        let history = History::new(&path);
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(history.entries().unwrap(), Vec::new());
+
+        let sessions = [Sessions::Qualifying, Sessions::Race];
+        let mut forecaster = WeatherForecaster::default().with_seed(1);
+        let first = HistoryEntry::new(1, &forecaster.generate_forecast(&sessions).unwrap());
+        let second = HistoryEntry::new(1, &forecaster.generate_forecast(&sessions).unwrap());
+        assert_eq!(history.append(&first).unwrap(), 1);
+        assert_eq!(history.append(&second).unwrap(), 2);
+
+        assert_eq!(history.entries().unwrap(), [first.clone(), second.clone()]);
+        assert_eq!(history.get(2).unwrap(), Some(second));
+        assert_eq!(history.get(0).unwrap(), None);
+        assert_eq!(history.get(3).unwrap(), None);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn restored_forecast_is_identical() {
+        let mut forecaster = WeatherForecaster::default().with_seed(2);
+        let forecast = forecaster
+            .generate_forecast(&[Sessions::Practice, Sessions::Qualifying, Sessions::Race])
+            .unwrap();
+        let entry = HistoryEntry::new(2, &forecast);
+        let entry: HistoryEntry =
+            serde_json::from_str(&serde_json::to_string(&entry).unwrap()).unwrap();
+        assert_eq!(entry.restore(&forecaster).unwrap(), forecast);
+    }
+
+    #[test]
+    fn rerolled_sessions_are_recorded() {
+        let forecast = WeatherForecaster::default()
+            .with_seed(3)
+            .generate_forecast(&[Sessions::Qualifying, Sessions::Race])
+            .unwrap();
+        let generated = HistoryEntry::new(3, &forecast);
+        assert!(
+            !serde_json::to_string(&generated)
+                .unwrap()
+                .contains("rerolled")
+        );
+
+        let rerolled = generated.with_rerolled(&[Sessions::Race]);
+        let line = serde_json::to_string(&rerolled).unwrap();
+        let parsed: HistoryEntry = serde_json::from_str(&line).unwrap();
+        assert_eq!(parsed.rerolled, [Sessions::Race]);
+    }
+}
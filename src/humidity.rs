@@ -0,0 +1,32 @@
+use rand::Rng;
+
+use crate::forecaster::{Round, WeatherOptions};
+
+/// Generates the relative humidity in percent for a slot with the given weather.
+pub fn generate_humidity<R: Rng>(option: WeatherOptions, rng: &mut R) -> f64 {
+    let (min, max) = option.humidity_range();
+    rng.random_range(min..=max).round_to_decimal_place(0)
+}
+
+/// Calculates the dew point in °C using the Magnus formula.
+pub fn dew_point(temperature: f64, humidity: f64) -> f64 {
+    const B: f64 = 17.62;
+    const C: f64 = 243.12;
+
+    let gamma = (humidity.clamp(1.0, 100.0) / 100.0).ln() + B * temperature / (C + temperature);
+    (C * gamma / (B - gamma)).round_to_decimal_place(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::assert_approx_eq;
+
+    use super::*;
+
+    #[test]
+    fn dew_point_matches_reference_values() {
+        assert_approx_eq!(f64, dew_point(20.0, 100.0), 20.0, epsilon = 0.05);
+        assert_approx_eq!(f64, dew_point(20.0, 50.0), 9.3, epsilon = 0.05);
+        assert_approx_eq!(f64, dew_point(30.0, 70.0), 23.9, epsilon = 0.05);
+    }
+}
@@ -1,2 +1,66 @@
+pub mod apply;
+pub mod batch;
+pub mod builder;
+pub mod bulletin;
+pub mod calendar;
+pub mod catalog;
+pub mod circuits;
+pub mod climatology;
 pub mod config;
+pub mod constraints;
+pub mod coupling;
+pub mod discord;
+pub mod drift;
+pub mod endurance;
+pub mod environment;
+pub mod error;
+pub mod export;
+#[cfg(feature = "ffi")]
+pub mod ffi;
 pub mod forecaster;
+pub mod geography;
+pub mod groups;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+#[cfg(feature = "gui")]
+pub mod gui;
+pub mod history;
+pub mod humidity;
+pub mod live;
+pub mod metar;
+pub mod migration;
+pub mod mqtt;
+pub mod outlook;
+pub mod output;
+pub mod plot;
+pub mod presets;
+pub mod realism;
+pub mod sampling;
+pub mod season;
+pub mod sessions;
+pub mod simulate;
+pub mod stats;
+#[cfg(feature = "sqlite")]
+pub mod store;
+pub mod summary;
+pub mod temperature;
+pub mod template;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+pub mod time_of_day;
+pub mod transitions;
+#[cfg(feature = "tui")]
+pub mod tui;
+pub mod unknown_keys;
+pub mod variety;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "fs")]
+pub mod web;
+pub mod weekend;
+pub mod wetness;
+
+pub use builder::WeatherForecasterBuilder;
+pub use config::Config;
+pub use error::ForecastError;
+pub use forecaster::{Sessions, WeatherForecast, WeatherForecaster, WeatherOptions};
@@ -0,0 +1,140 @@
+use std::{collections::HashMap, fmt::Display, str::FromStr};
+
+use serde::Deserialize;
+use strum::IntoEnumIterator;
+
+use crate::{error::ForecastError, forecaster::WeatherOptions};
+
+const OPEN_METEO_URL: &str = "https://api.open-meteo.com/v1/forecast";
+
+/// Location on earth in degrees, written as `<latitude>,<longitude>`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Coordinates {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+impl FromStr for Coordinates {
+    type Err = ForecastError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ForecastError::InvalidCoordinates(s.to_string());
+        let (latitude, longitude) = s.split_once(',').ok_or_else(invalid)?;
+        let latitude: f64 = latitude.trim().parse().map_err(|_| invalid())?;
+        let longitude: f64 = longitude.trim().parse().map_err(|_| invalid())?;
+        if !(-90.0..=90.0).contains(&latitude) || !(-180.0..=180.0).contains(&longitude) {
+            return Err(invalid());
+        }
+        Ok(Self {
+            latitude,
+            longitude,
+        })
+    }
+}
+
+impl Display for Coordinates {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{},{}", self.latitude, self.longitude)
+    }
+}
+
+/// Hourly forecast of the next 24 hours from [Open-Meteo](https://open-meteo.com).
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct LiveForecast {
+    pub hourly: HourlyWeather,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct HourlyWeather {
+    /// WMO weather interpretation code of every hour, missing hours are `null`
+    pub weather_code: Vec<Option<u8>>,
+}
+
+impl LiveForecast {
+    /// How often every weather option appears in the forecast, hours without a matching option
+    /// (e.g. snow) are skipped.
+    pub fn probabilities(&self) -> Result<HashMap<WeatherOptions, f64>, ForecastError> {
+        let options: Vec<WeatherOptions> = self
+            .hourly
+            .weather_code
+            .iter()
+            .flatten()
+            .filter_map(|code| weather_option(*code))
+            .collect();
+        if options.is_empty() {
+            return Err(ForecastError::LiveWeather(
+                "the forecast has no weather the game knows".to_string(),
+            ));
+        }
+
+        let mut probabilities: HashMap<_, _> =
+            WeatherOptions::iter().map(|option| (option, 0.0)).collect();
+        for option in &options {
+            *probabilities.get_mut(option).unwrap() += 1.0 / options.len() as f64;
+        }
+        Ok(probabilities)
+    }
+}
+
+/// Weather option of a WMO weather interpretation code as used by Open-Meteo.
+pub fn weather_option(code: u8) -> Option<WeatherOptions> {
+    match code {
+        0 => Some(WeatherOptions::Clear),
+        1 => Some(WeatherOptions::LightCloud),
+        2 => Some(WeatherOptions::MediumCloud),
+        3 => Some(WeatherOptions::Overcast),
+        45 => Some(WeatherOptions::Foggy),
+        48 => Some(WeatherOptions::HeavyFog),
+        51..=57 | 61 | 80 => Some(WeatherOptions::LightRain),
+        63 | 66 | 81 => Some(WeatherOptions::Rain),
+        65 | 67 | 82 => Some(WeatherOptions::Storm),
+        95..=99 => Some(WeatherOptions::Thunderstorm),
+        _ => None,
+    }
+}
+
+/// Open-Meteo request of the hourly weather codes of the next 24 hours.
+pub fn forecast_url(coordinates: Coordinates) -> String {
+    format!(
+        "{OPEN_METEO_URL}?latitude={}&longitude={}&hourly=weather_code&forecast_days=1",
+        coordinates.latitude, coordinates.longitude
+    )
+}
+
+/// Fetches the forecast at the coordinates.
+#[cfg(feature = "online")]
+pub fn fetch(coordinates: Coordinates) -> Result<LiveForecast, ForecastError> {
+    let body = ureq::get(&forecast_url(coordinates))
+        .call()
+        .and_then(|mut response| response.body_mut().read_to_string())
+        .map_err(|error| ForecastError::LiveWeather(error.to_string()))?;
+    Ok(serde_json::from_str(&body)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::assert_approx_eq;
+
+    use super::*;
+
+    #[test]
+    fn forecast_becomes_probabilities() {
+        let spa: Coordinates = "50.437, 5.971".parse().unwrap();
+        assert_eq!(spa.to_string(), "50.437,5.971");
+        assert!("50.437".parse::<Coordinates>().is_err());
+        assert!("91,0".parse::<Coordinates>().is_err());
+
+        let forecast: LiveForecast = serde_json::from_str(
+            r#"{"latitude": 50.44, "hourly": {"time": ["a", "b", "c", "d", "e"],
+                "weather_code": [3, 61, 61, 71, null]}}"#,
+        )
+        .unwrap();
+        let probabilities = forecast.probabilities().unwrap();
+        assert_eq!(probabilities.len(), WeatherOptions::iter().len());
+        assert_approx_eq!(f64, probabilities[&WeatherOptions::Overcast], 1.0 / 3.0);
+        assert_approx_eq!(f64, probabilities[&WeatherOptions::LightRain], 2.0 / 3.0);
+        assert_eq!(probabilities[&WeatherOptions::Clear], 0.0);
+
+        assert!(forecast_url(spa).contains("latitude=50.437&longitude=5.971"));
+    }
+}
@@ -1,11 +1,37 @@
-use std::{path::PathBuf, process::exit};
+use std::{
+    io::{IsTerminal, Write},
+    path::{Path, PathBuf},
+    process::exit,
+};
 
-use clap::Parser;
-use cli_clipboard::{ClipboardContext, ClipboardProvider};
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum, error::ErrorKind};
+use indexmap::IndexMap;
+use serde::Serialize;
 
 use weather_forecaster::{
-    config::Config,
-    forecaster::{Sessions, WeatherForecaster},
+    Config, Sessions, WeatherForecast, WeatherForecaster, WeatherOptions,
+    apply::{apply_forecast, read_forecast},
+    batch,
+    calendar::{Calendar, EventDate, SeasonSummary},
+    catalog::CatalogEntry,
+    circuits, climatology,
+    config::ConfigFormat,
+    constraints::Constraint,
+    history::HistoryEntry,
+    live::Coordinates,
+    metar::{self, Metar},
+    migration::CONFIG_VERSION,
+    mqtt::MqttConfig,
+    outlook::Outlook,
+    output::OutputFormat,
+    plot::{self, PlotSize},
+    presets::TrackPreset,
+    realism::Realism,
+    sessions,
+    simulate::Simulation,
+    template,
+    time_of_day::TimeOfDay,
+    web,
 };
 
 #[derive(Debug, Parser)]
@@ -14,41 +40,1224 @@ struct Args {
     #[arg(short, long, default_value = "./config.yml")]
     config_file: PathBuf,
 
-    /// Number of session to generate weather for
-    #[arg(
-        short,
-        long,
-        value_delimiter = ' ',
-        num_args = 1..,
-        default_value = "practice qualifying race"
-    )]
-    sessions: Vec<Sessions>,
+    /// Format of the config file, detected by its extension if not given
+    #[arg(long, value_enum)]
+    config_format: Option<ConfigFormat>,
+
+    /// Sessions to generate weather for, built-in or declared in the config. Defaults to the
+    /// declared sessions or practice, qualifying and race
+    #[arg(short, long, value_delimiter = ' ', num_args = 1..)]
+    sessions: Vec<String>,
+
+    /// Show more details of what is going on, repeat for even more (-vv, -vvv)
+    #[arg(short, long, action = clap::ArgAction::Count, global = true)]
+    verbose: u8,
+
+    /// Only show errors and print nothing but the forecast in the output format, e.g. to use it
+    /// in scripts
+    #[arg(short, long, conflicts_with = "verbose", global = true)]
+    quiet: bool,
+
+    /// Never color the output, it is only colored in terminals anyway
+    #[arg(long, global = true)]
+    no_color: bool,
+
+    /// Fail on unknown keys in the config file instead of ignoring them with a warning
+    #[arg(long)]
+    strict: bool,
+
+    /// Seed for the random number generator to reproduce a previous forecast
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Profile of the config file to use
+    #[arg(short, long)]
+    profile: Option<String>,
+
+    /// Track climate preset, overrides the track of the config file. The keys of
+    /// config.d/<track>.yml next to the config file override the config
+    #[arg(short, long)]
+    track: Option<TrackPreset>,
+
+    /// Real world circuit of the event, overrides the circuit of the config
+    #[arg(long)]
+    circuit: Option<String>,
+
+    /// Base the probabilities on the real weather of the next 24 hours at the coordinates, or at
+    /// the track preset if none are given. Requires the online feature
+    #[arg(long, value_name = "LAT,LON")]
+    live: Option<Option<Coordinates>>,
+
+    /// Base the probabilities on the weather in the month of the date over the past years at
+    /// --location or the track preset. Requires the online feature
+    #[arg(long, value_name = "YYYY-MM-DD")]
+    climate: Option<EventDate>,
+
+    /// Number of past years --climate looks at
+    #[arg(long, default_value_t = climatology::DEFAULT_YEARS, requires = "climate")]
+    climate_years: u16,
+
+    /// Start the race with the current weather of a METAR report or of the station with the ICAO
+    /// code, the following slots develop from it. Fetching a report requires the online feature
+    #[arg(long, value_name = "REPORT|ICAO")]
+    metar: Option<String>,
+
+    /// Location of the track for --climate
+    #[arg(long, value_name = "LAT,LON", requires = "climate")]
+    location: Option<Coordinates>,
+
+    /// Override the probability of a weather option for this run, e.g. `Rain=0.3`
+    #[arg(long, value_name = "OPTION=PROBABILITY", value_parser = parse_probability)]
+    probability: Vec<(WeatherOptions, f64)>,
+
+    /// Only accept forecasts fulfilling the condition, e.g. `wet_race`
+    #[arg(long, value_name = "CONDITION")]
+    require: Vec<Constraint>,
+
+    /// Only accept forecasts not fulfilling the condition, e.g. `thunderstorm_in_quali`
+    #[arg(long, value_name = "CONDITION")]
+    forbid: Vec<Constraint>,
+
+    /// Only accept forecasts containing the weather at least once, e.g. `fog`
+    #[arg(long, value_name = "WEATHER")]
+    at_least_one: Vec<Constraint>,
+
+    /// How strictly consecutive slots follow the graph of plausible weather changes, overrides
+    /// the config
+    #[arg(long, value_enum)]
+    realism: Option<Realism>,
+
+    /// Only accept forecasts whose rain changes by at most one step between consecutive slots
+    #[arg(long)]
+    smooth_rain: bool,
+
+    /// Write the forecast to a file, overrides the output file of the config
+    #[arg(short, long, value_name = "FILE")]
+    output: Option<PathBuf>,
+
+    /// Format of the output file and of the forecast printed to stdout, which otherwise shows the
+    /// forecast with its reports
+    #[arg(long, value_enum)]
+    format: Option<OutputFormat>,
+
+    /// Append to the output file instead of replacing it
+    #[arg(long, requires = "output")]
+    append: bool,
+
+    /// Replace the output file if it already exists
+    #[arg(long, requires = "output", conflicts_with = "append")]
+    force: bool,
+
+    /// Print the forecast to stdout as well when writing it to an output file
+    #[arg(long)]
+    stdout: bool,
+
+    /// Show an emoji next to the weather in markdown tables
+    #[arg(long)]
+    emoji: bool,
+
+    /// Handlebars template file to print the forecast with, overrides the template of the config
+    #[arg(long)]
+    template: Option<PathBuf>,
+
+    /// Generate this many different forecasts at once, e.g. for several lobbies, each is written
+    /// to its own file in --batch-dir
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..), conflicts_with_all = ["interactive", "output"])]
+    count: Option<u32>,
+
+    /// Directory to write the forecasts of --count to, named by their number and seed
+    #[arg(long, requires = "count", default_value = "./batch")]
+    batch_dir: PathBuf,
+
+    /// Ask whether to accept the forecast or to reroll it before copying or applying it
+    // the conflict is declared here, global arguments cannot refer to arguments of the top level
+    #[arg(short, long, conflicts_with = "quiet")]
+    interactive: bool,
+
+    /// Print the forecast as exactly one JSON or YAML document and everything meant for humans
+    /// to stderr, e.g. to pipe it into jq. Only for commands printing a single forecast
+    #[arg(long, value_enum, conflicts_with_all = ["interactive", "count"])]
+    payload: Option<Payload>,
+
+    /// Open a full screen dashboard to tweak probabilities and reroll before exporting
+    #[cfg(feature = "tui")]
+    #[arg(long, conflicts_with_all = ["interactive", "quiet", "payload"])]
+    tui: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+/// Document printed to stdout with --payload.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Payload {
+    Json,
+    Yaml,
+}
+
+impl Payload {
+    fn format(self) -> OutputFormat {
+        match self {
+            Payload::Json => OutputFormat::Json,
+            Payload::Yaml => OutputFormat::Yaml,
+        }
+    }
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Write the forecast directly into the game's settings file (e.g. player.JSON)
+    Apply {
+        /// Settings file to update
+        #[arg(short, long)]
+        file: PathBuf,
+    },
+
+    /// Generate a single session again while keeping the others of a saved forecast
+    Reroll {
+        /// Session to generate again
+        session: String,
+
+        /// Saved forecast, either a settings file or the copied output of a previous run
+        #[arg(short, long)]
+        file: PathBuf,
+    },
+
+    /// Print a forecast of the history again, selected by its id, the global seed option or the
+    /// latest one. Unknown seeds are generated again with the current config.
+    Replay {
+        /// Id of the forecast in the history
+        id: Option<usize>,
+
+        /// Settings file to write the replayed forecast to
+        #[arg(short, long)]
+        file: Option<PathBuf>,
+    },
+
+    /// Generate the forecasts of all events of a calendar at once
+    Season {
+        /// Calendar file listing the events
+        calendar: PathBuf,
+
+        /// Directory to write one forecast per event and a summary to
+        #[arg(short, long, default_value = "./season")]
+        output_dir: PathBuf,
+    },
+
+    /// Work with the config file
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+
+    /// Generate many forecasts and print how often every weather appeared per session
+    Simulate {
+        /// Number of weekends to simulate
+        #[arg(short, long, default_value_t = 1000)]
+        runs: usize,
+        /// Test how well the weather of each session fits the configured probabilities
+        #[arg(long)]
+        fit: bool,
+    },
+
+    /// Print the chance of rain and the expected cloud cover of every session and slot instead of
+    /// picking the weather
+    Outlook {
+        /// Number of weekends to simulate
+        #[arg(short, long, default_value_t = 1000)]
+        runs: usize,
+        /// Print the outlook as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Generate the weather of an endurance race hour by hour and compress it into the weather
+    /// slots of the race
+    Endurance {
+        /// Length of the race in hours
+        #[arg(long, default_value_t = 24, value_parser = clap::value_parser!(u32).range(1..=48))]
+        hours: u32,
+        /// Local time the race starts at, defaults to the start time of the race in the config or
+        /// noon
+        #[arg(long, value_name = "HH:MM")]
+        start: Option<TimeOfDay>,
+    },
+
+    /// Print the probabilities the weather is picked with, after the config has been applied
+    Probabilities {
+        /// Print them as JSON instead of tables
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// List the built-in circuits and the ones of the config
+    Circuits,
+
+    /// Serve the forecasts of the history as read-only web pages, the latest one at `/`
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        address: String,
+    },
+
+    /// Serve the forecaster as a gRPC service, see proto/weather_forecaster.proto
+    #[cfg(feature = "grpc")]
+    Grpc {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:50051")]
+        address: std::net::SocketAddr,
+    },
+
+    /// Draw charts of the config as SVG
+    Plot {
+        /// Width of the charts, the legends and axes need at least 400
+        #[arg(long, global = true, default_value_t = PlotSize::default().width)]
+        width: f64,
+
+        /// Height of the charts, the legends and axes need at least 250
+        #[arg(long, global = true, default_value_t = PlotSize::default().height)]
+        height: f64,
+
+        /// Factor the charts are displayed larger by, e.g. 2 for high DPI screens
+        #[arg(long, global = true, default_value_t = PlotSize::default().scale)]
+        scale: f64,
+
+        #[command(subcommand)]
+        command: PlotCommand,
+    },
+}
+
+impl Command {
+    /// Whether the command prints a single forecast, the only output --payload supports.
+    fn prints_forecast(&self) -> bool {
+        matches!(
+            self,
+            Command::Apply { .. }
+                | Command::Reroll { .. }
+                | Command::Replay { .. }
+                | Command::Endurance { .. }
+        )
+    }
+}
+
+#[derive(Debug, Subcommand)]
+enum ConfigCommand {
+    /// Check the config file for problems without generating a forecast
+    Validate,
+
+    /// Upgrade the config file to the current version, the original is kept as a backup
+    Migrate,
+
+    /// Print the JSON Schema of config files, e.g. for editors to validate and complete them
+    Schema {
+        /// Write the schema to a file instead
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum PlotCommand {
+    /// Pie chart of the probabilities of the weather options, grouped by weather group
+    Probabilities {
+        /// File to write the chart to
+        #[arg(short, long, default_value = "probabilities.svg")]
+        output: PathBuf,
+    },
+
+    /// Bar chart of how often every weather option appeared per session in simulated weekends
+    Outcomes {
+        /// Number of weekends to simulate
+        #[arg(short, long, default_value_t = 1000)]
+        runs: usize,
+
+        /// File to write the chart to
+        #[arg(short, long, default_value = "outcomes.svg")]
+        output: PathBuf,
+    },
+
+    /// Line chart of the probabilities every slot was picked with over simulated weekends
+    History {
+        /// Number of weekends to simulate
+        #[arg(short, long, default_value_t = 10)]
+        runs: usize,
+
+        /// File to write the chart to
+        #[arg(short, long, default_value = "probability_history.svg")]
+        output: PathBuf,
+
+        /// Stack the probabilities of the options of each weather group instead of a line per
+        /// option
+        #[arg(long)]
+        groups: bool,
+
+        /// Print the chart as text instead of writing it to a file
+        #[arg(long, conflicts_with = "groups")]
+        terminal: bool,
+    },
 }
 
 fn main() {
     let args = Args::parse();
+    if args.payload.is_some() && args.command.as_ref().is_some_and(|c| !c.prints_forecast()) {
+        Args::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "--payload only works with commands printing a single forecast",
+            )
+            .exit();
+    }
+    let level = match (args.quiet, args.verbose) {
+        (true, _) => tracing::Level::ERROR,
+        (false, 0) => tracing::Level::WARN,
+        (false, 1) => tracing::Level::INFO,
+        (false, 2) => tracing::Level::DEBUG,
+        (false, _) => tracing::Level::TRACE,
+    };
+    tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_writer(std::io::stderr)
+        .with_ansi(!args.no_color && std::io::stderr().is_terminal())
+        .without_time()
+        .with_target(false)
+        .init();
+    if args.no_color {
+        anstream::ColorChoice::Never.write_global();
+    }
+    let format = args
+        .config_format
+        .unwrap_or_else(|| ConfigFormat::from_path(&args.config_file));
+
+    if let Some(Command::Config { command }) = &args.command {
+        match command {
+            ConfigCommand::Validate => validate_config(&args.config_file, format),
+            ConfigCommand::Migrate => {
+                match Config::migrate_file(&args.config_file, format).unwrap_or_print() {
+                    Some(version) => println!(
+                        "Migrated {} from version {version} to version {CONFIG_VERSION}",
+                        args.config_file.display()
+                    ),
+                    None => println!("{} is up to date", args.config_file.display()),
+                }
+            }
+            ConfigCommand::Schema { output } => {
+                let schema = serde_json::to_string_pretty(&Config::json_schema()).unwrap_or_print();
+                match output {
+                    Some(path) => std::fs::write(path, schema).unwrap_or_print(),
+                    None => println!("{schema}"),
+                }
+            }
+        }
+        return;
+    }
 
     if !std::fs::exists(&args.config_file).unwrap_or_print() {
-        Config::generate_default_config(&args.config_file).unwrap_or_print();
+        tracing::info!("Creating the default config {}", args.config_file.display());
+        Config::generate_default_config(&args.config_file, format).unwrap_or_print();
+    }
+
+    tracing::info!("Loading the config {}", args.config_file.display());
+    let mut config = Config::load(&args.config_file, format, args.strict).unwrap_or_print();
+    if let Some(profile) = &args.profile {
+        config = config.with_profile(profile).unwrap_or_print();
+    }
+    if args.circuit.is_some() {
+        config.circuit = args.circuit.clone();
+    }
+    if args.track.is_some() {
+        config.track = args.track;
+    }
+    config = config
+        .with_track_overrides(&args.config_file, args.strict)
+        .unwrap_or_print()
+        .with_env_overrides(std::env::vars(), args.strict)
+        .unwrap_or_print();
+    if args.template.is_some() {
+        config.template = args.template.clone();
+    }
+    if args.output.is_some() {
+        config.output.file = args.output.clone();
+        config.output.append = args.append;
+        config.output.overwrite = args.force;
+    }
+    if let Some(format) = args.format {
+        config.output.format = format;
+    }
+    config.output.stdout |= args.stdout;
+    config.emoji |= args.emoji;
+    if let Some(date) = args.climate {
+        apply_climate(&mut config, args.location, date, args.climate_years);
+    }
+    if let Some(coordinates) = args.live {
+        apply_live_weather(&mut config, coordinates);
+    }
+    if let Some(metar) = &args.metar {
+        apply_metar(&mut config, metar);
+    }
+    config
+        .probabilities
+        .extend(args.probability.iter().copied());
+    config
+        .constraints
+        .require
+        .extend(args.require.iter().cloned());
+    config
+        .constraints
+        .forbid
+        .extend(args.forbid.iter().cloned());
+    config
+        .constraints
+        .at_least_one
+        .extend(args.at_least_one.iter().cloned());
+    config.constraints.smooth_rain |= args.smooth_rain;
+    if let Some(realism) = args.realism {
+        config.realism = realism;
+    }
+
+    let sessions = sessions::resolve(&config.sessions, &args.sessions).unwrap_or_print();
+    let template = config
+        .template
+        .as_deref()
+        .map(template::load)
+        .transpose()
+        .unwrap_or_print();
+
+    // always seed explicitly so the forecast can be reproduced from the history
+    let mut seed = args.seed.unwrap_or_else(rand::random);
+    tracing::debug!("Seed {seed}");
+    if let Some(Command::Season {
+        calendar,
+        output_dir,
+    }) = &args.command
+    {
+        generate_season(
+            &config,
+            calendar,
+            output_dir,
+            &sessions,
+            template.as_deref(),
+            seed,
+        );
+        return;
+    }
+    if let (Some(count), None) = (args.count, &args.command) {
+        generate_batch(
+            &config,
+            count as usize,
+            &args.batch_dir,
+            &sessions,
+            template.as_deref(),
+            seed,
+        );
+        return;
+    }
+
+    let mut forecaster = WeatherForecaster::new(config.clone())
+        .unwrap_or_print()
+        .with_seed(seed);
+
+    if let Some(Command::Simulate { runs, fit }) = &args.command {
+        let simulation =
+            Simulation::run_parallel(&config, &sessions, *runs, seed).unwrap_or_print();
+        print!("{simulation}");
+        if *fit {
+            println!();
+            for session in simulation.counts.keys() {
+                println!(
+                    "{session}: {}",
+                    simulation
+                        .goodness_of_fit(*session, &forecaster)
+                        .unwrap_or_print()
+                );
+            }
+        }
+        return;
+    }
+
+    if let Some(Command::Circuits) = &args.command {
+        print_circuits(&config);
+        return;
+    }
+
+    if let Some(Command::Serve { address }) = &args.command {
+        let Some(history) = config.history.history() else {
+            eprintln!("The history is disabled");
+            exit(1)
+        };
+        let listener = std::net::TcpListener::bind(address).unwrap_or_else(|error| {
+            eprintln!("Failed to listen on {address}: {error}");
+            exit(1)
+        });
+        eprintln!(
+            "Serving the forecasts of {} on http://{address}",
+            history.path().display()
+        );
+        if let Err(error) = web::serve(&listener, &history, &forecaster) {
+            eprintln!("The server stopped: {error}");
+            exit(1)
+        }
+        return;
+    }
+
+    #[cfg(feature = "grpc")]
+    if let Some(Command::Grpc { address }) = &args.command {
+        let service = weather_forecaster::grpc::ForecastService::new(config).unwrap_or_print();
+        let runtime = tokio::runtime::Runtime::new().unwrap_or_else(|error| {
+            eprintln!("Failed to start the runtime: {error}");
+            exit(1)
+        });
+        eprintln!("Serving gRPC on {address}");
+        if let Err(error) = runtime.block_on(weather_forecaster::grpc::serve(*address, service)) {
+            eprintln!("The server stopped: {error}");
+            exit(1)
+        }
+        return;
+    }
+
+    if let Some(Command::Outlook { runs, json }) = &args.command {
+        let outlook = Outlook::generate(&mut forecaster, &sessions, *runs).unwrap_or_print();
+        if *json {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&outlook).unwrap_or_print()
+            );
+        } else {
+            print!("{outlook}");
+        }
+        return;
+    }
+
+    if let Some(Command::Endurance { hours, start }) = &args.command {
+        let start = start
+            .or_else(|| config.start_times.get(&Sessions::Race).copied())
+            .unwrap_or(TimeOfDay::new(12, 0).unwrap_or_print());
+        let hourly = forecaster.generate_hourly(*hours as usize, start);
+        let forecast = forecaster.compress_hourly(&hourly, Sessions::Race);
+        if let Some(payload) = args.payload {
+            if !args.quiet {
+                eprintln!("{hourly}");
+            }
+            print_payload(payload, &forecast, None, seed, &[], args.quiet);
+        } else {
+            print!("{hourly}");
+            println!();
+            print_forecast(&forecast, None, seed, &[]);
+        }
+        return;
+    }
+
+    if let Some(Command::Probabilities { json }) = &args.command {
+        print_probabilities(&forecaster, &sessions, *json);
+        return;
+    }
+
+    if let Some(Command::Plot {
+        width,
+        height,
+        scale,
+        command,
+    }) = &args.command
+    {
+        let size = PlotSize {
+            width: *width,
+            height: *height,
+            scale: *scale,
+        };
+        size.validate().unwrap_or_print();
+        let (chart, path) = match command {
+            PlotCommand::Probabilities { output } => {
+                (plot::probability_pie(&forecaster, &size), output)
+            }
+            PlotCommand::Outcomes { runs, output } => {
+                let simulation =
+                    Simulation::run(&mut forecaster, &sessions, *runs).unwrap_or_print();
+                (plot::outcome_histogram(&simulation, &size), output)
+            }
+            PlotCommand::History {
+                runs,
+                output,
+                groups,
+                terminal,
+            } => {
+                forecaster = forecaster.with_probability_history();
+                Simulation::run(&mut forecaster, &sessions, *runs).unwrap_or_print();
+                let history = forecaster
+                    .probability_history()
+                    .expect("the history is recorded");
+                if *terminal {
+                    print!("{}", plot::terminal_history(history, 72, 20));
+                    return;
+                }
+                let chart = if *groups {
+                    plot::group_history(history, &forecaster.groups(), &size)
+                } else {
+                    plot::probability_history(history, &size)
+                };
+                (chart, output)
+            }
+        };
+        std::fs::write(path, chart).unwrap_or_print();
+        println!("Wrote chart to {}", path.display());
+        return;
+    }
+
+    // sessions rerolled after generating the forecast with the seed, which no longer reproduces it
+    let mut rerolled = Vec::new();
+    let mut forecast = match &args.command {
+        Some(Command::Reroll { session, file }) => {
+            let session = sessions::resolve(&config.sessions, std::slice::from_ref(session))
+                .unwrap_or_print()[0];
+            let saved = std::fs::read_to_string(file).unwrap_or_print();
+            let saved = forecaster
+                .restore_forecast(read_forecast(&saved, &config.sessions).unwrap_or_print())
+                .unwrap_or_print();
+            rerolled.push(session);
+            forecaster.reroll(&saved, session).unwrap_or_print()
+        }
+        Some(Command::Replay { id, .. }) => {
+            let (forecast, replayed) = replay(&config, &mut forecaster, *id, args.seed, &sessions);
+            seed = replayed;
+            forecast
+        }
+        _ => forecaster
+            .generate_constrained_forecast(&sessions)
+            .unwrap_or_print(),
+    };
+
+    #[cfg(feature = "tui")]
+    if args.tui {
+        match weather_forecaster::tui::run(&mut forecaster, &sessions, forecast).unwrap_or_print() {
+            Some((accepted, rerolled_sessions)) => {
+                forecast = accepted;
+                rerolled = rerolled_sessions;
+            }
+            None => return,
+        }
     }
 
-    let config: Config =
-        serde_yaml::from_str(&std::fs::read_to_string(&args.config_file).unwrap_or_print())
+    let print = config.output.file.is_none() || config.output.stdout || args.interactive;
+    // the forecast with its reports, unless a format is asked for
+    let show = |forecast: &WeatherForecast, seed: u64, rerolled: &[Sessions]| {
+        if args.quiet || args.format.is_some() {
+            let rendered = config
+                .output
+                .format
+                .render(forecast, template.as_deref(), Some(seed), config.emoji)
+                .unwrap_or_print();
+            print!("{rendered}");
+            if !rendered.ends_with('\n') {
+                println!();
+            }
+        } else {
+            print_forecast(forecast, template.as_deref(), seed, rerolled);
+        }
+    };
+    if let Some(payload) = args.payload {
+        print_payload(
+            payload,
+            &forecast,
+            template.as_deref(),
+            seed,
+            &rerolled,
+            args.quiet,
+        );
+    } else if print {
+        show(&forecast, seed, &rerolled);
+    }
+    if args.interactive {
+        loop {
+            match prompt().unwrap_or_print() {
+                Choice::Accept => break,
+                Choice::RerollRace if !forecast.contains(Sessions::Race) => {
+                    println!("The forecast has no race to reroll");
+                    continue;
+                }
+                Choice::RerollRace => {
+                    forecast = forecaster
+                        .reroll(&forecast, Sessions::Race)
+                        .unwrap_or_print();
+                    if !rerolled.contains(&Sessions::Race) {
+                        rerolled.push(Sessions::Race);
+                    }
+                }
+                Choice::RerollAll => {
+                    // a fresh seed keeps the accepted forecast reproducible
+                    seed = rand::random();
+                    forecaster = forecaster.with_seed(seed);
+                    forecast = forecaster
+                        .generate_constrained_forecast(&sessions)
+                        .unwrap_or_print();
+                    rerolled.clear();
+                }
+                Choice::Quit => return,
+            }
+            show(&forecast, seed, &rerolled);
+        }
+    }
+
+    if !matches!(args.command, Some(Command::Replay { .. })) {
+        archive(&config, seed, &forecast, &rerolled);
+    }
+    config
+        .output
+        .write(&forecast, template.as_deref(), Some(seed), config.emoji)
+        .unwrap_or_print();
+    if let Some(webhook) = &config.discord_webhook {
+        publish(webhook, &forecast, seed);
+    }
+    if let Some(mqtt) = &config.mqtt {
+        publish_mqtt(mqtt, &forecast, seed);
+    }
+
+    if let Some(
+        Command::Apply { file }
+        | Command::Replay {
+            file: Some(file), ..
+        },
+    ) = &args.command
+    {
+        let settings = std::fs::read_to_string(file).unwrap_or_print();
+        let settings = apply_forecast(&settings, &forecast).unwrap_or_print();
+        std::fs::write(file, settings).unwrap_or_print();
+        eprintln!("Applied forecast to {}", file.display());
+        return;
+    }
+
+    if config.set_clipboard {
+        let content = config
+            .clipboard_format
+            .render(&forecast, template.as_deref(), Some(seed), config.emoji)
             .unwrap_or_print();
+        copy_to_clipboard(content);
+    }
+}
+
+#[cfg(feature = "clipboard")]
+fn copy_to_clipboard(content: String) {
+    use cli_clipboard::{ClipboardContext, ClipboardProvider};
+
+    if let Ok(mut clipboard) = ClipboardContext::new() {
+        clipboard.set_contents(content).unwrap();
+    }
+}
 
-    let mut forecaster = WeatherForecaster::new(config.clone());
+#[cfg(not(feature = "clipboard"))]
+fn copy_to_clipboard(_: String) {
+    tracing::warn!("Copying the forecast to the clipboard requires the clipboard feature");
+}
+
+fn archive(config: &Config, seed: u64, forecast: &WeatherForecast, rerolled: &[Sessions]) {
+    let entry = HistoryEntry::new(seed, forecast).with_rerolled(rerolled);
+    if let Some(history) = config.history.history()
+        && let Err(error) = history.append(&entry)
+    {
+        tracing::warn!("Failed to archive the forecast: {error}");
+    }
+    if let Some(database) = &config.history.database {
+        store_forecast(database, &entry, config, forecast);
+    }
+}
+
+/// Content of a file of a season or batch, the forecast with its reports or the template.
+fn forecast_file(forecast: &WeatherForecast, template: Option<&str>, seed: u64) -> String {
+    match template {
+        Some(template) => template::render(template, forecast, Some(seed)).unwrap_or_print(),
+        None => format!(
+            "{forecast}{}\n{}{}\n",
+            forecast.conditions_report(),
+            forecast.summary(),
+            forecast.narrative()
+        ),
+    }
+}
+
+fn generate_batch(
+    config: &Config,
+    count: usize,
+    output_dir: &Path,
+    sessions: &[Sessions],
+    template: Option<&str>,
+    seed: u64,
+) {
+    let batch = batch::generate(config, sessions, count, seed).unwrap_or_print();
+
+    std::fs::create_dir_all(output_dir).unwrap_or_print();
+    for (index, entry) in batch.iter().enumerate() {
+        let path = output_dir.join(format!("{:02}-{}.txt", index + 1, entry.seed));
+        let content = forecast_file(&entry.forecast, template, entry.seed);
+        std::fs::write(&path, content).unwrap_or_print();
+        archive(config, entry.seed, &entry.forecast, &[]);
+        println!("{}: {}", path.display(), entry.forecast.narrative());
+    }
+    println!(
+        "Wrote {} forecasts to {}",
+        batch.len(),
+        output_dir.display()
+    );
+}
+
+fn generate_season(
+    config: &Config,
+    calendar: &Path,
+    output_dir: &Path,
+    sessions: &[Sessions],
+    template: Option<&str>,
+    seed: u64,
+) {
+    let calendar = Calendar::load(calendar, ConfigFormat::from_path(calendar)).unwrap_or_print();
+    let season = calendar.generate(config, sessions, seed).unwrap_or_print();
+
+    std::fs::create_dir_all(output_dir).unwrap_or_print();
+    for (index, event) in season.iter().enumerate() {
+        let path = output_dir.join(format!("{}.txt", event.file_stem(index + 1)));
+        let content = forecast_file(&event.forecast, template, event.seed);
+        std::fs::write(path, content).unwrap_or_print();
+        archive(config, event.seed, &event.forecast, &[]);
+    }
 
-    let forecast = forecaster.generate_forecast(&args.sessions);
+    let summary = SeasonSummary(&season).to_string();
+    std::fs::write(output_dir.join("summary.txt"), &summary).unwrap_or_print();
+    println!();
+    print!("{summary}");
+    println!(
+        "Wrote the forecasts of {} events to {}",
+        season.len(),
+        output_dir.display()
+    );
+}
+
+fn replay(
+    config: &Config,
+    forecaster: &mut WeatherForecaster,
+    id: Option<usize>,
+    seed: Option<u64>,
+    sessions: &[Sessions],
+) -> (WeatherForecast, u64) {
+    let Some(history) = config.history.history() else {
+        eprintln!("The history is disabled");
+        exit(1)
+    };
+    let entry = match (id, seed) {
+        (Some(id), _) => history.get(id).unwrap_or_print(),
+        (None, Some(seed)) => history
+            .entries()
+            .unwrap_or_print()
+            .into_iter()
+            .rfind(|entry| entry.seed == seed),
+        (None, None) => history.entries().unwrap_or_print().pop(),
+    };
+
+    match (entry, seed) {
+        (Some(entry), _) => {
+            eprintln!(
+                "Replaying forecast from {} with seed {}",
+                entry.timestamp, entry.seed
+            );
+            (entry.restore(forecaster).unwrap_or_print(), entry.seed)
+        }
+        (None, Some(seed)) if id.is_none() => {
+            eprintln!("Seed {seed} is not part of the history, generating it again");
+            let forecast = forecaster
+                .generate_constrained_forecast(sessions)
+                .unwrap_or_print();
+            (forecast, seed)
+        }
+        (None, _) => {
+            eprintln!("No matching forecast in {}", history.path().display());
+            exit(1)
+        }
+    }
+}
+
+#[cfg(feature = "sqlite")]
+fn store_forecast(path: &Path, entry: &HistoryEntry, config: &Config, forecast: &WeatherForecast) {
+    let result = weather_forecaster::store::ForecastStore::open(path)
+        .and_then(|mut store| store.insert(entry.timestamp, entry.seed, config, forecast));
+    if let Err(error) = result {
+        tracing::warn!(
+            "Failed to store the forecast in {}: {error}",
+            path.display()
+        );
+    }
+}
+
+#[cfg(not(feature = "sqlite"))]
+fn store_forecast(path: &Path, _: &HistoryEntry, _: &Config, _: &WeatherForecast) {
+    tracing::warn!(
+        "Storing forecasts in {} requires the sqlite feature",
+        path.display()
+    );
+}
+
+/// Replaces the probabilities of the config with how often each weather appeared in the past.
+#[cfg(feature = "online")]
+fn apply_climate(config: &mut Config, location: Option<Coordinates>, date: EventDate, years: u16) {
+    let coordinates = track_coordinates(config, location);
+    tracing::info!("Fetching the weather of the past {years} years at {coordinates}");
+    config.probabilities = climatology::fetch(coordinates, date, years).unwrap_or_print();
+}
+
+#[cfg(not(feature = "online"))]
+fn apply_climate(_config: &mut Config, _location: Option<Coordinates>, _date: EventDate, _: u16) {
+    tracing::warn!("Historical weather requires the online feature, using the config instead");
+}
+
+/// Replaces the probabilities of the config with how often each weather is forecast.
+#[cfg(feature = "online")]
+fn apply_live_weather(config: &mut Config, coordinates: Option<Coordinates>) {
+    let coordinates = track_coordinates(config, coordinates);
+    tracing::info!("Fetching the live weather at {coordinates}");
+    let forecast = weather_forecaster::live::fetch(coordinates).unwrap_or_print();
+    config.probabilities = forecast.probabilities().unwrap_or_print();
+}
+
+/// Starts the leading session with the reported weather and lets the other slots follow it.
+fn apply_metar(config: &mut Config, metar: &str) {
+    let Some(metar) = read_metar(metar) else {
+        return;
+    };
+    tracing::info!("Current weather at {}: {:?}", metar.station, metar.weather);
+    config
+        .start_weather
+        .insert(config.coupling.leader, metar.weather);
+    config.transitions.get_or_insert_default();
+}
+
+fn read_metar(metar: &str) -> Option<Metar> {
+    if !metar::is_station_code(metar) {
+        return Some(metar.parse().unwrap_or_print());
+    }
+    #[cfg(feature = "online")]
+    return Some(metar::fetch(metar).unwrap_or_print());
+    #[cfg(not(feature = "online"))]
+    {
+        tracing::warn!("Fetching METAR reports requires the online feature, pass a report instead");
+        None
+    }
+}
+
+/// The given coordinates or those of the circuit or track preset.
+#[cfg(feature = "online")]
+fn track_coordinates(config: &Config, coordinates: Option<Coordinates>) -> Coordinates {
+    let circuit = config.find_circuit().unwrap_or_print();
+    coordinates
+        .or_else(|| circuit.map(|circuit| circuit.coordinates()))
+        .or_else(|| config.track.map(|track| track.coordinates()))
+        .ok_or_else(|| {
+            weather_forecaster::ForecastError::LiveWeather(
+                "pass coordinates or choose a track preset".to_string(),
+            )
+        })
+        .unwrap_or_print()
+}
+
+#[cfg(not(feature = "online"))]
+fn apply_live_weather(_config: &mut Config, _coordinates: Option<Coordinates>) {
+    tracing::warn!("Live weather requires the online feature, using the config instead");
+}
+
+#[cfg(feature = "discord")]
+fn publish(webhook: &str, forecast: &WeatherForecast, seed: u64) {
+    if let Err(error) = weather_forecaster::discord::publish(webhook, forecast, Some(seed)) {
+        tracing::warn!("{error}");
+    }
+}
+
+#[cfg(not(feature = "discord"))]
+fn publish(_: &str, _: &WeatherForecast, _: u64) {
+    tracing::warn!("Posting forecasts to Discord requires the discord feature");
+}
+
+#[cfg(feature = "mqtt")]
+fn publish_mqtt(mqtt: &MqttConfig, forecast: &WeatherForecast, seed: u64) {
+    if let Err(error) = weather_forecaster::mqtt::publish(mqtt, forecast, Some(seed)) {
+        tracing::warn!("{error}");
+    }
+}
+
+#[cfg(not(feature = "mqtt"))]
+fn publish_mqtt(_: &MqttConfig, _: &WeatherForecast, _: u64) {
+    tracing::warn!("Publishing forecasts to MQTT requires the mqtt feature");
+}
+
+fn validate_config(path: &Path, format: ConfigFormat) {
+    let content = std::fs::read_to_string(path).unwrap_or_print();
+    let issues = Config::validate(&content, format);
+    if issues.is_empty() {
+        println!("{} is valid", path.display());
+        return;
+    }
+
+    for issue in &issues {
+        eprintln!("{}: {issue}", path.display());
+    }
+    eprintln!("Found {} problem(s) in {}", issues.len(), path.display());
+    exit(1)
+}
+
+fn parse_probability(value: &str) -> Result<(WeatherOptions, f64), String> {
+    let (option, probability) = value
+        .split_once('=')
+        .ok_or_else(|| format!("expected OPTION=PROBABILITY, got '{value}'"))?;
+    let option = option
+        .parse::<WeatherOptions>()
+        .map_err(|error| error.to_string())?;
+    let probability = probability
+        .trim()
+        .parse()
+        .map_err(|error| format!("invalid probability '{probability}': {error}"))?;
+    Ok((option, probability))
+}
+
+fn print_forecast(
+    forecast: &WeatherForecast,
+    template: Option<&str>,
+    seed: u64,
+    rerolled: &[Sessions],
+) {
+    if let Some(template) = template {
+        print!(
+            "{}",
+            template::render(template, forecast, Some(seed)).unwrap_or_print()
+        );
+        return;
+    }
 
     println!("Forecast for your next Raceday:");
     println!("// {}\n", "=".repeat(80));
-    print!("{forecast}");
+    anstream::print!("{forecast:#}");
     println!("// {}", "=".repeat(80));
+    println!();
+    print!("{}", forecast.conditions_report());
+    println!();
+    print!("{}", forecast.summary());
+    println!("{}", forecast.narrative());
+    println!();
+    println!("{}", seed_note(seed, rerolled));
+}
 
-    if let Ok(mut clipboard) = ClipboardContext::new()
-        && config.set_clipboard
-    {
-        clipboard.set_contents(forecast.to_string()).unwrap();
+/// How the forecast can be generated again, which the seed alone cannot once sessions got
+/// rerolled.
+fn seed_note(seed: u64, rerolled: &[Sessions]) -> String {
+    if rerolled.is_empty() {
+        return format!("Seed: {seed} (generate this forecast again with --seed {seed})");
+    }
+    let rerolled: Vec<_> = rerolled.iter().map(ToString::to_string).collect();
+    format!(
+        "Seed: {seed} (the seed does not reproduce this forecast, {} got rerolled)",
+        rerolled.join(", ")
+    )
+}
+
+/// Prints the single document of --payload to stdout, the forecast for humans goes to stderr
+/// unless `quiet`.
+fn print_payload(
+    payload: Payload,
+    forecast: &WeatherForecast,
+    template: Option<&str>,
+    seed: u64,
+    rerolled: &[Sessions],
+    quiet: bool,
+) {
+    if !quiet {
+        eprint!("{}", forecast_file(forecast, template, seed));
+        eprintln!("{}", seed_note(seed, rerolled));
+    }
+    let document = payload
+        .format()
+        .render(forecast, None, Some(seed), false)
+        .unwrap_or_print();
+    print!("{document}");
+    if !document.ends_with('\n') {
+        println!();
+    }
+}
+
+/// Prints the base probabilities and those of the sessions with their own adjustments, as JSON
+/// every session is listed.
+fn print_probabilities(forecaster: &WeatherForecaster, sessions: &[Sessions], json: bool) {
+    if json {
+        let sessions: IndexMap<_, _> = sessions
+            .iter()
+            .map(|session| {
+                let probabilities = by_name(forecaster.session_probabilities(*session));
+                (session.to_string(), probabilities)
+            })
+            .collect();
+        let report = ProbabilityReport {
+            probabilities: by_name(forecaster.probabilities()),
+            sessions,
+        };
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&report).unwrap_or_print()
+        );
+        return;
+    }
+
+    anstream::print!("{}", forecaster.colored_probability_table(None));
+    for session in sessions {
+        if forecaster.has_session_probabilities(*session) {
+            println!("\n{session}:");
+            anstream::print!("{}", forecaster.colored_probability_table(Some(*session)));
+        }
+    }
+}
+
+fn print_circuits(config: &Config) {
+    for circuit in config.circuits.iter().cloned().chain(circuits::built_in()) {
+        let climate = circuit
+            .climate
+            .map(|climate| format!(", climate {}", format!("{climate:?}").to_lowercase()))
+            .unwrap_or_default();
+        println!(
+            "{} ({}) at {}{climate}",
+            circuit.name,
+            circuit.country.as_deref().unwrap_or("custom"),
+            circuit.coordinates(),
+        );
+    }
+}
+
+/// Probabilities by the name of the catalog entry, in the order of the catalog.
+#[derive(Serialize)]
+struct ProbabilityReport {
+    probabilities: IndexMap<String, f64>,
+    sessions: IndexMap<String, IndexMap<String, f64>>,
+}
+
+fn by_name<'a>(
+    probabilities: impl Iterator<Item = (&'a CatalogEntry, f64)>,
+) -> IndexMap<String, f64> {
+    probabilities
+        .map(|(entry, probability)| (entry.name.clone(), probability))
+        .collect()
+}
+
+enum Choice {
+    Accept,
+    RerollRace,
+    RerollAll,
+    Quit,
+}
+
+fn prompt() -> std::io::Result<Choice> {
+    let stdin = std::io::stdin();
+    loop {
+        print!("\n[a]ccept / reroll [r]ace / reroll a[l]l / [q]uit: ");
+        std::io::stdout().flush()?;
+
+        let mut line = String::new();
+        if stdin.read_line(&mut line)? == 0 {
+            return Ok(Choice::Quit);
+        }
+        match line.trim().to_lowercase().as_str() {
+            "a" | "accept" => return Ok(Choice::Accept),
+            "r" | "race" | "reroll race" => return Ok(Choice::RerollRace),
+            "l" | "all" | "reroll all" => return Ok(Choice::RerollAll),
+            "q" | "quit" => return Ok(Choice::Quit),
+            other => println!("Unknown choice '{other}'"),
+        }
     }
 }
 
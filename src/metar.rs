@@ -0,0 +1,240 @@
+use std::str::FromStr;
+
+use crate::{error::ForecastError, forecaster::WeatherOptions};
+
+const AVIATION_WEATHER_URL: &str = "https://aviationweather.gov/api/data/metar";
+
+/// Precipitation codes of METAR weather groups.
+const PRECIPITATION: &[&str] = &["DZ", "RA", "SN", "SG", "PL", "GR", "GS", "IC", "UP"];
+/// Descriptor and obscuration codes that may be combined with precipitation.
+const OTHER_CODES: &[&str] = &[
+    "MI", "BC", "PR", "DR", "BL", "SH", "TS", "FZ", "BR", "FG", "FU", "VA", "DU", "SA", "HZ", "PY",
+    "PO", "SQ", "FC", "SS", "DS",
+];
+
+/// Current weather at an airport, parsed from a METAR report such as
+/// `EBLG 121350Z 24012KT 6000 -RA BKN012 OVC025 12/10 Q1008`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Metar {
+    /// ICAO code of the station
+    pub station: String,
+    /// Weather option closest to the reported conditions
+    pub weather: WeatherOptions,
+}
+
+#[derive(Debug, Default)]
+struct Observation {
+    /// Eighths of the sky covered by clouds
+    cloud_cover: u8,
+    /// Visibility in meters
+    visibility: Option<u32>,
+    /// Intensity of the precipitation, `None` if there is none
+    precipitation: Option<Intensity>,
+    thunderstorm: bool,
+    fog: bool,
+    mist: bool,
+    haze: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Intensity {
+    Light,
+    Moderate,
+    Heavy,
+}
+
+impl FromStr for Metar {
+    type Err = ForecastError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut tokens = s
+            .split_whitespace()
+            .skip_while(|token| ["METAR", "SPECI"].contains(token));
+        let station = tokens
+            .next()
+            .filter(|station| is_station(station))
+            .ok_or_else(|| ForecastError::InvalidMetar(s.trim().to_string()))?;
+
+        let mut observation = Observation::default();
+        // remarks and trends do not describe the current weather
+        for token in tokens.take_while(|token| !["RMK", "BECMG", "TEMPO", "NOSIG"].contains(token))
+        {
+            observation.read(token);
+        }
+        Ok(Self {
+            station: station.to_string(),
+            weather: observation.weather(),
+        })
+    }
+}
+
+impl Observation {
+    fn read(&mut self, token: &str) {
+        if token == "CAVOK" {
+            self.visibility = Some(10_000);
+        } else if let Some(cover) = cloud_cover(token) {
+            self.cloud_cover = self.cloud_cover.max(cover);
+        } else if token.starts_with("VV") {
+            // vertical visibility, the sky is obscured
+            self.fog = true;
+        } else if let Some(visibility) = visibility(token) {
+            self.visibility = Some(visibility);
+        } else if let Some((intensity, codes)) = weather_group(token) {
+            let has = |code: &str| codes.contains(&code);
+            if codes.iter().any(|code| PRECIPITATION.contains(code)) {
+                let intensity = if has("DZ") {
+                    Intensity::Light
+                } else {
+                    intensity
+                };
+                self.precipitation = self.precipitation.max(Some(intensity));
+            }
+            self.thunderstorm |= has("TS");
+            self.fog |= has("FG");
+            self.mist |= has("BR");
+            self.haze |= ["HZ", "FU", "DU", "SA"].iter().any(|code| has(code));
+        }
+    }
+
+    fn weather(&self) -> WeatherOptions {
+        let dense = self.visibility.is_some_and(|visibility| visibility < 200);
+        match (self.thunderstorm, self.precipitation, self.fog || self.mist) {
+            (true, _, _) => WeatherOptions::Thunderstorm,
+            (_, Some(_), true) if dense => WeatherOptions::HeavyFogWithRain,
+            (_, Some(_), true) => WeatherOptions::FogWithRain,
+            (_, Some(Intensity::Light), _) => WeatherOptions::LightRain,
+            (_, Some(Intensity::Moderate), _) => WeatherOptions::Rain,
+            (_, Some(Intensity::Heavy), _) => WeatherOptions::Storm,
+            (_, None, true) if self.fog && dense => WeatherOptions::HeavyFog,
+            (_, None, true) => WeatherOptions::Foggy,
+            _ if self.haze => WeatherOptions::Hazy,
+            _ => match self.cloud_cover {
+                0 => WeatherOptions::Clear,
+                1..=2 => WeatherOptions::LightCloud,
+                3..=4 => WeatherOptions::MediumCloud,
+                5..=7 => WeatherOptions::HeavyCloud,
+                _ => WeatherOptions::Overcast,
+            },
+        }
+    }
+}
+
+fn is_station(token: &str) -> bool {
+    token.len() == 4 && token.chars().all(|char| char.is_ascii_alphanumeric())
+}
+
+/// Eighths of the sky covered by a cloud group like `BKN012`.
+fn cloud_cover(token: &str) -> Option<u8> {
+    match token.get(..3)? {
+        "SKC" | "CLR" | "NSC" | "NCD" => Some(0),
+        "FEW" => Some(2),
+        "SCT" => Some(4),
+        "BKN" => Some(7),
+        "OVC" => Some(8),
+        _ => None,
+    }
+}
+
+/// Visibility in meters of a group like `0800` or `1/2SM`.
+fn visibility(token: &str) -> Option<u32> {
+    if let Some(miles) = token.strip_suffix("SM") {
+        let miles = miles.trim_start_matches(['M', 'P']);
+        let miles: f64 = match miles.split_once('/') {
+            Some((numerator, denominator)) => {
+                numerator.parse::<f64>().ok()? / denominator.parse::<f64>().ok()?
+            }
+            None => miles.parse().ok()?,
+        };
+        return Some((miles * 1609.0) as u32);
+    }
+    let meters = token.strip_suffix("NDV").unwrap_or(token);
+    (meters.len() == 4 && meters.chars().all(|char| char.is_ascii_digit()))
+        .then(|| meters.parse().ok())
+        .flatten()
+}
+
+/// Intensity and codes of a present weather group like `-SHRA` or `+TSRA`.
+fn weather_group(token: &str) -> Option<(Intensity, Vec<&str>)> {
+    let (intensity, codes) = if let Some(codes) = token.strip_prefix('-') {
+        (Intensity::Light, codes)
+    } else if let Some(codes) = token.strip_prefix('+') {
+        (Intensity::Heavy, codes)
+    } else {
+        (Intensity::Moderate, token)
+    };
+    if codes.is_empty() || codes.len() % 2 != 0 || !codes.is_ascii() {
+        return None;
+    }
+    let codes: Vec<&str> = (0..codes.len())
+        .step_by(2)
+        .map(|index| &codes[index..index + 2])
+        .collect();
+    codes
+        .iter()
+        .all(|code| PRECIPITATION.contains(code) || OTHER_CODES.contains(code))
+        .then_some((intensity, codes))
+}
+
+/// Whether `input` is an ICAO code rather than a report.
+pub fn is_station_code(input: &str) -> bool {
+    is_station(input.trim())
+}
+
+/// Request of the latest METAR report of the station.
+pub fn report_url(station: &str) -> String {
+    format!("{AVIATION_WEATHER_URL}?ids={}&format=raw", station.trim())
+}
+
+/// Fetches the latest report of the station from aviationweather.gov.
+#[cfg(feature = "online")]
+pub fn fetch(station: &str) -> Result<Metar, ForecastError> {
+    let report = ureq::get(&report_url(station))
+        .call()
+        .and_then(|mut response| response.body_mut().read_to_string())
+        .map_err(|error| ForecastError::LiveWeather(error.to_string()))?;
+    if report.trim().is_empty() {
+        return Err(ForecastError::LiveWeather(format!(
+            "there is no METAR report of {station}"
+        )));
+    }
+    report.parse()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_become_weather() {
+        let weather = |report: &str| report.parse::<Metar>().unwrap().weather;
+        let metar: Metar = "METAR EBLG 121350Z 24012KT 6000 -RA BKN012 OVC025 12/10 Q1008"
+            .parse()
+            .unwrap();
+        assert_eq!(metar.station, "EBLG");
+        assert_eq!(metar.weather, WeatherOptions::LightRain);
+
+        assert_eq!(
+            weather("EGLL 121350Z CAVOK 25/12 Q1020"),
+            WeatherOptions::Clear
+        );
+        assert_eq!(
+            weather("KJFK 121351Z 20008KT 10SM SCT050 24/18 A2992"),
+            WeatherOptions::MediumCloud
+        );
+        assert_eq!(
+            weather("EDDM 121350Z 27015G30KT 3000 +TSRA FEW020CB 22/19 Q1006"),
+            WeatherOptions::Thunderstorm
+        );
+        assert_eq!(
+            weather("EHAM 120625Z 00000KT 0100 FG VV001 08/08 Q1025"),
+            WeatherOptions::HeavyFog
+        );
+        assert_eq!(
+            weather("LFPG 121300Z 18010KT 4000 RA BR OVC008 10/09 Q1012 TEMPO TSRA"),
+            WeatherOptions::FogWithRain
+        );
+        assert!("not a report".parse::<Metar>().is_err());
+        assert!(is_station_code("EBLG"));
+        assert!(!is_station_code("EBLG 121350Z"));
+    }
+}
@@ -0,0 +1,81 @@
+use serde_json::{Map, Value};
+
+use crate::error::ForecastError;
+
+/// Version of the structure of the config files written by this version of the forecaster.
+pub const CONFIG_VERSION: u32 = 1;
+
+/// Upgrades of the config structure, the migration at index `n` upgrades version `n` to `n + 1`.
+const MIGRATIONS: [fn(&mut Map<String, Value>); CONFIG_VERSION as usize] = [
+    // files written before the version was introduced already have the current structure
+    |_| {},
+];
+
+/// Version of a parsed config file, files without one predate versioning and have version 0.
+/// `None` if the version is not a number.
+pub fn version(config: &Value) -> Option<u32> {
+    match config.get("version") {
+        None => Some(0),
+        Some(version) => version.as_u64().and_then(|version| version.try_into().ok()),
+    }
+}
+
+/// Upgrades a parsed config file to [`CONFIG_VERSION`] and returns the version it had before,
+/// `None` if it is up to date or no config at all.
+pub fn migrate(config: &mut Value) -> Result<Option<u32>, ForecastError> {
+    let Some(version) = version(config) else {
+        // reported when the config gets deserialized
+        return Ok(None);
+    };
+    if version > CONFIG_VERSION {
+        return Err(ForecastError::UnsupportedConfigVersion(version));
+    }
+    let Some(mapping) = config.as_object_mut() else {
+        return Ok(None);
+    };
+    if version == CONFIG_VERSION {
+        return Ok(None);
+    }
+
+    for migration in &MIGRATIONS[version as usize..] {
+        migration(mapping);
+    }
+    // the version goes first, like in files written by the forecaster
+    let mut migrated = Map::new();
+    migrated.insert("version".into(), CONFIG_VERSION.into());
+    migrated.extend(
+        std::mem::take(mapping)
+            .into_iter()
+            .filter(|(key, _)| key != "version"),
+    );
+    *mapping = migrated;
+    Ok(Some(version))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn old_configs_are_upgraded() {
+        let mut old = serde_json::json!({
+            "probabilities": {},
+            "weather_slots": {},
+            "set_clipboard": true,
+        });
+        assert_eq!(version(&old), Some(0));
+        assert_eq!(migrate(&mut old).unwrap(), Some(0));
+        assert_eq!(version(&old), Some(CONFIG_VERSION));
+        assert_eq!(migrate(&mut old).unwrap(), None);
+        let config: Config = serde_json::from_value(old).unwrap();
+        assert_eq!(config.version, CONFIG_VERSION);
+        assert!(config.set_clipboard);
+
+        let mut newer = serde_json::json!({"version": 99});
+        assert!(matches!(
+            migrate(&mut newer),
+            Err(ForecastError::UnsupportedConfigVersion(99))
+        ));
+    }
+}
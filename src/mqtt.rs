@@ -0,0 +1,125 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{error::ForecastError, forecaster::WeatherForecast, output::OutputFormat};
+
+/// MQTT broker the forecast gets published to, e.g. for dashboards and stream overlays that
+/// subscribe to the topic.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct MqttConfig {
+    /// Host name or address of the broker
+    pub broker: String,
+    #[serde(default = "default_port")]
+    pub port: u16,
+    #[serde(default = "default_topic")]
+    pub topic: String,
+    #[serde(default = "default_client_id")]
+    pub client_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    /// Keep the forecast on the broker, so subscribers connecting later still get it
+    #[serde(default = "default_retain")]
+    pub retain: bool,
+    /// Format of the published message
+    #[serde(default = "default_format")]
+    pub format: OutputFormat,
+}
+
+fn default_port() -> u16 {
+    1883
+}
+
+fn default_topic() -> String {
+    "league/weather/next_race".to_string()
+}
+
+fn default_client_id() -> String {
+    "weather_forecaster".to_string()
+}
+
+fn default_retain() -> bool {
+    true
+}
+
+fn default_format() -> OutputFormat {
+    OutputFormat::Json
+}
+
+impl MqttConfig {
+    /// The message published for the forecast.
+    pub fn payload(
+        &self,
+        forecast: &WeatherForecast,
+        seed: Option<u64>,
+    ) -> Result<String, ForecastError> {
+        self.format.render(forecast, None, seed, false)
+    }
+}
+
+/// Seconds to wait for the broker to acknowledge the forecast.
+#[cfg(feature = "mqtt")]
+const ACKNOWLEDGE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Publishes the forecast and waits until the broker acknowledged it.
+#[cfg(feature = "mqtt")]
+pub fn publish(
+    config: &MqttConfig,
+    forecast: &WeatherForecast,
+    seed: Option<u64>,
+) -> Result<(), ForecastError> {
+    use rumqttc::{Client, Event, MqttOptions, Packet, QoS, RecvTimeoutError};
+
+    let failed = |error: &dyn std::fmt::Display| ForecastError::Mqtt(error.to_string());
+    let mut options = MqttOptions::new(&config.client_id, &config.broker, config.port);
+    if let Some(username) = &config.username {
+        options.set_credentials(username, config.password.as_deref().unwrap_or_default());
+    }
+    let (client, mut connection) = Client::new(options, 10);
+    client
+        .publish(
+            &config.topic,
+            QoS::AtLeastOnce,
+            config.retain,
+            config.payload(forecast, seed)?,
+        )
+        .map_err(|error| failed(&error))?;
+
+    loop {
+        match connection.recv_timeout(ACKNOWLEDGE_TIMEOUT) {
+            Ok(Ok(Event::Incoming(Packet::PubAck(_)))) => break,
+            Ok(Ok(_)) => {}
+            Ok(Err(error)) => return Err(failed(&error)),
+            Err(RecvTimeoutError::Timeout) => {
+                return Err(failed(
+                    &"the broker did not acknowledge the forecast in time",
+                ));
+            }
+            Err(RecvTimeoutError::Disconnected) => return Err(failed(&"disconnected")),
+        }
+    }
+    tracing::debug!("Published the forecast to {}", config.topic);
+    client.disconnect().map_err(|error| failed(&error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forecaster::{Sessions, WeatherOptions};
+
+    #[test]
+    fn forecasts_are_published_as_json_to_the_next_race() {
+        let config: MqttConfig = serde_json::from_str(r#"{"broker": "mqtt.example.com"}"#).unwrap();
+        assert_eq!(config.port, 1883);
+        assert_eq!(config.topic, "league/weather/next_race");
+        assert!(config.retain);
+
+        let mut forecast = WeatherForecast::default();
+        forecast
+            .forecast
+            .insert(Sessions::Race, vec![WeatherOptions::Rain]);
+        let payload: serde_json::Value =
+            serde_json::from_str(&config.payload(&forecast, Some(7)).unwrap()).unwrap();
+        assert_eq!(payload["seed"], 7);
+    }
+}
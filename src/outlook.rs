@@ -0,0 +1,165 @@
+use std::collections::BTreeMap;
+
+use rand::Rng;
+use serde::Serialize;
+
+use crate::{
+    error::ForecastError,
+    forecaster::{Round, Sessions, WeatherForecaster},
+};
+
+/// Chances of rain and expected cloud cover of the sessions and their slots, estimated from
+/// simulated weekends instead of a single pick, e.g. for a "40% chance of rain" announcement.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Outlook {
+    pub runs: usize,
+    pub sessions: Vec<SessionOutlook>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SessionOutlook {
+    pub session: Sessions,
+    /// Share of the weekends with rain in at least one slot from `0.0` to `1.0`
+    pub rain_chance: f64,
+    /// Average cloud cover of all slots in percent
+    pub cloud_cover: f64,
+    pub slots: Vec<SlotOutlook>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct SlotOutlook {
+    /// Share of the weekends the slot had rain in from `0.0` to `1.0`
+    pub rain_chance: f64,
+    /// Average cloud cover of the slot in percent
+    pub cloud_cover: f64,
+}
+
+/// Weekends, wet weekends and summed up cloud cover of a session or slot.
+#[derive(Debug, Clone, Copy, Default)]
+struct Tally {
+    weekends: usize,
+    wet: usize,
+    cloud_cover: f64,
+    slots: usize,
+}
+
+impl Tally {
+    fn outlook(&self) -> SlotOutlook {
+        SlotOutlook {
+            rain_chance: self.wet as f64 / self.weekends.max(1) as f64,
+            cloud_cover: self.cloud_cover / self.slots.max(1) as f64,
+        }
+    }
+}
+
+impl Outlook {
+    /// Generates `runs` forecasts of the sessions, including the constraints of the config, and
+    /// counts how often each session and slot was wet. Slots that only exist in some weekends
+    /// are based on those weekends.
+    pub fn generate<R: Rng>(
+        forecaster: &mut WeatherForecaster<R>,
+        sessions: &[Sessions],
+        runs: usize,
+    ) -> Result<Self, ForecastError> {
+        let mut tallies: BTreeMap<Sessions, (Tally, Vec<Tally>)> = BTreeMap::new();
+        for _ in 0..runs {
+            let forecast = forecaster.generate_constrained_forecast(sessions)?;
+            for (session, options) in forecast.forecast() {
+                let (total, slots) = tallies.entry(*session).or_default();
+                if slots.len() < options.len() {
+                    slots.resize(options.len(), Tally::default());
+                }
+                let conditions = forecast.conditions.get(session);
+                for (index, option) in options.iter().enumerate() {
+                    let cloud_cover = conditions
+                        .and_then(|conditions| conditions.get(index))
+                        .map_or_else(
+                            || {
+                                let (min, max) = option.cloud_cover_range();
+                                (min + max) / 2.0
+                            },
+                            |conditions| conditions.cloud_cover,
+                        );
+                    let slot = &mut slots[index];
+                    slot.weekends += 1;
+                    slot.wet += usize::from(option.rain_intensity() > 0);
+                    slot.cloud_cover += cloud_cover;
+                    slot.slots += 1;
+                    total.cloud_cover += cloud_cover;
+                    total.slots += 1;
+                }
+                total.weekends += 1;
+                total.wet += usize::from(options.iter().any(|option| option.rain_intensity() > 0));
+            }
+        }
+
+        let sessions = tallies
+            .into_iter()
+            .map(|(session, (total, slots))| {
+                let total = total.outlook();
+                SessionOutlook {
+                    session,
+                    rain_chance: total.rain_chance,
+                    cloud_cover: total.cloud_cover,
+                    slots: slots.iter().map(Tally::outlook).collect(),
+                }
+            })
+            .collect();
+        Ok(Self { runs, sessions })
+    }
+}
+
+impl std::fmt::Display for Outlook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let percent = |share: f64| (share * 100.0).round_to_decimal_place(0);
+        writeln!(f, "Outlook of {} simulated weekends", self.runs)?;
+        for session in &self.sessions {
+            writeln!(f)?;
+            writeln!(
+                f,
+                "{}: {}% chance of rain, clouds {}%",
+                session.session,
+                percent(session.rain_chance),
+                session.cloud_cover.round_to_decimal_place(0)
+            )?;
+            for (index, slot) in session.slots.iter().enumerate() {
+                writeln!(
+                    f,
+                    "  Slot {}: {:>3}% chance of rain, clouds {}%",
+                    index + 1,
+                    percent(slot.rain_chance),
+                    slot.cloud_cover.round_to_decimal_place(0)
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chances_are_shares_of_the_weekends() {
+        let mut forecaster = WeatherForecaster::default().with_seed(3);
+        let sessions = [Sessions::Qualifying, Sessions::Race];
+        let outlook = Outlook::generate(&mut forecaster, &sessions, 200).unwrap();
+
+        assert_eq!(outlook.runs, 200);
+        assert_eq!(outlook.sessions.len(), 2);
+        let race = &outlook.sessions[1];
+        assert_eq!(race.session, Sessions::Race);
+        assert_eq!(race.slots.len(), 4);
+        for slot in &race.slots {
+            assert!((0.0..=1.0).contains(&slot.rain_chance));
+            assert!(slot.rain_chance <= race.rain_chance);
+            assert!((0.0..=100.0).contains(&slot.cloud_cover));
+        }
+        assert!(race.rain_chance > 0.0 && race.rain_chance < 1.0);
+
+        let printed = outlook.to_string();
+        assert!(printed.starts_with("Outlook of 200 simulated weekends\n\nQualify: "));
+        assert!(printed.contains("\n  Slot 4: "));
+    }
+}
@@ -0,0 +1,333 @@
+use std::{fmt::Write as _, path::PathBuf};
+#[cfg(feature = "fs")]
+use std::{io::Write as _, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::ForecastError,
+    export::{ac, acc::AccWeather, ams2::Ams2Weather, csv, iracing::IRacingWeather},
+    forecaster::WeatherForecast,
+    template::{self, TemplateData},
+};
+
+/// Formats a forecast can be written in.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    schemars::JsonSchema,
+    strum::EnumIter,
+)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "snake_case")]
+pub enum OutputFormat {
+    /// Keys of the game's settings, as printed by default
+    #[default]
+    Game,
+    /// The sessions and slots with their conditions as JSON
+    Json,
+    /// The sessions and slots with their conditions as YAML
+    Yaml,
+    /// One table per session
+    Markdown,
+    /// One row per slot of every session
+    Csv,
+    /// One line of weather emoji per session, e.g. for race threads on Discord
+    Emoji,
+    /// Race control bulletin telling the weather story of the weekend
+    Bulletin,
+    /// The configured Handlebars template
+    Template,
+    /// `[WEATHER_x]` sections of an Assetto Corsa server's `server_cfg.ini`
+    Ac,
+    /// Weather fields of an Assetto Corsa Competizione server's `event.json`
+    Acc,
+    /// Weather slots of an Automobilista 2 dedicated server
+    Ams2,
+    /// Custom weather of iRacing's sessions as JSON
+    #[cfg_attr(feature = "cli", value(name = "iracing"))]
+    #[serde(rename = "iracing")]
+    IRacing,
+}
+
+impl OutputFormat {
+    /// Renders the forecast, `template` is required by [`OutputFormat::Template`] and `emoji` only
+    /// affects [`OutputFormat::Markdown`].
+    pub fn render(
+        &self,
+        forecast: &WeatherForecast,
+        template: Option<&str>,
+        seed: Option<u64>,
+        emoji: bool,
+    ) -> Result<String, ForecastError> {
+        match self {
+            OutputFormat::Game => Ok(forecast.to_string()),
+            OutputFormat::Json => Ok(serde_json::to_string_pretty(&TemplateData::new(
+                forecast, seed,
+            ))?),
+            #[cfg(feature = "yaml")]
+            OutputFormat::Yaml => Ok(serde_yaml::to_string(&TemplateData::new(forecast, seed))?),
+            #[cfg(not(feature = "yaml"))]
+            OutputFormat::Yaml => Err(ForecastError::MissingFeature {
+                what: "YAML output",
+                feature: "yaml",
+            }),
+            OutputFormat::Markdown => Ok(markdown(&TemplateData::new(forecast, seed), emoji)),
+            OutputFormat::Csv => Ok(csv::csv(forecast)),
+            OutputFormat::Emoji => Ok(emoji_lines(&TemplateData::new(forecast, seed))),
+            OutputFormat::Bulletin => Ok(forecast.bulletin().to_string()),
+            OutputFormat::Template => match template {
+                Some(template) => template::render(template, forecast, seed),
+                None => Err(ForecastError::Template(
+                    "no template file is configured".to_string(),
+                )),
+            },
+            OutputFormat::Ac => Ok(ac::server_cfg(forecast)),
+            OutputFormat::Acc => Ok(AccWeather::new(forecast)?
+                .map(|weather| weather.to_string())
+                .unwrap_or_default()),
+            OutputFormat::Ams2 => Ok(Ams2Weather::new(forecast).to_string()),
+            OutputFormat::IRacing => Ok(serde_json::to_string_pretty(&IRacingWeather::new(
+                forecast,
+            )?)?),
+        }
+    }
+}
+
+/// Where the forecast gets written to besides the terminal.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(default)]
+pub struct OutputConfig {
+    /// File to write the forecast to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<PathBuf>,
+    pub format: OutputFormat,
+    /// Add the forecast to the end of the file, e.g. to keep a log of all forecasts
+    pub append: bool,
+    /// Replace an existing file, otherwise nothing gets written if the file exists
+    pub overwrite: bool,
+    /// Print the forecast to the terminal as well when it gets written to a file, otherwise the
+    /// file replaces the terminal
+    pub stdout: bool,
+}
+
+#[cfg(feature = "fs")]
+impl OutputConfig {
+    /// Writes the rendered forecast to the file, if there is one.
+    pub fn write(
+        &self,
+        forecast: &WeatherForecast,
+        template: Option<&str>,
+        seed: Option<u64>,
+        emoji: bool,
+    ) -> Result<(), ForecastError> {
+        let Some(path) = &self.file else {
+            return Ok(());
+        };
+        let content = self.format.render(forecast, template, seed, emoji)?;
+        if self.append {
+            append(path, &content)
+        } else {
+            replace(path, &content, self.overwrite)
+        }
+    }
+}
+
+#[cfg(feature = "fs")]
+fn append(path: &Path, content: &str) -> Result<(), ForecastError> {
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|error| ForecastError::io(path, error))?;
+    // keep the entries of a log apart
+    let separator = if content.ends_with('\n') {
+        "\n"
+    } else {
+        "\n\n"
+    };
+    file.write_all(format!("{content}{separator}").as_bytes())
+        .map_err(|error| ForecastError::io(path, error))
+}
+
+/// Writes to a temporary file first, so an existing file is never left half written.
+#[cfg(feature = "fs")]
+fn replace(path: &Path, content: &str, overwrite: bool) -> Result<(), ForecastError> {
+    if !overwrite && path.exists() {
+        return Err(ForecastError::OutputExists(path.to_path_buf()));
+    }
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(".tmp");
+    let temporary = PathBuf::from(temporary);
+    std::fs::write(&temporary, content).map_err(|error| ForecastError::io(&temporary, error))?;
+    std::fs::rename(&temporary, path).map_err(|error| ForecastError::io(path, error))
+}
+
+fn emoji_lines(data: &TemplateData) -> String {
+    let mut lines = String::new();
+    for session in &data.sessions {
+        let slots: Vec<&str> = session
+            .slots
+            .iter()
+            .map(|slot| slot.emoji.as_str())
+            .collect();
+        let _ = writeln!(lines, "{}: {}", session.name, slots.join(" "));
+    }
+    lines
+}
+
+fn markdown(data: &TemplateData, emoji: bool) -> String {
+    let mut markdown = String::new();
+    for session in &data.sessions {
+        let _ = writeln!(markdown, "**{}**\n", session.name);
+        let _ = writeln!(markdown, "| Slot | Weather | Ambient | Track | Humidity |");
+        let _ = writeln!(markdown, "|---:|---|---:|---:|---:|");
+        for slot in &session.slots {
+            let format = |value: Option<f64>, unit: &str| {
+                value.map_or_else(|| "-".to_string(), |value| format!("{value}{unit}"))
+            };
+            let _ = writeln!(
+                markdown,
+                "| {} | {} | {} | {} | {} |",
+                slot.number,
+                if emoji {
+                    format!("{} {}", slot.emoji, slot.weather)
+                } else {
+                    slot.weather.clone()
+                },
+                format(slot.ambient_temperature, "°C"),
+                format(slot.track_temperature, "°C"),
+                format(slot.humidity, "%"),
+            );
+        }
+        markdown.push('\n');
+    }
+    if !data.summary.is_empty() {
+        let _ = writeln!(markdown, "{}\n", data.summary);
+    }
+    if let Some(seed) = data.seed {
+        let _ = writeln!(markdown, "Seed: `{seed}`");
+    }
+    markdown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forecaster::{Sessions, WeatherOptions};
+
+    #[test]
+    fn forecast_in_every_format() {
+        let mut forecast = WeatherForecast::default();
+        forecast
+            .forecast
+            .insert(Sessions::Race, vec![WeatherOptions::LightRain]);
+
+        assert_eq!(
+            OutputFormat::Game
+                .render(&forecast, None, None, false)
+                .unwrap(),
+            forecast.to_string()
+        );
+
+        let json = OutputFormat::Json
+            .render(&forecast, None, Some(5), false)
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(json["seed"], 5);
+        assert_eq!(json["sessions"][0]["slots"][0]["weather"], "LightRain");
+        #[cfg(feature = "yaml")]
+        {
+            let yaml = OutputFormat::Yaml
+                .render(&forecast, None, Some(5), false)
+                .unwrap();
+            let yaml: serde_json::Value = serde_yaml::from_str(&yaml).unwrap();
+            assert_eq!(yaml, json);
+        }
+
+        assert_eq!(
+            OutputFormat::Markdown
+                .render(&forecast, None, None, false)
+                .unwrap(),
+            "**Race**\n\n\
+             | Slot | Weather | Ambient | Track | Humidity |\n\
+             |---:|---|---:|---:|---:|\n\
+             | 1 | LightRain | - | - | - |\n\n\
+             Expect light rain throughout the race.\n\n"
+        );
+        assert_eq!(
+            OutputFormat::Emoji
+                .render(&forecast, None, None, false)
+                .unwrap(),
+            "Race: 🌦️\n"
+        );
+        assert!(
+            OutputFormat::Markdown
+                .render(&forecast, None, None, true)
+                .unwrap()
+                .contains("| 1 | 🌦️ LightRain |")
+        );
+
+        #[cfg(feature = "templates")]
+        assert_eq!(
+            OutputFormat::Template
+                .render(
+                    &forecast,
+                    Some("{{#each sessions}}{{key}}{{/each}}"),
+                    None,
+                    false,
+                )
+                .unwrap(),
+            "Race"
+        );
+        assert!(
+            OutputFormat::Template
+                .render(&forecast, None, None, false)
+                .is_err()
+        );
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn output_files_are_not_overwritten_by_accident() {
+        let path = std::env::temp_dir().join(format!(
+            "weather_forecaster_output_{}.txt",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let mut forecast = WeatherForecast::default();
+        forecast
+            .forecast
+            .insert(Sessions::Race, vec![WeatherOptions::Clear]);
+        let mut output = OutputConfig {
+            file: Some(path.clone()),
+            ..OutputConfig::default()
+        };
+
+        output.write(&forecast, None, None, false).unwrap();
+        assert!(matches!(
+            output.write(&forecast, None, None, false),
+            Err(ForecastError::OutputExists(_))
+        ));
+        output.overwrite = true;
+        output.write(&forecast, None, None, false).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            forecast.to_string()
+        );
+
+        output.append = true;
+        output.write(&forecast, None, None, false).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            format!("{forecast}{forecast}\n")
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+}
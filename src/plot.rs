@@ -0,0 +1,575 @@
+use std::{f64::consts::TAU, fmt::Write as _};
+
+use rand::Rng;
+use strum::IntoEnumIterator;
+
+use crate::{
+    error::ForecastError,
+    forecaster::{ProbabilityHistory, Round, WeatherForecaster, WeatherOptions},
+    simulate::Simulation,
+};
+
+/// Size of the SVG charts. The charts are laid out in `width` x `height` units and displayed
+/// `scale` times as large, e.g. `2.0` for high DPI screens or sharper images when converting them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlotSize {
+    pub width: f64,
+    pub height: f64,
+    pub scale: f64,
+}
+
+impl Default for PlotSize {
+    fn default() -> Self {
+        Self {
+            width: 800.0,
+            height: 500.0,
+            scale: 1.0,
+        }
+    }
+}
+
+impl PlotSize {
+    /// Smallest width and height the legends and axes fit into.
+    pub const MIN: (f64, f64) = (400.0, 250.0);
+
+    pub fn validate(&self) -> Result<(), ForecastError> {
+        let (min_width, min_height) = Self::MIN;
+        let fits = self.width >= min_width && self.height >= min_height;
+        let finite = [self.width, self.height, self.scale]
+            .iter()
+            .all(|value| value.is_finite());
+        if !fits || !finite || self.scale <= 0.0 {
+            return Err(ForecastError::InvalidPlotSize(*self));
+        }
+        Ok(())
+    }
+}
+
+/// Colors of the weather groups in the order they appear, repeated if there are more groups.
+const GROUP_COLORS: [(u8, u8, u8); 6] = [
+    (245, 158, 11),
+    (148, 163, 184),
+    (96, 165, 250),
+    (37, 99, 235),
+    (124, 58, 237),
+    (168, 162, 158),
+];
+
+/// Donut chart of the probabilities of the forecaster as SVG. Options of the same group sit next
+/// to each other in shades of the group's color, options that never appear are left out.
+pub fn probability_pie<R: Rng>(forecaster: &WeatherForecaster<R>, size: &PlotSize) -> String {
+    let mut groups: Vec<(String, Vec<(String, f64)>)> = Vec::new();
+    for (entry, probability) in forecaster.probabilities() {
+        if probability <= 0.0 {
+            continue;
+        }
+        let option = (entry.name.clone(), probability);
+        match groups.iter_mut().find(|(group, _)| *group == entry.group) {
+            Some((_, options)) => options.push(option),
+            None => groups.push((entry.group.clone(), vec![option])),
+        }
+    }
+
+    let (cx, cy) = (size.height / 2.0, size.height / 2.0);
+    let (outer, inner) = (size.height * 0.42, size.height * 0.22);
+    let mut svg = header("Weather probabilities", size);
+    let mut legend = String::new();
+    let mut angle = 0.0;
+    let mut line = 0;
+    for (index, (group, options)) in groups.iter().enumerate() {
+        let color = GROUP_COLORS[index % GROUP_COLORS.len()];
+        let total: f64 = options.iter().map(|(_, probability)| probability).sum();
+        line += 1;
+        let _ = writeln!(
+            legend,
+            r#"<text x="{}" y="{}" font-weight="bold">{} {}%</text>"#,
+            size.height + 20.0,
+            line as f64 * 18.0 + 20.0,
+            escape(group),
+            (total * 100.0).round_to_decimal_place(1)
+        );
+        for (shade, (name, probability)) in options.iter().enumerate() {
+            let fill = lighten(color, shade as f64 / options.len() as f64 * 0.6);
+            let end = angle + probability * TAU;
+            let _ = writeln!(
+                svg,
+                r#"<path d="{}" fill="{fill}" stroke="white"><title>{} {}%</title></path>"#,
+                donut_slice(cx, cy, outer, inner, angle, end),
+                escape(name),
+                (probability * 100.0).round_to_decimal_place(2)
+            );
+            angle = end;
+
+            line += 1;
+            let y = line as f64 * 18.0 + 20.0;
+            let _ = writeln!(
+                legend,
+                r#"<rect x="{}" y="{}" width="12" height="12" fill="{fill}"/><text x="{}" y="{y}">{} {}%</text>"#,
+                size.height + 30.0,
+                y - 11.0,
+                size.height + 48.0,
+                escape(name),
+                (probability * 100.0).round_to_decimal_place(1)
+            );
+        }
+    }
+    svg.push_str(&legend);
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Bar chart of the share of the slots every option had per session in the simulation as SVG.
+pub fn outcome_histogram(simulation: &Simulation, size: &PlotSize) -> String {
+    let options = simulation.options();
+    let sessions: Vec<_> = simulation.counts.keys().copied().collect();
+    let (left, right, top, bottom) = (60.0, 20.0, 50.0, 90.0);
+    let (plot_width, plot_height) = (size.width - left - right, size.height - top - bottom);
+    let max = options
+        .iter()
+        .flat_map(|option| {
+            sessions
+                .iter()
+                .map(|session| simulation.share(*session, *option))
+        })
+        .fold(0.0, f64::max);
+    // grid lines every 10%, at least up to the highest bar
+    let steps = ((max * 10.0).ceil() as usize).max(1);
+    let scale = plot_height / (steps as f64 / 10.0);
+
+    let mut svg = header("Simulated weather", size);
+    let _ = writeln!(
+        svg,
+        r#"<text x="{left}" y="20" font-weight="bold">Share of the slots in {} simulated weekends</text>"#,
+        simulation.runs
+    );
+    for step in 0..=steps {
+        let y = top + plot_height - step as f64 * plot_height / steps as f64;
+        let _ = writeln!(
+            svg,
+            r##"<line x1="{left}" y1="{y:.2}" x2="{}" y2="{y:.2}" stroke="#e5e7eb"/><text x="{}" y="{:.2}" text-anchor="end">{}%</text>"##,
+            left + plot_width,
+            left - 6.0,
+            y + 4.0,
+            step * 10
+        );
+    }
+
+    let group_width = plot_width / options.len().max(1) as f64;
+    let bar_width = group_width * 0.8 / sessions.len().max(1) as f64;
+    for (index, option) in options.iter().enumerate() {
+        let x = left + index as f64 * group_width;
+        for (number, session) in sessions.iter().enumerate() {
+            let share = simulation.share(*session, *option);
+            let height = share * scale;
+            let (r, g, b) = GROUP_COLORS[number % GROUP_COLORS.len()];
+            let _ = writeln!(
+                svg,
+                r#"<rect x="{:.2}" y="{:.2}" width="{bar_width:.2}" height="{height:.2}" fill="rgb({r},{g},{b})"><title>{session} {option:?} {}%</title></rect>"#,
+                x + group_width * 0.1 + number as f64 * bar_width,
+                top + plot_height - height,
+                (share * 100.0).round_to_decimal_place(1)
+            );
+        }
+        let _ = writeln!(
+            svg,
+            r#"<text transform="translate({:.2} {}) rotate(-40)" text-anchor="end">{option:?}</text>"#,
+            x + group_width / 2.0,
+            top + plot_height + 14.0
+        );
+    }
+    for (number, session) in sessions.iter().enumerate() {
+        let (r, g, b) = GROUP_COLORS[number % GROUP_COLORS.len()];
+        let x = size.width - right - 110.0 * (sessions.len() - number) as f64;
+        let _ = writeln!(
+            svg,
+            r#"<rect x="{x}" y="30" width="12" height="12" fill="rgb({r},{g},{b})"/><text x="{}" y="41">{session}</text>"#,
+            x + 16.0
+        );
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Line chart of the recorded probabilities of every option that was possible at any step as SVG.
+pub fn probability_history(history: &ProbabilityHistory, size: &PlotSize) -> String {
+    let options: Vec<_> = WeatherOptions::iter()
+        .filter(|option| {
+            history
+                .get(option)
+                .is_some_and(|steps| steps.iter().any(|probability| *probability > 0.0))
+        })
+        .collect();
+    let steps = history.values().map(Vec::len).max().unwrap_or_default();
+    let (left, right, top, bottom) = (60.0, 170.0, 40.0, 40.0);
+    let (plot_width, plot_height) = (size.width - left - right, size.height - top - bottom);
+    let max = history.values().flatten().copied().fold(0.0, f64::max);
+    let grid = ((max * 10.0).ceil() as usize).max(1);
+    let x = |step: usize| left + step as f64 * plot_width / steps.saturating_sub(1).max(1) as f64;
+    let y = |probability: f64| top + plot_height - probability * plot_height * 10.0 / grid as f64;
+
+    let mut svg = header("Probability history", size);
+    let _ = writeln!(
+        svg,
+        r#"<text x="{left}" y="20" font-weight="bold">Probabilities over {steps} steps</text>"#
+    );
+    for line in 0..=grid {
+        let height = y(line as f64 / 10.0);
+        let _ = writeln!(
+            svg,
+            r##"<line x1="{left}" y1="{height:.2}" x2="{}" y2="{height:.2}" stroke="#e5e7eb"/><text x="{}" y="{:.2}" text-anchor="end">{}%</text>"##,
+            left + plot_width,
+            left - 6.0,
+            height + 4.0,
+            line * 10
+        );
+    }
+    for (index, option) in options.iter().enumerate() {
+        let color = format!("hsl({}, 65%, 45%)", index * 360 / options.len());
+        let points: Vec<String> = history[option]
+            .iter()
+            .enumerate()
+            .map(|(step, probability)| format!("{:.2},{:.2}", x(step), y(*probability)))
+            .collect();
+        let _ = writeln!(
+            svg,
+            r#"<polyline points="{}" fill="none" stroke="{color}" stroke-width="2"><title>{option:?}</title></polyline>"#,
+            points.join(" ")
+        );
+        let legend = top + index as f64 * 18.0;
+        let _ = writeln!(
+            svg,
+            r#"<rect x="{}" y="{legend}" width="12" height="12" fill="{color}"/><text x="{}" y="{}">{option:?}</text>"#,
+            size.width - right + 20.0,
+            size.width - right + 38.0,
+            legend + 11.0
+        );
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Stacked area chart of the recorded probabilities summed up per weather group as SVG, easier to
+/// read than a line per option.
+pub fn group_history(
+    history: &ProbabilityHistory,
+    groups: &[(&str, Vec<WeatherOptions>)],
+    size: &PlotSize,
+) -> String {
+    let steps = history.values().map(Vec::len).max().unwrap_or_default();
+    let (left, right, top, bottom) = (60.0, 170.0, 40.0, 40.0);
+    let (plot_width, plot_height) = (size.width - left - right, size.height - top - bottom);
+    let x = |step: usize| left + step as f64 * plot_width / steps.saturating_sub(1).max(1) as f64;
+    let y = |probability: f64| top + plot_height - probability.min(1.0) * plot_height;
+
+    let mut svg = header("Probability history by group", size);
+    let _ = writeln!(
+        svg,
+        r#"<text x="{left}" y="20" font-weight="bold">Probabilities of the groups over {steps} steps</text>"#
+    );
+    for line in 0..=4 {
+        let height = y(line as f64 / 4.0);
+        let _ = writeln!(
+            svg,
+            r##"<line x1="{left}" y1="{height:.2}" x2="{}" y2="{height:.2}" stroke="#e5e7eb"/><text x="{}" y="{:.2}" text-anchor="end">{}%</text>"##,
+            left + plot_width,
+            left - 6.0,
+            height + 4.0,
+            line * 25
+        );
+    }
+
+    let mut lower = vec![0.0; steps];
+    for (index, (group, options)) in groups.iter().enumerate() {
+        let upper: Vec<f64> = (0..steps)
+            .map(|step| {
+                lower[step]
+                    + options
+                        .iter()
+                        .filter_map(|option| history.get(option)?.get(step))
+                        .sum::<f64>()
+            })
+            .collect();
+        let points: Vec<String> = (0..steps)
+            .map(|step| format!("{:.2},{:.2}", x(step), y(upper[step])))
+            .chain(
+                (0..steps)
+                    .rev()
+                    .map(|step| format!("{:.2},{:.2}", x(step), y(lower[step]))),
+            )
+            .collect();
+        let (r, g, b) = GROUP_COLORS[index % GROUP_COLORS.len()];
+        let _ = writeln!(
+            svg,
+            r#"<polygon points="{}" fill="rgb({r},{g},{b})"><title>{}</title></polygon>"#,
+            points.join(" "),
+            escape(group)
+        );
+        let legend = top + index as f64 * 18.0;
+        let _ = writeln!(
+            svg,
+            r#"<rect x="{}" y="{legend}" width="12" height="12" fill="rgb({r},{g},{b})"/><text x="{}" y="{}">{}</text>"#,
+            size.width - right + 20.0,
+            size.width - right + 38.0,
+            legend + 11.0,
+            escape(group)
+        );
+        lower = upper;
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Markers of the options in terminal charts, options beyond them share the last one.
+const TERMINAL_MARKERS: &[u8] = b"*o+x#@%&=~$?ABC";
+
+/// Plain text version of [`probability_history`] for terminals without a desktop to view SVG
+/// files, `width` and `height` are the size of the chart area in characters.
+pub fn terminal_history(history: &ProbabilityHistory, width: usize, height: usize) -> String {
+    let (width, height) = (width.max(2), height.max(2));
+    let options: Vec<_> = WeatherOptions::iter()
+        .filter(|option| {
+            history
+                .get(option)
+                .is_some_and(|steps| steps.iter().any(|probability| *probability > 0.0))
+        })
+        .collect();
+    let steps = history.values().map(Vec::len).max().unwrap_or_default();
+    let max = history.values().flatten().copied().fold(0.0, f64::max);
+    let top = ((max * 10.0).ceil() / 10.0).max(0.1);
+
+    let mut grid = vec![vec![b' '; width]; height];
+    for (index, option) in options.iter().enumerate() {
+        let marker = TERMINAL_MARKERS[index.min(TERMINAL_MARKERS.len() - 1)];
+        let values = &history[option];
+        let cells: Vec<(usize, usize)> = (0..width)
+            .filter_map(|column| {
+                let probability = values.get(column * steps.saturating_sub(1) / (width - 1))?;
+                let row = (probability / top * (height - 1) as f64).round() as usize;
+                Some((height - 1 - row.min(height - 1), column))
+            })
+            .collect();
+        for (row, column) in cells {
+            grid[row][column] = marker;
+        }
+    }
+
+    let mut chart = String::new();
+    for (index, row) in grid.iter().enumerate() {
+        let label = if index == 0 {
+            format!("{}%", (top * 100.0).round())
+        } else if index == height - 1 {
+            "0%".to_string()
+        } else {
+            String::new()
+        };
+        let _ = writeln!(
+            chart,
+            "{label:>5} |{}",
+            String::from_utf8_lossy(row).trim_end()
+        );
+    }
+    let _ = writeln!(chart, "{:>5} +{}", "", "-".repeat(width));
+    let _ = writeln!(chart, "{:>5}  1{:>width$}", "", steps, width = width - 1);
+    for (index, option) in options.iter().enumerate() {
+        let marker = TERMINAL_MARKERS[index.min(TERMINAL_MARKERS.len() - 1)] as char;
+        let _ = writeln!(chart, "{marker} {option:?}");
+    }
+    chart
+}
+
+fn header(title: &str, size: &PlotSize) -> String {
+    let PlotSize {
+        width,
+        height,
+        scale,
+    } = size;
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" \
+         viewBox=\"0 0 {width} {height}\" font-family=\"sans-serif\" font-size=\"13\">\n\
+         <title>{title}</title>\n\
+         <rect width=\"100%\" height=\"100%\" fill=\"white\"/>\n",
+        width * scale,
+        height * scale
+    )
+}
+
+/// Path of a ring segment between the angles, measured clockwise from the top.
+fn donut_slice(cx: f64, cy: f64, outer: f64, inner: f64, start: f64, end: f64) -> String {
+    // an arc cannot end where it starts, so a full ring is left open by a hair
+    let end = end.min(start + TAU - 1e-4);
+    let large = u8::from(end - start > TAU / 2.0);
+    let point = |radius: f64, angle: f64| {
+        format!(
+            "{:.2} {:.2}",
+            cx + radius * angle.sin(),
+            cy - radius * angle.cos()
+        )
+    };
+    format!(
+        "M {} A {outer} {outer} 0 {large} 1 {} L {} A {inner} {inner} 0 {large} 0 {} Z",
+        point(outer, start),
+        point(outer, end),
+        point(inner, end),
+        point(inner, start),
+    )
+}
+
+/// Mixes the color with white, `amount` ranges from `0.0` (unchanged) to `1.0` (white).
+fn lighten((r, g, b): (u8, u8, u8), amount: f64) -> String {
+    let mix = |channel: u8| (channel as f64 + (255.0 - channel as f64) * amount).round() as u8;
+    format!("#{:02x}{:02x}{:02x}", mix(r), mix(g), mix(b))
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::Config, forecaster::Sessions};
+
+    #[test]
+    fn pie_has_a_slice_per_option() {
+        let probabilities = WeatherOptions::iter()
+            .map(|option| {
+                let probability = match option {
+                    WeatherOptions::Clear => 0.5,
+                    WeatherOptions::LightCloud | WeatherOptions::Rain => 0.25,
+                    _ => 0.0,
+                };
+                (option, probability)
+            })
+            .collect();
+        let config = Config {
+            probabilities,
+            ..Config::default()
+        };
+        let forecaster = WeatherForecaster::new(config).unwrap();
+
+        let svg = probability_pie(&forecaster, &PlotSize::default());
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("<path").count(), 3);
+        assert!(svg.contains("<title>Clear 50%</title>"));
+        assert!(svg.contains(">dry 75%</text>"));
+        assert!(svg.contains(">wet 25%</text>"));
+    }
+
+    #[test]
+    fn histogram_has_a_bar_per_session_and_option() {
+        let mut forecaster = WeatherForecaster::default().with_seed(3);
+        let simulation =
+            Simulation::run(&mut forecaster, &[Sessions::Qualifying, Sessions::Race], 20).unwrap();
+        let svg = outcome_histogram(&simulation, &PlotSize::default());
+        assert_eq!(
+            svg.matches("<rect x").count(),
+            simulation.options().len() * 2 + 2
+        );
+        assert!(svg.contains("in 20 simulated weekends"));
+    }
+
+    #[test]
+    fn history_has_a_line_per_possible_option() {
+        let mut forecaster = WeatherForecaster::default()
+            .with_seed(2)
+            .with_probability_history();
+        forecaster.generate_forecast(&[Sessions::Race]).unwrap();
+        let history = forecaster.probability_history().unwrap();
+        let svg = probability_history(history, &PlotSize::default());
+        // Random has no probability by default
+        assert_eq!(
+            svg.matches("<polyline").count(),
+            WeatherOptions::iter().len() - 1
+        );
+        let steps = history[&WeatherOptions::Clear].len();
+        assert!(svg.contains(&format!("over {steps} steps")));
+        assert!(svg.contains("<title>Clear</title>"));
+    }
+
+    #[test]
+    fn groups_are_stacked() {
+        let history: ProbabilityHistory = [
+            (WeatherOptions::Clear, vec![0.5, 0.25]),
+            (WeatherOptions::LightCloud, vec![0.25, 0.25]),
+            (WeatherOptions::Rain, vec![0.25, 0.5]),
+        ]
+        .into_iter()
+        .collect();
+        let groups = [
+            (
+                "dry",
+                vec![WeatherOptions::Clear, WeatherOptions::LightCloud],
+            ),
+            ("wet", vec![WeatherOptions::Rain]),
+        ];
+        let svg = group_history(&history, &groups, &PlotSize::default());
+        assert_eq!(svg.matches("<polygon").count(), 2);
+        // dry from 0% to 75% and 50%, wet stacked on top up to 100%
+        assert!(svg.contains(
+            r#"<polygon points="60.00,145.00 630.00,250.00 630.00,460.00 60.00,460.00" fill="rgb(245,158,11)">"#
+        ));
+        assert!(svg.contains(r#"points="60.00,40.00 630.00,40.00 630.00,250.00 60.00,145.00""#));
+    }
+
+    #[test]
+    fn charts_can_be_resized() {
+        let history: ProbabilityHistory = [(WeatherOptions::Clear, vec![0.5, 1.0])]
+            .into_iter()
+            .collect();
+        let size = PlotSize {
+            width: 1200.0,
+            height: 600.0,
+            scale: 2.0,
+        };
+        size.validate().unwrap();
+        let svg = probability_history(&history, &size);
+        assert!(svg.starts_with(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="2400" height="1200" viewBox="0 0 1200 600""#
+        ));
+        // the chart ends at the width minus the legend
+        assert!(svg.contains(r#"points="60.00,300.00 1030.00,40.00""#));
+
+        for invalid in [
+            PlotSize {
+                width: 100.0,
+                ..PlotSize::default()
+            },
+            PlotSize {
+                scale: 0.0,
+                ..PlotSize::default()
+            },
+        ] {
+            assert!(matches!(
+                invalid.validate(),
+                Err(ForecastError::InvalidPlotSize(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn history_in_the_terminal() {
+        let history: ProbabilityHistory = [
+            (WeatherOptions::Clear, vec![0.0, 0.5, 1.0]),
+            (WeatherOptions::Rain, vec![1.0, 0.5, 0.0]),
+            (WeatherOptions::Storm, vec![0.0, 0.0, 0.0]),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(
+            terminal_history(&history, 3, 3),
+            " 100% |o *\n      | o\n   0% |* o\n      +---\n       1 3\n* Clear\no Rain\n"
+        );
+    }
+
+    #[test]
+    fn slices_start_at_the_top() {
+        assert_eq!(
+            donut_slice(0.0, 0.0, 2.0, 1.0, 0.0, TAU / 4.0),
+            "M 0.00 -2.00 A 2 2 0 0 1 2.00 -0.00 L 1.00 -0.00 A 1 1 0 0 0 0.00 -1.00 Z"
+        );
+        assert_eq!(lighten((0, 100, 255), 0.5), "#80b2ff");
+    }
+}
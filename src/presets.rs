@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use strum::EnumIter;
+
+use crate::{circuits, forecaster::WeatherOptions, geography::Geography, live::Coordinates};
+
+/// Built-in climate presets for real world tracks.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, schemars::JsonSchema, EnumIter,
+)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum TrackPreset {
+    /// Rainy and foggy Ardennes
+    Spa,
+    /// Bone-dry desert
+    Sakhir,
+    /// Humid with the occasional typhoon
+    Suzuka,
+    /// Tropical showers and thunderstorms
+    Interlagos,
+}
+
+impl TrackPreset {
+    /// Location of the track, used for its live weather.
+    pub fn coordinates(&self) -> Coordinates {
+        circuits::find(&format!("{self:?}"), &[])
+            .expect("every preset is a built-in circuit")
+            .coordinates()
+    }
+
+    /// Altitude and coast of the track the preset is named after.
+    pub fn geography(&self) -> Geography {
+        let (altitude, coastal) = match self {
+            TrackPreset::Spa => (400.0, false),
+            TrackPreset::Sakhir => (10.0, false),
+            TrackPreset::Suzuka => (45.0, true),
+            TrackPreset::Interlagos => (760.0, false),
+        };
+        Geography { altitude, coastal }
+    }
+
+    pub fn get_probabilities(&self) -> HashMap<WeatherOptions, f64> {
+        use WeatherOptions::*;
+
+        // probabilities in percent
+        let table: [(WeatherOptions, f64); 14] = match self {
+            TrackPreset::Spa => [
+                (Clear, 8.0),
+                (LightCloud, 10.0),
+                (MediumCloud, 12.0),
+                (HeavyCloud, 12.0),
+                (Overcast, 14.0),
+                (LightRain, 10.0),
+                (Rain, 8.0),
+                (Storm, 4.0),
+                (Thunderstorm, 3.0),
+                (Foggy, 6.0),
+                (FogWithRain, 4.0),
+                (HeavyFog, 3.0),
+                (HeavyFogWithRain, 2.0),
+                (Hazy, 4.0),
+            ],
+            TrackPreset::Sakhir => [
+                (Clear, 40.0),
+                (LightCloud, 22.0),
+                (MediumCloud, 8.0),
+                (HeavyCloud, 3.0),
+                (Overcast, 2.0),
+                (LightRain, 1.0),
+                (Rain, 0.5),
+                (Storm, 0.3),
+                (Thunderstorm, 0.2),
+                (Foggy, 1.0),
+                (FogWithRain, 0.0),
+                (HeavyFog, 0.0),
+                (HeavyFogWithRain, 0.0),
+                (Hazy, 22.0),
+            ],
+            TrackPreset::Suzuka => [
+                (Clear, 14.0),
+                (LightCloud, 14.0),
+                (MediumCloud, 14.0),
+                (HeavyCloud, 10.0),
+                (Overcast, 10.0),
+                (LightRain, 8.0),
+                (Rain, 6.0),
+                (Storm, 4.0),
+                (Thunderstorm, 3.0),
+                (Foggy, 4.0),
+                (FogWithRain, 2.0),
+                (HeavyFog, 2.0),
+                (HeavyFogWithRain, 1.0),
+                (Hazy, 8.0),
+            ],
+            TrackPreset::Interlagos => [
+                (Clear, 14.0),
+                (LightCloud, 14.0),
+                (MediumCloud, 12.0),
+                (HeavyCloud, 10.0),
+                (Overcast, 8.0),
+                (LightRain, 8.0),
+                (Rain, 8.0),
+                (Storm, 5.0),
+                (Thunderstorm, 7.0),
+                (Foggy, 3.0),
+                (FogWithRain, 2.0),
+                (HeavyFog, 1.0),
+                (HeavyFogWithRain, 1.0),
+                (Hazy, 7.0),
+            ],
+        };
+
+        table
+            .into_iter()
+            .map(|(option, percent)| (option, percent / 100.0))
+            .chain([(Random, 0.0)])
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::assert_approx_eq;
+    use strum::IntoEnumIterator;
+
+    use super::*;
+
+    #[test]
+    fn sane_preset_probabilities() {
+        for preset in TrackPreset::iter() {
+            let probabilities = preset.get_probabilities();
+            assert_eq!(probabilities.len(), WeatherOptions::iter().len());
+            let sum: f64 = probabilities.values().sum();
+            assert_approx_eq!(f64, sum, 1.0, epsilon = 0.0001);
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{catalog::WeatherCatalog, forecaster::WeatherOptions};
+
+/// Built-in graph of plausible changes of the weather from one slot to the next. Staying the same
+/// is always plausible, and so is anything involving `Random`.
+const PLAUSIBLE_TRANSITIONS: &[(WeatherOptions, &[WeatherOptions])] = {
+    use WeatherOptions::*;
+    &[
+        (Clear, &[LightCloud, Hazy]),
+        (LightCloud, &[Clear, MediumCloud, Hazy]),
+        (MediumCloud, &[LightCloud, HeavyCloud, LightRain]),
+        (
+            HeavyCloud,
+            &[MediumCloud, Overcast, LightRain, Rain, Thunderstorm],
+        ),
+        (Overcast, &[HeavyCloud, LightRain, Rain, Foggy]),
+        (
+            LightRain,
+            &[MediumCloud, HeavyCloud, Overcast, Rain, FogWithRain],
+        ),
+        (Rain, &[LightRain, Storm, HeavyCloud, Overcast, FogWithRain]),
+        (Storm, &[Rain, Thunderstorm, HeavyCloud]),
+        (Thunderstorm, &[Storm, Rain, HeavyCloud]),
+        (Foggy, &[HeavyFog, Hazy, FogWithRain, Overcast]),
+        (FogWithRain, &[Foggy, HeavyFogWithRain, LightRain, Rain]),
+        (HeavyFog, &[Foggy, HeavyFogWithRain]),
+        (HeavyFogWithRain, &[HeavyFog, FogWithRain]),
+        (Hazy, &[Clear, LightCloud, Foggy]),
+    ]
+};
+
+/// How strictly consecutive slots of a session have to follow the built-in graph of plausible
+/// weather changes, e.g. haze may turn into fog but heavy fog never directly into a thunderstorm.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema,
+)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "snake_case")]
+pub enum Realism {
+    /// Only changes along an edge of the graph
+    Strict,
+    /// Changes along up to two edges of the graph
+    Loose,
+    /// Any change
+    #[default]
+    Off,
+}
+
+impl Realism {
+    /// Whether the weather may change from `from` to `to` between two slots.
+    pub fn allows(&self, from: WeatherOptions, to: WeatherOptions) -> bool {
+        match self {
+            Realism::Strict => is_plausible(from, to),
+            Realism::Loose => {
+                is_plausible(from, to)
+                    || successors(from).iter().any(|next| is_plausible(*next, to))
+            }
+            Realism::Off => true,
+        }
+    }
+
+    /// Removes the catalog entries that may not follow the entry `previous` from the
+    /// `probabilities`. Entries use the graph of the built-in option they are based on.
+    pub fn filter(
+        &self,
+        catalog: &WeatherCatalog,
+        mut probabilities: Vec<f64>,
+        previous: usize,
+    ) -> Vec<f64> {
+        let previous = catalog[previous].option;
+        for (entry, probability) in catalog.entries().iter().zip(&mut probabilities) {
+            if !self.allows(previous, entry.option) {
+                *probability = 0.0;
+            }
+        }
+        probabilities
+    }
+}
+
+fn successors(option: WeatherOptions) -> &'static [WeatherOptions] {
+    PLAUSIBLE_TRANSITIONS
+        .iter()
+        .find(|(from, _)| *from == option)
+        .map_or(&[], |(_, successors)| successors)
+}
+
+fn is_plausible(from: WeatherOptions, to: WeatherOptions) -> bool {
+    from == to
+        || from == WeatherOptions::Random
+        || to == WeatherOptions::Random
+        || successors(from).contains(&to)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::groups::WeatherGroups;
+
+    #[test]
+    fn implausible_changes_are_filtered() {
+        use WeatherOptions::*;
+        assert!(Realism::Strict.allows(Hazy, Foggy));
+        assert!(!Realism::Strict.allows(HeavyFog, Thunderstorm));
+        assert!(!Realism::Strict.allows(Clear, MediumCloud));
+        assert!(Realism::Loose.allows(Clear, MediumCloud));
+        assert!(!Realism::Loose.allows(HeavyFog, Thunderstorm));
+        assert!(!Realism::Loose.allows(Clear, Thunderstorm));
+        assert!(Realism::Off.allows(Clear, Thunderstorm));
+
+        let catalog = WeatherCatalog::built_in(&WeatherGroups::default());
+        let clear = catalog.position(Clear).unwrap();
+        let filtered = Realism::Strict.filter(&catalog, vec![1.0; catalog.len()], clear);
+        let allowed: Vec<_> = catalog
+            .entries()
+            .iter()
+            .zip(&filtered)
+            .filter(|(_, probability)| **probability > 0.0)
+            .map(|(entry, _)| entry.option)
+            .collect();
+        assert_eq!(allowed, [Clear, LightCloud, Hazy, Random]);
+    }
+}
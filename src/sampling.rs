@@ -0,0 +1,157 @@
+use rand::Rng;
+
+use crate::catalog::WeatherCatalog;
+
+/// Alias table of a discrete distribution (Vose's alias method), picks an index in constant time
+/// no matter how many entries the distribution has.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AliasTable {
+    probabilities: Vec<f64>,
+    /// Chance to keep the index of a column instead of taking its alias
+    thresholds: Vec<f64>,
+    aliases: Vec<usize>,
+}
+
+impl AliasTable {
+    /// The probabilities do not have to be normalized. If they sum up to zero every index is
+    /// equally likely.
+    pub fn new(probabilities: Vec<f64>) -> Self {
+        let count = probabilities.len();
+        let sum: f64 = probabilities.iter().sum();
+        let mut scaled: Vec<f64> = if sum > 0.0 {
+            probabilities
+                .iter()
+                .map(|probability| probability * count as f64 / sum)
+                .collect()
+        } else {
+            vec![1.0; count]
+        };
+
+        let mut thresholds = vec![1.0; count];
+        let mut aliases: Vec<usize> = (0..count).collect();
+        let (mut small, mut large): (Vec<usize>, Vec<usize>) =
+            (0..count).partition(|index| scaled[*index] < 1.0);
+        while let (Some(&less), Some(&more)) = (small.last(), large.last()) {
+            small.pop();
+            thresholds[less] = scaled[less];
+            aliases[less] = more;
+            scaled[more] -= 1.0 - scaled[less];
+            if scaled[more] < 1.0 {
+                large.pop();
+                small.push(more);
+            }
+        }
+        // whatever is left is only off by rounding errors
+
+        Self {
+            probabilities,
+            thresholds,
+            aliases,
+        }
+    }
+
+    /// The probabilities the table was created with.
+    pub fn probabilities(&self) -> &[f64] {
+        &self.probabilities
+    }
+
+    /// Index of the picked entry, the table must not be empty.
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> usize {
+        let column = rng.random_range(0..self.thresholds.len());
+        if rng.random::<f64>() < self.thresholds[column] {
+            column
+        } else {
+            self.aliases[column]
+        }
+    }
+}
+
+/// Alias tables of the catalog entries, once with all entries and once with the dry ones only
+/// for slots that must not have rain.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeatherSampler {
+    any: AliasTable,
+    /// `None` if no dry entry has a probability greater than 0
+    dry: Option<AliasTable>,
+}
+
+impl WeatherSampler {
+    pub fn new(catalog: &WeatherCatalog, probabilities: Vec<f64>) -> Self {
+        let dry: Vec<f64> = catalog
+            .entries()
+            .iter()
+            .zip(&probabilities)
+            .map(|(entry, probability)| {
+                if entry.rain_intensity == 0 {
+                    *probability
+                } else {
+                    0.0
+                }
+            })
+            .collect();
+        let dry = (dry.iter().sum::<f64>() > 0.0).then(|| AliasTable::new(dry));
+        Self {
+            any: AliasTable::new(probabilities),
+            dry,
+        }
+    }
+
+    /// Probabilities of all entries.
+    pub fn probabilities(&self) -> &[f64] {
+        self.any.probabilities()
+    }
+
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> usize {
+        self.any.sample(rng)
+    }
+
+    /// Picks one of the dry entries with their probabilities renormalized, `None` if there are
+    /// none.
+    pub fn sample_dry<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<usize> {
+        self.dry.as_ref().map(|dry| dry.sample(rng))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::assert_approx_eq;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use crate::{forecaster::WeatherOptions, groups::WeatherGroups};
+
+    use super::*;
+
+    #[test]
+    fn samples_follow_the_probabilities() {
+        let table = AliasTable::new(vec![0.5, 0.0, 0.2, 0.3]);
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut counts = [0; 4];
+        for _ in 0..20_000 {
+            counts[table.sample(&mut rng)] += 1;
+        }
+        assert_eq!(counts[1], 0);
+        for (count, probability) in counts.iter().zip(table.probabilities()) {
+            assert_approx_eq!(f64, *count as f64 / 20_000.0, *probability, epsilon = 0.02);
+        }
+
+        let uniform = AliasTable::new(vec![0.0, 0.0]);
+        let picks: Vec<_> = (0..100).map(|_| uniform.sample(&mut rng)).collect();
+        assert!(picks.contains(&0) && picks.contains(&1));
+
+        let catalog = WeatherCatalog::built_in(&WeatherGroups::default());
+        let probabilities: Vec<f64> = catalog
+            .entries()
+            .iter()
+            .map(|entry| if entry.rain_intensity > 0 { 1.0 } else { 0.0 })
+            .collect();
+        let wet = WeatherSampler::new(&catalog, probabilities.clone());
+        assert_eq!(wet.sample_dry(&mut rng), None);
+        assert!(catalog[wet.sample(&mut rng)].rain_intensity > 0);
+
+        let mut probabilities = probabilities;
+        let clear = catalog.position(WeatherOptions::Clear).unwrap();
+        probabilities[clear] = 0.001;
+        let mostly_wet = WeatherSampler::new(&catalog, probabilities);
+        assert_eq!(mostly_wet.sample_dry(&mut rng), Some(clear));
+    }
+}
@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use strum::EnumIter;
+
+use crate::{error::ForecastError, forecaster::WeatherOptions};
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, schemars::JsonSchema, EnumIter,
+)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum Month {
+    January,
+    February,
+    March,
+    April,
+    May,
+    June,
+    July,
+    August,
+    September,
+    October,
+    November,
+    December,
+}
+
+impl Month {
+    /// Meteorological season of the month on the northern hemisphere.
+    pub fn season(&self) -> Season {
+        match self {
+            Month::December | Month::January | Month::February => Season::Winter,
+            Month::March | Month::April | Month::May => Season::Spring,
+            Month::June | Month::July | Month::August => Season::Summer,
+            Month::September | Month::October | Month::November => Season::Autumn,
+        }
+    }
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, schemars::JsonSchema, EnumIter,
+)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum Season {
+    Spring,
+    Summer,
+    Autumn,
+    Winter,
+}
+
+impl Season {
+    pub fn get_default_modifier(&self) -> SeasonModifier {
+        match self {
+            Season::Spring => SeasonModifier {
+                rain: 1.2,
+                fog: 1.1,
+                storm: 0.9,
+            },
+            Season::Summer => SeasonModifier {
+                rain: 0.7,
+                fog: 0.5,
+                storm: 1.6,
+            },
+            Season::Autumn => SeasonModifier {
+                rain: 1.5,
+                fog: 1.6,
+                storm: 0.8,
+            },
+            Season::Winter => SeasonModifier {
+                rain: 1.3,
+                fog: 1.4,
+                storm: 0.3,
+            },
+        }
+    }
+
+    /// Returns the modifier of the season, preferring the user provided ones over the defaults.
+    pub fn get_modifier(&self, overrides: &HashMap<Season, SeasonModifier>) -> SeasonModifier {
+        overrides
+            .get(self)
+            .copied()
+            .unwrap_or_else(|| self.get_default_modifier())
+    }
+}
+
+/// Factors the probabilities of wet, foggy and stormy weather get multiplied with.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct SeasonModifier {
+    pub rain: f64,
+    pub fog: f64,
+    pub storm: f64,
+}
+
+impl SeasonModifier {
+    pub fn validate(&self) -> Result<(), ForecastError> {
+        for (name, value) in [
+            ("rain", self.rain),
+            ("fog", self.fog),
+            ("storm", self.storm),
+        ] {
+            if !value.is_finite() || value < 0.0 {
+                return Err(ForecastError::InvalidSeasonModifier { name, value });
+            }
+        }
+        Ok(())
+    }
+
+    pub fn factor(&self, option: WeatherOptions) -> f64 {
+        match option {
+            WeatherOptions::LightRain | WeatherOptions::Rain => self.rain,
+            WeatherOptions::Storm | WeatherOptions::Thunderstorm => self.storm,
+            WeatherOptions::Foggy | WeatherOptions::HeavyFog => self.fog,
+            WeatherOptions::FogWithRain | WeatherOptions::HeavyFogWithRain => self.fog * self.rain,
+            _ => 1.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use strum::IntoEnumIterator;
+
+    use super::*;
+
+    #[test]
+    fn overrides_take_precedence() {
+        let custom = SeasonModifier {
+            rain: 3.0,
+            fog: 1.0,
+            storm: 0.0,
+        };
+        let overrides = [(Season::Summer, custom)].into_iter().collect();
+        assert_eq!(Season::Summer.get_modifier(&overrides), custom);
+        assert_eq!(
+            Season::Winter.get_modifier(&overrides),
+            Season::Winter.get_default_modifier()
+        );
+        for season in Season::iter() {
+            assert!(season.get_default_modifier().validate().is_ok());
+        }
+    }
+}
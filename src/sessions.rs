@@ -0,0 +1,131 @@
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+
+use crate::{error::ForecastError, forecaster::Sessions};
+
+/// A session of the weekend declared in the config.
+///
+/// Every declared session follows the rules of a built-in session, e.g. its rain coupling to the
+/// race. Each built-in session can be declared once, so a weekend with two practice sessions
+/// declares them as `Fp1` and `Fp2`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct SessionDefinition {
+    /// Name of the session, used to select it with `--sessions`
+    pub name: String,
+    /// Built-in session whose rules the session follows
+    pub session: Sessions,
+    /// Number of weather slots, replaces the slots of the built-in session
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weather_slots: Option<usize>,
+    /// Prefix of the keys in the game's settings, defaults to the one of the built-in session
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_prefix: Option<String>,
+}
+
+impl SessionDefinition {
+    pub fn key_prefix(&self) -> String {
+        self.key_prefix
+            .clone()
+            .unwrap_or_else(|| self.session.to_string())
+    }
+}
+
+/// Makes sure no built-in session is declared twice.
+pub fn validate(definitions: &[SessionDefinition]) -> Result<(), ForecastError> {
+    for (index, definition) in definitions.iter().enumerate() {
+        if let Some(duplicate) = definitions[..index]
+            .iter()
+            .find(|other| other.session == definition.session)
+        {
+            return Err(ForecastError::DuplicateSession {
+                session: definition.session,
+                names: vec![duplicate.name.clone(), definition.name.clone()],
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Looks up the sessions selected on the command line by their declared or built-in names. Without
+/// a selection all declared sessions are used, or practice, qualifying and race if there are none.
+pub fn resolve(
+    definitions: &[SessionDefinition],
+    names: &[String],
+) -> Result<Vec<Sessions>, ForecastError> {
+    if names.is_empty() {
+        if definitions.is_empty() {
+            return Ok(vec![
+                Sessions::Practice,
+                Sessions::Qualifying,
+                Sessions::Race,
+            ]);
+        }
+        return Ok(definitions
+            .iter()
+            .map(|definition| definition.session)
+            .collect());
+    }
+
+    names
+        .iter()
+        .map(|name| {
+            definitions
+                .iter()
+                .find(|definition| definition.name.eq_ignore_ascii_case(name.trim()))
+                .map(|definition| definition.session)
+                .or_else(|| {
+                    Sessions::iter()
+                        .find(|session| format!("{session:?}").eq_ignore_ascii_case(name.trim()))
+                })
+                .ok_or_else(|| ForecastError::UnknownSession(name.clone()))
+        })
+        .collect()
+}
+
+/// Prefix of the keys of `session` in the game's settings.
+pub fn key_prefix(definitions: &[SessionDefinition], session: Sessions) -> String {
+    definitions
+        .iter()
+        .find(|definition| definition.session == session)
+        .map(SessionDefinition::key_prefix)
+        .unwrap_or_else(|| session.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sessions_are_resolved_by_name() {
+        let definitions: Vec<SessionDefinition> = serde_json::from_value(serde_json::json!([
+            {"name": "Practice A", "session": "Fp1", "key_prefix": "PracticeA"},
+            {"name": "Practice B", "session": "Fp2"},
+            {"name": "Feature Race", "session": "Race", "weather_slots": 3},
+        ]))
+        .unwrap();
+        validate(&definitions).unwrap();
+
+        assert_eq!(
+            resolve(&definitions, &[]).unwrap(),
+            [Sessions::Fp1, Sessions::Fp2, Sessions::Race]
+        );
+        let names = ["practice b".to_string(), "qualifying".to_string()];
+        assert_eq!(
+            resolve(&definitions, &names).unwrap(),
+            [Sessions::Fp2, Sessions::Qualifying]
+        );
+        assert!(resolve(&definitions, &["superpole".to_string()]).is_err());
+        assert_eq!(resolve(&[], &[]).unwrap().len(), 3);
+
+        assert_eq!(key_prefix(&definitions, Sessions::Fp1), "PracticeA");
+        assert_eq!(key_prefix(&definitions, Sessions::Fp2), "Practice2");
+        assert_eq!(key_prefix(&definitions, Sessions::Qualifying), "Qualify");
+
+        let mut duplicate = definitions.clone();
+        duplicate[1].session = Sessions::Fp1;
+        assert!(matches!(
+            validate(&duplicate),
+            Err(ForecastError::DuplicateSession { .. })
+        ));
+    }
+}
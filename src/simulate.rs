@@ -0,0 +1,271 @@
+use std::collections::{BTreeMap, HashMap};
+
+use rand::{Rng, SeedableRng, rngs::StdRng};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use strum::IntoEnumIterator;
+
+use crate::{
+    config::Config,
+    error::ForecastError,
+    forecaster::{Round, Sessions, WeatherForecaster, WeatherOptions},
+};
+
+/// Weekends simulated with the same seed in [`Simulation::run_parallel`]. The chunks do not
+/// depend on the number of threads, so the results are the same on every machine and without the
+/// `parallel` feature.
+const RUNS_PER_CHUNK: usize = 10_000;
+
+/// How often every weather option appeared in the slots of the sessions of simulated weekends.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Simulation {
+    pub runs: usize,
+    /// Appearances of every option per session
+    pub counts: BTreeMap<Sessions, HashMap<WeatherOptions, usize>>,
+    /// Number of weekends each session had rain in at least one slot
+    pub wet: BTreeMap<Sessions, usize>,
+    /// Summed up rain intensity of all slots per session
+    pub rain_intensity: BTreeMap<Sessions, usize>,
+}
+
+impl Simulation {
+    /// Generates `runs` forecasts of the sessions, including the constraints of the config.
+    pub fn run<R: Rng>(
+        forecaster: &mut WeatherForecaster<R>,
+        sessions: &[Sessions],
+        runs: usize,
+    ) -> Result<Self, ForecastError> {
+        let mut simulation = Self {
+            runs,
+            counts: BTreeMap::new(),
+            wet: BTreeMap::new(),
+            rain_intensity: BTreeMap::new(),
+        };
+        for _ in 0..runs {
+            let forecast = forecaster.generate_constrained_forecast(sessions)?;
+            for (session, options) in forecast.forecast() {
+                let counts = simulation.counts.entry(*session).or_default();
+                for option in options {
+                    *counts.entry(*option).or_default() += 1;
+                }
+                let rain_intensity: usize =
+                    options.iter().map(|option| option.rain_intensity()).sum();
+                *simulation.wet.entry(*session).or_default() += usize::from(rain_intensity > 0);
+                *simulation.rain_intensity.entry(*session).or_default() += rain_intensity;
+            }
+        }
+        Ok(simulation)
+    }
+
+    /// Generates `runs` forecasts like [`Self::run`], spread over all available threads with the
+    /// `parallel` feature. Every chunk of runs gets its own seed drawn from `seed`, so the results
+    /// can be reproduced.
+    pub fn run_parallel(
+        config: &Config,
+        sessions: &[Sessions],
+        runs: usize,
+        seed: u64,
+    ) -> Result<Self, ForecastError> {
+        let mut seeds = StdRng::seed_from_u64(seed);
+        let chunks: Vec<(u64, usize)> = (0..runs)
+            .step_by(RUNS_PER_CHUNK)
+            .map(|start| (seeds.random(), RUNS_PER_CHUNK.min(runs - start)))
+            .collect();
+        let run_chunk = |(seed, runs): &(u64, usize)| {
+            let mut forecaster = WeatherForecaster::new(config.clone())?.with_seed(*seed);
+            Self::run(&mut forecaster, sessions, *runs)
+        };
+        #[cfg(feature = "parallel")]
+        let results: Vec<Result<Self, ForecastError>> = chunks.par_iter().map(run_chunk).collect();
+        #[cfg(not(feature = "parallel"))]
+        let results: Vec<Result<Self, ForecastError>> = chunks.iter().map(run_chunk).collect();
+
+        let mut simulation = Self::default();
+        for result in results {
+            simulation.merge(result?);
+        }
+        Ok(simulation)
+    }
+
+    /// Adds the weekends of `other` to the simulation.
+    pub fn merge(&mut self, other: Self) {
+        self.runs += other.runs;
+        for (session, counts) in other.counts {
+            let merged = self.counts.entry(session).or_default();
+            for (option, count) in counts {
+                *merged.entry(option).or_default() += count;
+            }
+        }
+        for (session, wet) in other.wet {
+            *self.wet.entry(session).or_default() += wet;
+        }
+        for (session, rain_intensity) in other.rain_intensity {
+            *self.rain_intensity.entry(session).or_default() += rain_intensity;
+        }
+    }
+
+    /// Share of the slots of the session that had the option.
+    pub fn share(&self, session: Sessions, option: WeatherOptions) -> f64 {
+        let Some(counts) = self.counts.get(&session) else {
+            return 0.0;
+        };
+        let slots: usize = counts.values().sum();
+        if slots == 0 {
+            return 0.0;
+        }
+        counts.get(&option).copied().unwrap_or_default() as f64 / slots as f64
+    }
+
+    /// Share of the weekends the session had rain in.
+    pub fn wet_rate(&self, session: Sessions) -> f64 {
+        if self.runs == 0 {
+            return 0.0;
+        }
+        self.wet.get(&session).copied().unwrap_or_default() as f64 / self.runs as f64
+    }
+
+    /// Average rain intensity of the slots of the session.
+    pub fn average_rain_intensity(&self, session: Sessions) -> f64 {
+        let slots: usize = self
+            .counts
+            .get(&session)
+            .map(|counts| counts.values().sum())
+            .unwrap_or_default();
+        if slots == 0 {
+            return 0.0;
+        }
+        self.rain_intensity
+            .get(&session)
+            .copied()
+            .unwrap_or_default() as f64
+            / slots as f64
+    }
+
+    /// Options that appeared at least once, in their declaration order.
+    pub fn options(&self) -> Vec<WeatherOptions> {
+        WeatherOptions::iter()
+            .filter(|option| {
+                self.counts
+                    .values()
+                    .any(|counts| counts.get(option).is_some_and(|count| *count > 0))
+            })
+            .collect()
+    }
+}
+
+impl std::fmt::Display for Simulation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Simulated {} weekends", self.runs)?;
+        for session in self.counts.keys() {
+            writeln!(f)?;
+            writeln!(
+                f,
+                "{session}: wet in {}% of the weekends, average rain intensity {}",
+                (self.wet_rate(*session) * 100.0).round_to_decimal_place(1),
+                self.average_rain_intensity(*session)
+                    .round_to_decimal_place(2)
+            )?;
+            for option in self.options() {
+                let share = self.share(*session, option);
+                if share > 0.0 {
+                    writeln!(
+                        f,
+                        "  {:<18}{:>6}%",
+                        format!("{option:?}"),
+                        (share * 100.0).round_to_decimal_place(1)
+                    )?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn outcomes_are_counted() {
+        let mut forecaster = WeatherForecaster::default().with_seed(5);
+        let sessions = [Sessions::Qualifying, Sessions::Race];
+        let simulation = Simulation::run(&mut forecaster, &sessions, 50).unwrap();
+
+        assert_eq!(simulation.runs, 50);
+        assert_eq!(
+            simulation.counts[&Sessions::Qualifying]
+                .values()
+                .sum::<usize>(),
+            100
+        );
+        assert_eq!(
+            simulation.counts[&Sessions::Race].values().sum::<usize>(),
+            200
+        );
+        let total: f64 = simulation
+            .options()
+            .into_iter()
+            .map(|option| simulation.share(Sessions::Race, option))
+            .sum();
+        assert!((total - 1.0).abs() < 1e-9);
+        assert_eq!(
+            simulation.share(Sessions::Practice, WeatherOptions::Clear),
+            0.0
+        );
+
+        let wet = simulation.wet_rate(Sessions::Race);
+        assert!((0.0..=1.0).contains(&wet));
+        assert_eq!(
+            wet > 0.0,
+            simulation.average_rain_intensity(Sessions::Race) > 0.0
+        );
+        let report = simulation.to_string();
+        assert!(report.starts_with("Simulated 50 weekends\n\nQualify: wet in "));
+        assert!(report.contains("\nRace: wet in "));
+    }
+
+    #[test]
+    fn dry_configs_never_rain() {
+        let probabilities = WeatherOptions::iter()
+            .map(|option| {
+                let probability = if option.rain_intensity() > 0 {
+                    0.0
+                } else {
+                    0.1
+                };
+                (option, probability)
+            })
+            .collect();
+        let config = Config {
+            probabilities,
+            ..Config::default()
+        };
+        let mut forecaster = WeatherForecaster::new(config).unwrap().with_seed(1);
+        let simulation = Simulation::run(&mut forecaster, &[Sessions::Race], 20).unwrap();
+        assert_eq!(simulation.wet_rate(Sessions::Race), 0.0);
+        assert_eq!(simulation.average_rain_intensity(Sessions::Race), 0.0);
+    }
+
+    #[test]
+    fn parallel_runs_are_reproducible() {
+        let sessions = [Sessions::Race];
+        let runs = RUNS_PER_CHUNK + 10;
+        let simulation = Simulation::run_parallel(&Config::default(), &sessions, runs, 4).unwrap();
+        assert_eq!(simulation.runs, runs);
+        assert_eq!(
+            simulation.counts[&Sessions::Race].values().sum::<usize>(),
+            4 * runs
+        );
+        assert_eq!(
+            Simulation::run_parallel(&Config::default(), &sessions, runs, 4).unwrap(),
+            simulation
+        );
+        assert_eq!(
+            Simulation::run_parallel(&Config::default(), &sessions, 0, 4)
+                .unwrap()
+                .runs,
+            0
+        );
+    }
+}
@@ -0,0 +1,240 @@
+use rand::Rng;
+use strum::IntoEnumIterator;
+
+use crate::{
+    error::ForecastError,
+    forecaster::{Round, Sessions, WeatherForecaster, WeatherOptions},
+    simulate::Simulation,
+};
+
+/// How well observed frequencies fit expected probabilities. Small p-values (e.g. below 0.05)
+/// mean the observations are unlikely to come from the expected probabilities.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GoodnessOfFit {
+    /// Pearson's chi-squared statistic
+    pub chi_squared: f64,
+    pub degrees_of_freedom: usize,
+    pub chi_squared_p_value: f64,
+    /// Largest difference between the observed and expected cumulative distributions
+    pub ks_statistic: f64,
+    /// Asymptotic p-value of the Kolmogorov-Smirnov test, conservative for discrete distributions
+    pub ks_p_value: f64,
+}
+
+/// Compares observed counts with the expected probability of each category, the probabilities
+/// have to sum up to 1. Categories that are not expected at all but were observed make the fit
+/// impossible. Fails if not every category has an expected probability.
+pub fn goodness_of_fit(
+    observed: &[usize],
+    expected: &[f64],
+) -> Result<GoodnessOfFit, ForecastError> {
+    if observed.len() != expected.len() {
+        return Err(ForecastError::MismatchedCategories {
+            observed: observed.len(),
+            expected: expected.len(),
+        });
+    }
+    let total: usize = observed.iter().sum();
+    let n = total as f64;
+
+    let mut chi_squared = 0.0;
+    let mut categories: usize = 0;
+    for (observed, probability) in observed.iter().zip(expected) {
+        let expected = probability * n;
+        if expected > 0.0 {
+            chi_squared += (*observed as f64 - expected).powi(2) / expected;
+            categories += 1;
+        } else if *observed > 0 {
+            chi_squared = f64::INFINITY;
+        }
+    }
+    let degrees_of_freedom = categories.saturating_sub(1);
+    let chi_squared_p_value = if chi_squared.is_infinite() {
+        0.0
+    } else if degrees_of_freedom == 0 {
+        1.0
+    } else {
+        upper_incomplete_gamma(degrees_of_freedom as f64 / 2.0, chi_squared / 2.0)
+    };
+
+    let mut ks_statistic: f64 = 0.0;
+    let (mut observed_sum, mut expected_sum) = (0.0, 0.0);
+    for (observed, probability) in observed.iter().zip(expected) {
+        observed_sum += *observed as f64 / n.max(1.0);
+        expected_sum += probability;
+        ks_statistic = ks_statistic.max((observed_sum - expected_sum).abs());
+    }
+
+    Ok(GoodnessOfFit {
+        chi_squared,
+        degrees_of_freedom,
+        chi_squared_p_value,
+        ks_statistic,
+        ks_p_value: kolmogorov_p_value(n.sqrt() * ks_statistic),
+    })
+}
+
+impl Simulation {
+    /// Fit of the weather of the session's slots to the configured probability of each option.
+    /// Coupling, transitions and unique slots shift the weather on purpose, so a bad fit is
+    /// expected unless they are disabled.
+    pub fn goodness_of_fit<R: Rng>(
+        &self,
+        session: Sessions,
+        forecaster: &WeatherForecaster<R>,
+    ) -> Result<GoodnessOfFit, ForecastError> {
+        let counts = self.counts.get(&session);
+        let (observed, expected): (Vec<usize>, Vec<f64>) = WeatherOptions::iter()
+            .map(|option| {
+                let observed = counts
+                    .and_then(|counts| counts.get(&option))
+                    .copied()
+                    .unwrap_or_default();
+                (observed, forecaster.get_probability(option))
+            })
+            .unzip();
+        goodness_of_fit(&observed, &expected)
+    }
+}
+
+impl std::fmt::Display for GoodnessOfFit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "chi² = {} ({} degrees of freedom, p = {}), KS D = {} (p = {})",
+            self.chi_squared.round_to_decimal_place(2),
+            self.degrees_of_freedom,
+            self.chi_squared_p_value.round_to_decimal_place(4),
+            self.ks_statistic.round_to_decimal_place(4),
+            self.ks_p_value.round_to_decimal_place(4)
+        )
+    }
+}
+
+/// Regularized upper incomplete gamma function Q(a, x), the survival function of the
+/// chi-squared distribution with `2a` degrees of freedom at `2x`.
+fn upper_incomplete_gamma(a: f64, x: f64) -> f64 {
+    const EPSILON: f64 = 1e-14;
+    const TINY: f64 = 1e-300;
+    if x <= 0.0 {
+        return 1.0;
+    }
+    let prefactor = (-x + a * x.ln() - ln_gamma(a)).exp();
+
+    if x < a + 1.0 {
+        // series of the lower function
+        let (mut term, mut sum, mut denominator) = (1.0 / a, 1.0 / a, a);
+        for _ in 0..1000 {
+            denominator += 1.0;
+            term *= x / denominator;
+            sum += term;
+            if term.abs() < sum.abs() * EPSILON {
+                break;
+            }
+        }
+        return (1.0 - sum * prefactor).clamp(0.0, 1.0);
+    }
+
+    // continued fraction of the upper function (modified Lentz)
+    let mut b = x + 1.0 - a;
+    let mut c = 1.0 / TINY;
+    let mut d = 1.0 / b;
+    let mut result = d;
+    for i in 1..1000 {
+        let an = -(i as f64) * (i as f64 - a);
+        b += 2.0;
+        d = an * d + b;
+        if d.abs() < TINY {
+            d = TINY;
+        }
+        c = b + an / c;
+        if c.abs() < TINY {
+            c = TINY;
+        }
+        d = 1.0 / d;
+        let delta = d * c;
+        result *= delta;
+        if (delta - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+    (result * prefactor).clamp(0.0, 1.0)
+}
+
+/// Natural logarithm of the gamma function (Lanczos approximation).
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 6] = [
+        76.180_091_729_471_46,
+        -86.505_320_329_416_77,
+        24.014_098_240_830_91,
+        -1.231_739_572_450_155,
+        0.120_865_097_386_617_9e-2,
+        -0.539_523_938_495_3e-5,
+    ];
+    let mut tmp = x + 5.5;
+    tmp -= (x + 0.5) * tmp.ln();
+    let mut series = 1.000_000_000_190_015;
+    let mut y = x;
+    for coefficient in COEFFICIENTS {
+        y += 1.0;
+        series += coefficient / y;
+    }
+    -tmp + (2.506_628_274_631_000_5 * series / x).ln()
+}
+
+/// Probability that the Kolmogorov distribution exceeds `lambda`.
+fn kolmogorov_p_value(lambda: f64) -> f64 {
+    if lambda < 0.2 {
+        return 1.0;
+    }
+    let sum: f64 = (1..=100)
+        .map(|k| {
+            let sign = if k % 2 == 1 { 1.0 } else { -1.0 };
+            sign * (-2.0 * (k * k) as f64 * lambda * lambda).exp()
+        })
+        .sum();
+    (2.0 * sum).clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::assert_approx_eq;
+
+    use super::*;
+
+    #[test]
+    fn fit_of_known_samples() {
+        let perfect = goodness_of_fit(&[50, 30, 20], &[0.5, 0.3, 0.2]).unwrap();
+        assert_approx_eq!(f64, perfect.chi_squared, 0.0);
+        assert_eq!(perfect.degrees_of_freedom, 2);
+        assert_approx_eq!(f64, perfect.chi_squared_p_value, 1.0);
+        assert_approx_eq!(f64, perfect.ks_p_value, 1.0);
+
+        let skewed = goodness_of_fit(&[60, 40], &[0.5, 0.5]).unwrap();
+        assert_approx_eq!(f64, skewed.chi_squared, 4.0);
+        assert_approx_eq!(f64, skewed.chi_squared_p_value, 0.0455, epsilon = 1e-4);
+        assert_approx_eq!(f64, skewed.ks_statistic, 0.1, epsilon = 1e-12);
+        assert_approx_eq!(f64, skewed.ks_p_value, 0.27, epsilon = 1e-3);
+
+        let chi_squared_10 = upper_incomplete_gamma(5.0, 9.0);
+        assert_approx_eq!(f64, chi_squared_10, 0.055, epsilon = 1e-4);
+
+        let impossible = goodness_of_fit(&[5, 1], &[1.0, 0.0]).unwrap();
+        assert_eq!(impossible.chi_squared_p_value, 0.0);
+        assert!(matches!(
+            goodness_of_fit(&[5, 1], &[1.0]),
+            Err(ForecastError::MismatchedCategories {
+                observed: 2,
+                expected: 1
+            })
+        ));
+
+        let mut forecaster = WeatherForecaster::default().with_seed(1);
+        let simulation = Simulation::run(&mut forecaster, &[Sessions::Race], 20).unwrap();
+        let fit = simulation
+            .goodness_of_fit(Sessions::Race, &forecaster)
+            .unwrap();
+        assert!((0.0..=1.0).contains(&fit.chi_squared_p_value));
+        assert!((0.0..=1.0).contains(&fit.ks_statistic));
+    }
+}
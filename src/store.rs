@@ -0,0 +1,209 @@
+use std::{collections::BTreeMap, path::Path};
+
+use rusqlite::{Connection, params};
+use strum::IntoEnumIterator;
+
+use crate::{
+    config::Config,
+    error::ForecastError,
+    forecaster::{Sessions, WeatherForecast},
+};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS forecasts (
+    id INTEGER PRIMARY KEY,
+    timestamp INTEGER NOT NULL,
+    seed INTEGER NOT NULL,
+    config TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS slots (
+    forecast_id INTEGER NOT NULL REFERENCES forecasts(id) ON DELETE CASCADE,
+    session TEXT NOT NULL,
+    slot INTEGER NOT NULL,
+    weather TEXT NOT NULL,
+    game_string TEXT NOT NULL,
+    rain_intensity INTEGER NOT NULL,
+    PRIMARY KEY (forecast_id, session, slot)
+);
+";
+
+/// A forecast read back from the store.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredForecast {
+    pub id: i64,
+    /// Seconds since the unix epoch
+    pub timestamp: u64,
+    pub seed: u64,
+    /// Game strings of the weather slots of every generated session
+    pub sessions: BTreeMap<Sessions, Vec<String>>,
+}
+
+/// SQLite database of generated forecasts together with the config and seed they were
+/// generated with.
+pub struct ForecastStore {
+    connection: Connection,
+}
+
+impl ForecastStore {
+    pub fn open(path: &Path) -> Result<Self, ForecastError> {
+        Self::with_connection(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> Result<Self, ForecastError> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(connection: Connection) -> Result<Self, ForecastError> {
+        connection.execute_batch(SCHEMA)?;
+        Ok(Self { connection })
+    }
+
+    /// Stores a forecast and returns its id.
+    pub fn insert(
+        &mut self,
+        timestamp: u64,
+        seed: u64,
+        config: &Config,
+        forecast: &WeatherForecast,
+    ) -> Result<i64, ForecastError> {
+        let config = serde_json::to_string(config)?;
+        let transaction = self.connection.transaction()?;
+        // SQLite only knows signed integers, the seed is stored with the same bits
+        transaction.execute(
+            "INSERT INTO forecasts (timestamp, seed, config) VALUES (?1, ?2, ?3)",
+            params![timestamp as i64, seed as i64, config],
+        )?;
+        let id = transaction.last_insert_rowid();
+
+        for session in Sessions::iter() {
+            let Some(options) = forecast.forecast.get(&session) else {
+                continue;
+            };
+            for (index, option) in options.iter().enumerate() {
+                transaction.execute(
+                    "INSERT INTO slots (forecast_id, session, slot, weather, game_string, rain_intensity)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    params![
+                        id,
+                        format!("{session:?}"),
+                        index as i64 + 1,
+                        format!("{option:?}"),
+                        forecast.game_string(session, index),
+                        option.rain_intensity() as i64,
+                    ],
+                )?;
+            }
+        }
+        transaction.commit()?;
+        Ok(id)
+    }
+
+    pub fn get(&self, id: i64) -> Result<Option<StoredForecast>, ForecastError> {
+        Ok(self
+            .query(
+                "SELECT id, timestamp, seed FROM forecasts WHERE id = ?1",
+                id,
+            )?
+            .pop())
+    }
+
+    /// The config a forecast was generated with.
+    pub fn config(&self, id: i64) -> Result<Option<Config>, ForecastError> {
+        let mut statement = self
+            .connection
+            .prepare("SELECT config FROM forecasts WHERE id = ?1")?;
+        let mut rows = statement.query([id])?;
+        match rows.next()? {
+            Some(row) => Ok(Some(serde_json::from_str(&row.get::<_, String>(0)?)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// All forecasts generated since `timestamp` that have rain in the race.
+    pub fn wet_races(&self, since: u64) -> Result<Vec<StoredForecast>, ForecastError> {
+        self.query(
+            "SELECT id, timestamp, seed FROM forecasts
+             WHERE timestamp >= ?1 AND EXISTS (
+                 SELECT 1 FROM slots
+                 WHERE forecast_id = forecasts.id AND session = 'Race' AND rain_intensity > 0
+             )
+             ORDER BY timestamp",
+            since as i64,
+        )
+    }
+
+    fn query(&self, sql: &str, parameter: i64) -> Result<Vec<StoredForecast>, ForecastError> {
+        let mut statement = self.connection.prepare(sql)?;
+        let forecasts = statement
+            .query_map([parameter], |row| {
+                Ok(StoredForecast {
+                    id: row.get(0)?,
+                    timestamp: row.get::<_, i64>(1)? as u64,
+                    seed: row.get::<_, i64>(2)? as u64,
+                    sessions: BTreeMap::new(),
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut statement = self.connection.prepare(
+            "SELECT session, game_string FROM slots WHERE forecast_id = ?1 ORDER BY session, slot",
+        )?;
+        forecasts
+            .into_iter()
+            .map(|mut forecast| {
+                let mut rows = statement.query([forecast.id])?;
+                while let Some(row) = rows.next()? {
+                    let session: String = row.get(0)?;
+                    let Some(session) = Sessions::iter().find(|s| format!("{s:?}") == session)
+                    else {
+                        continue;
+                    };
+                    forecast
+                        .sessions
+                        .entry(session)
+                        .or_default()
+                        .push(row.get(1)?);
+                }
+                Ok(forecast)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forecaster::WeatherOptions;
+
+    #[test]
+    fn wet_races_are_found() {
+        let mut store = ForecastStore::open_in_memory().unwrap();
+        let config = Config::default();
+
+        let mut dry = WeatherForecast::default();
+        dry.forecast
+            .insert(Sessions::Race, vec![WeatherOptions::Clear]);
+        let mut wet = WeatherForecast::default();
+        wet.forecast.insert(
+            Sessions::Race,
+            vec![WeatherOptions::Overcast, WeatherOptions::Rain],
+        );
+        wet.forecast
+            .insert(Sessions::Qualifying, vec![WeatherOptions::Clear]);
+
+        store.insert(10, 1, &config, &dry).unwrap();
+        let old = store.insert(20, u64::MAX, &config, &wet).unwrap();
+        let new = store.insert(30, 3, &config, &wet).unwrap();
+
+        let ids: Vec<_> = store.wet_races(0).unwrap().iter().map(|f| f.id).collect();
+        assert_eq!(ids, [old, new]);
+        let ids: Vec<_> = store.wet_races(25).unwrap().iter().map(|f| f.id).collect();
+        assert_eq!(ids, [new]);
+
+        let stored = store.get(old).unwrap().unwrap();
+        assert_eq!(stored.seed, u64::MAX);
+        assert_eq!(stored.sessions[&Sessions::Race], ["Overcast", "Rain"]);
+        assert_eq!(stored.sessions[&Sessions::Qualifying], ["Clear"]);
+        assert_eq!(store.config(old).unwrap(), Some(config));
+    }
+}
@@ -0,0 +1,244 @@
+use crate::forecaster::{Sessions, WeatherForecast, WeatherOptions};
+
+/// How much rain a session of a forecast gets.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SessionSummary {
+    /// Share of the slots with rain from `0.0` to `1.0`
+    pub wet_share: f64,
+    /// Weather with the most intense rain, `None` if the session stays dry
+    pub peak: Option<WeatherOptions>,
+}
+
+impl WeatherForecast {
+    /// Summary of the session, `None` if the forecast does not contain it.
+    pub fn session_summary(&self, session: Sessions) -> Option<SessionSummary> {
+        let options = self.forecast.get(&session)?;
+        let wet_slots = options
+            .iter()
+            .filter(|option| option.rain_intensity() > 0)
+            .count();
+        // the first of equally intense options wins
+        let peak = options
+            .iter()
+            .rev()
+            .max_by_key(|option| option.rain_intensity())
+            .filter(|option| option.rain_intensity() > 0)
+            .copied();
+        Some(SessionSummary {
+            wet_share: wet_slots as f64 / options.len().max(1) as f64,
+            peak,
+        })
+    }
+
+    /// One line per session with its share of wet slots and heaviest rain.
+    pub fn summary(&self) -> ForecastSummary<'_> {
+        ForecastSummary(self)
+    }
+
+    /// The forecast as a sentence for humans, e.g. "Expect a dry qualifying; light rain arriving
+    /// mid-race, clearing towards the end."
+    pub fn narrative(&self) -> String {
+        let mut phrases = Vec::new();
+        // consecutive dry sessions are mentioned together
+        let mut dry = Vec::new();
+        for session in self.sessions() {
+            match self.describe_rain(session) {
+                Some(rain) => {
+                    if !dry.is_empty() {
+                        phrases.push(format!("a dry {}", list(&dry)));
+                        dry.clear();
+                    }
+                    phrases.push(rain);
+                }
+                None => dry.push(self.spoken_name(session)),
+            }
+        }
+        if !dry.is_empty() {
+            phrases.push(format!("a dry {}", list(&dry)));
+        }
+        if phrases.is_empty() {
+            return String::new();
+        }
+        format!("Expect {}.", phrases.join("; "))
+    }
+
+    pub(crate) fn spoken_name(&self, session: Sessions) -> String {
+        match self.session_name(session).to_lowercase() {
+            name if name == "qualify" => "qualifying".to_string(),
+            name => name,
+        }
+    }
+
+    /// When the rain of the session comes and goes, `None` if it stays dry.
+    pub(crate) fn describe_rain(&self, session: Sessions) -> Option<String> {
+        let name = self.spoken_name(session);
+        let options = &self.forecast[&session];
+        let peak = self.session_summary(session)?.peak?;
+        let rain = match peak {
+            WeatherOptions::LightRain => "light rain",
+            WeatherOptions::Storm => "heavy rain",
+            WeatherOptions::Thunderstorm => "thunderstorms",
+            WeatherOptions::FogWithRain | WeatherOptions::HeavyFogWithRain => "rain and fog",
+            _ => "rain",
+        };
+
+        let wet: Vec<bool> = options
+            .iter()
+            .map(|option| option.rain_intensity() > 0)
+            .collect();
+        let first = wet.iter().position(|wet| *wet).unwrap_or_default();
+        let last = wet.iter().rposition(|wet| *wet).unwrap_or_default();
+        if wet[first..=last].contains(&false) {
+            return Some(format!("{rain} on and off during the {name}"));
+        }
+
+        let arrival = match self.slot_start(session, first) {
+            0.0 if last + 1 == options.len() => {
+                return Some(format!("{rain} throughout the {name}"));
+            }
+            0.0 => format!("{rain} from the start of the {name}"),
+            start if start < 1.0 / 3.0 => format!("{rain} arriving early in the {name}"),
+            start if start < 2.0 / 3.0 => format!("{rain} arriving mid-{name}"),
+            _ => format!("{rain} arriving late in the {name}"),
+        };
+        if last + 1 == options.len() {
+            return Some(arrival);
+        }
+        let clearing = match self.slot_start(session, last + 1) {
+            end if end < 1.0 / 3.0 => "clearing early on".to_string(),
+            end if end < 2.0 / 3.0 => format!("clearing by mid-{name}"),
+            _ => "clearing towards the end".to_string(),
+        };
+        Some(format!("{arrival}, {clearing}"))
+    }
+
+    /// Share of the session that has passed when the slot begins, slots are evenly spread if
+    /// their timings were not generated.
+    fn slot_start(&self, session: Sessions, index: usize) -> f64 {
+        self.slot_timings
+            .get(&session)
+            .and_then(|timings| timings.get(index))
+            .map_or_else(
+                || index as f64 / self.forecast[&session].len() as f64,
+                |timing| timing.start,
+            )
+    }
+}
+
+/// Joins the names like "practice, qualifying and race".
+fn list(names: &[String]) -> String {
+    match names {
+        [] => String::new(),
+        [name] => name.clone(),
+        [names @ .., last] => format!("{} and {last}", names.join(", ")),
+    }
+}
+
+pub struct ForecastSummary<'a>(&'a WeatherForecast);
+
+impl std::fmt::Display for ForecastSummary<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for session in self.0.sessions() {
+            let name = self.0.session_name(session);
+            let Some(summary) = self.0.session_summary(session) else {
+                continue;
+            };
+            match summary.peak {
+                Some(peak) => writeln!(
+                    f,
+                    "{name}: {:.0}% of slots wet, peak intensity {peak:?}",
+                    summary.wet_share * 100.0
+                )?,
+                None => writeln!(f, "{name}: dry")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn one_line_per_session() {
+        let mut forecast = WeatherForecast::default();
+        forecast.forecast.insert(
+            Sessions::Race,
+            vec![
+                WeatherOptions::Clear,
+                WeatherOptions::Thunderstorm,
+                WeatherOptions::Storm,
+                WeatherOptions::Overcast,
+            ],
+        );
+        forecast
+            .forecast
+            .insert(Sessions::Qualifying, vec![WeatherOptions::LightCloud]);
+
+        let race = forecast.session_summary(Sessions::Race).unwrap();
+        assert_eq!(race.wet_share, 0.5);
+        assert_eq!(race.peak, Some(WeatherOptions::Thunderstorm));
+        assert_eq!(forecast.session_summary(Sessions::Practice), None);
+        assert_eq!(
+            forecast.summary().to_string(),
+            "Qualify: dry\nRace: 50% of slots wet, peak intensity Thunderstorm\n"
+        );
+    }
+
+    #[test]
+    fn forecast_becomes_a_sentence() {
+        let mut forecast = WeatherForecast::default();
+        forecast.forecast.insert(
+            Sessions::Race,
+            vec![
+                WeatherOptions::Clear,
+                WeatherOptions::LightCloud,
+                WeatherOptions::LightRain,
+                WeatherOptions::Overcast,
+            ],
+        );
+        forecast
+            .forecast
+            .insert(Sessions::Qualifying, vec![WeatherOptions::Clear]);
+        assert_eq!(
+            forecast.narrative(),
+            "Expect a dry qualifying; light rain arriving mid-race, clearing towards the end."
+        );
+
+        forecast.forecast.insert(
+            Sessions::Qualifying,
+            vec![WeatherOptions::Storm, WeatherOptions::Thunderstorm],
+        );
+        forecast.forecast.insert(
+            Sessions::Race,
+            vec![
+                WeatherOptions::Rain,
+                WeatherOptions::Clear,
+                WeatherOptions::Rain,
+            ],
+        );
+        assert_eq!(
+            forecast.narrative(),
+            "Expect heavy rain throughout the qualifying; rain on and off during the race."
+        );
+        forecast
+            .forecast
+            .insert(Sessions::Practice, vec![WeatherOptions::Hazy]);
+        forecast
+            .forecast
+            .insert(Sessions::Race, vec![WeatherOptions::Clear]);
+        assert_eq!(
+            forecast.narrative(),
+            "Expect a dry practice; heavy rain throughout the qualifying; a dry race."
+        );
+        forecast
+            .forecast
+            .insert(Sessions::Qualifying, vec![WeatherOptions::Clear]);
+        assert_eq!(
+            forecast.narrative(),
+            "Expect a dry practice, qualifying and race."
+        );
+        assert_eq!(WeatherForecast::default().narrative(), "");
+    }
+}
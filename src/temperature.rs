@@ -0,0 +1,117 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::ForecastError,
+    forecaster::{Round, WeatherOptions},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct TemperatureRange {
+    /// Lowest temperature in °C
+    pub min: f64,
+    /// Highest temperature in °C
+    pub max: f64,
+}
+
+impl TemperatureRange {
+    fn lerp(&self, fraction: f64) -> f64 {
+        self.min + (self.max - self.min) * fraction.clamp(0.0, 1.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct TemperatureConfig {
+    pub ambient: TemperatureRange,
+    pub track: TemperatureRange,
+}
+
+impl Default for TemperatureConfig {
+    fn default() -> Self {
+        Self {
+            ambient: TemperatureRange {
+                min: 10.0,
+                max: 32.0,
+            },
+            track: TemperatureRange {
+                min: 12.0,
+                max: 52.0,
+            },
+        }
+    }
+}
+
+impl TemperatureConfig {
+    pub fn validate(&self) -> Result<(), ForecastError> {
+        for (name, range) in [("ambient", self.ambient), ("track", self.track)] {
+            if !range.min.is_finite() || !range.max.is_finite() || range.min > range.max {
+                return Err(ForecastError::InvalidTemperatureRange {
+                    name,
+                    min: range.min,
+                    max: range.max,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Generates the ambient and track temperature of a single weather slot.
+    ///
+    /// `warmth` in `0.0..=1.0` describes how warm the whole event is and is shared by all slots so
+    /// that temperatures stay plausible across sessions. The weather then pulls the temperature
+    /// up (sunshine) or down (clouds and rain), the track reacting stronger than the air.
+    pub fn generate_temperatures<R: Rng>(
+        &self,
+        warmth: f64,
+        option: WeatherOptions,
+        rng: &mut R,
+    ) -> (f64, f64) {
+        self.generate_temperatures_in_daylight(warmth, option, 1.0, rng)
+    }
+
+    /// Generates the temperatures like [`Self::generate_temperatures`] at a time of day with the
+    /// `daylight` from `0.0` (night) to `1.0`. Without the sun the air cools down and the track
+    /// loses the warmth it gets from the sunshine.
+    pub fn generate_temperatures_in_daylight<R: Rng>(
+        &self,
+        warmth: f64,
+        option: WeatherOptions,
+        daylight: f64,
+        rng: &mut R,
+    ) -> (f64, f64) {
+        let sunshine = option.sunshine() * daylight;
+        let ambient_fraction =
+            0.6 * warmth * (0.6 + 0.4 * daylight) + 0.4 * sunshine + rng.random_range(-0.05..=0.05);
+        let track_fraction = 0.4 * ambient_fraction + 0.6 * sunshine;
+
+        (
+            self.ambient
+                .lerp(ambient_fraction)
+                .round_to_decimal_place(1),
+            self.track.lerp(track_fraction).round_to_decimal_place(1),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use super::*;
+
+    #[test]
+    fn rain_is_colder_than_sunshine() {
+        let config = TemperatureConfig::default();
+        let mut rng = StdRng::seed_from_u64(0);
+        for warmth in [0.0, 0.5, 1.0] {
+            let clear = config.generate_temperatures(warmth, WeatherOptions::Clear, &mut rng);
+            let rain = config.generate_temperatures(warmth, WeatherOptions::Rain, &mut rng);
+            assert!(clear.0 > rain.0);
+            assert!(clear.1 > rain.1);
+            for (ambient, track) in [clear, rain] {
+                assert!((10.0..=32.0).contains(&ambient));
+                assert!((12.0..=52.0).contains(&track));
+            }
+        }
+    }
+}
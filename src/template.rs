@@ -0,0 +1,190 @@
+#[cfg(feature = "fs")]
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::{
+    error::ForecastError,
+    forecaster::{Round, WeatherForecast},
+    time_of_day::TimeOfDay,
+};
+
+/// Data a template is rendered with. Sessions are listed in the order of the forecast, slots are
+/// numbered from 1.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TemplateData {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    /// The forecast as a sentence, see [`WeatherForecast::narrative`]
+    pub summary: String,
+    pub sessions: Vec<SessionData>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SessionData {
+    /// Name of the session for humans
+    pub name: String,
+    /// Prefix of the keys of the session in the game's settings
+    pub key: String,
+    /// Whether any slot of the session has rain
+    pub wet: bool,
+    /// Wetness of the track at the start from `0.0` (dry) to `1.0`, rounded to two decimal places
+    pub starting_wetness: f64,
+    pub slots: Vec<SlotData>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SlotData {
+    pub number: usize,
+    pub weather: String,
+    pub emoji: String,
+    pub game_string: String,
+    pub rain_intensity: usize,
+    /// Air temperature in °C rounded to one decimal place
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ambient_temperature: Option<f64>,
+    /// Track temperature in °C rounded to one decimal place
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub track_temperature: Option<f64>,
+    /// Relative humidity in whole percent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub humidity: Option<f64>,
+    /// Share of the sky covered by clouds in whole percent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cloud_cover: Option<f64>,
+    /// Share of the session that has passed when the slot begins
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start: Option<f64>,
+    /// Share of the session the slot lasts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_time: Option<TimeOfDay>,
+}
+
+impl TemplateData {
+    pub fn new(forecast: &WeatherForecast, seed: Option<u64>) -> Self {
+        let sessions = forecast
+            .starting_wetness()
+            .into_iter()
+            .map(|(session, starting_wetness)| {
+                let options = &forecast.forecast[&session];
+                let conditions = forecast.conditions.get(&session);
+                let timings = forecast.slot_timings.get(&session);
+                let slots = options
+                    .iter()
+                    .enumerate()
+                    .map(|(index, option)| {
+                        let conditions = conditions.and_then(|conditions| conditions.get(index));
+                        let timing = timings.and_then(|timings| timings.get(index));
+                        SlotData {
+                            number: index + 1,
+                            weather: format!("{option:?}"),
+                            emoji: option.emoji().to_string(),
+                            game_string: forecast.game_string(session, index),
+                            rain_intensity: option.rain_intensity(),
+                            ambient_temperature: conditions
+                                .map(|c| c.ambient_temperature.round_to_decimal_place(1)),
+                            track_temperature: conditions
+                                .map(|c| c.track_temperature.round_to_decimal_place(1)),
+                            humidity: conditions.map(|c| c.humidity.round()),
+                            cloud_cover: conditions.map(|c| c.cloud_cover.round()),
+                            start: timing.map(|timing| timing.start),
+                            duration: timing.map(|timing| timing.duration),
+                            start_time: timing.and_then(|timing| timing.time),
+                        }
+                    })
+                    .collect();
+
+                SessionData {
+                    name: forecast.session_name(session),
+                    key: forecast.key_prefix(session),
+                    wet: options.iter().any(|option| option.rain_intensity() > 0),
+                    starting_wetness: starting_wetness.round_to_decimal_place(2),
+                    slots,
+                }
+            })
+            .collect();
+
+        Self {
+            seed,
+            summary: forecast.narrative(),
+            sessions,
+        }
+    }
+}
+
+/// Renders a [Handlebars](https://handlebarsjs.com) template with the [`TemplateData`] of the
+/// forecast. Nothing gets escaped, the output is not meant to be HTML.
+#[cfg(feature = "templates")]
+pub fn render(
+    template: &str,
+    forecast: &WeatherForecast,
+    seed: Option<u64>,
+) -> Result<String, ForecastError> {
+    let mut handlebars = handlebars::Handlebars::new();
+    handlebars.register_escape_fn(handlebars::no_escape);
+    handlebars
+        .render_template(template, &TemplateData::new(forecast, seed))
+        .map_err(|error| ForecastError::Template(error.to_string()))
+}
+
+#[cfg(not(feature = "templates"))]
+pub fn render(
+    _template: &str,
+    _forecast: &WeatherForecast,
+    _seed: Option<u64>,
+) -> Result<String, ForecastError> {
+    Err(ForecastError::MissingFeature {
+        what: "templates",
+        feature: "templates",
+    })
+}
+
+#[cfg(feature = "fs")]
+pub fn load(path: &Path) -> Result<String, ForecastError> {
+    std::fs::read_to_string(path).map_err(|error| ForecastError::io(path, error))
+}
+
+#[cfg(all(test, feature = "templates"))]
+mod tests {
+    use super::*;
+    use crate::forecaster::{Sessions, WeatherForecaster, WeatherOptions};
+
+    #[test]
+    fn templates_see_sessions_and_slots() {
+        let mut forecast = WeatherForecast::default();
+        forecast.forecast.insert(
+            Sessions::Race,
+            vec![WeatherOptions::Clear, WeatherOptions::Rain],
+        );
+        forecast
+            .forecast
+            .insert(Sessions::Qualifying, vec![WeatherOptions::Overcast]);
+
+        let template = "\
+{{#each sessions}}{{name}}{{#if wet}} (wet){{/if}}:{{#each slots}} {{number}}={{weather}}{{/each}}
+{{/each}}seed {{seed}}";
+        assert_eq!(
+            render(template, &forecast, Some(3)).unwrap(),
+            "Qualify: 1=Overcast\nRace (wet): 1=Clear 2=Rain\nseed 3"
+        );
+        assert!(matches!(
+            render("{{#each}}", &forecast, None),
+            Err(ForecastError::Template(_))
+        ));
+
+        let forecast = WeatherForecaster::default()
+            .with_seed(1)
+            .generate_forecast(&[Sessions::Race])
+            .unwrap();
+        let rendered = render(
+            "{{#each sessions}}{{#each slots}}{{ambient_temperature}}°C {{/each}}{{/each}}",
+            &forecast,
+            None,
+        )
+        .unwrap();
+        assert_eq!(rendered.matches("°C").count(), 4);
+        assert!(!rendered.starts_with('°'));
+    }
+}
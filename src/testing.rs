@@ -0,0 +1,118 @@
+use rand::RngCore;
+
+use crate::forecaster::{Sessions, WeatherForecast, WeatherOptions};
+
+/// Random number generator that returns a scripted sequence of values in `0.0..1.0` instead of
+/// random ones, starting over once all of them were used. A value `x` is drawn as the float `x`
+/// and picks about the index `x * n` of `n` options, so sampling can be tested without
+/// statistics.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptedRng {
+    values: Vec<f64>,
+    draws: usize,
+}
+
+impl ScriptedRng {
+    /// Panics if there are no values or one is outside of `0.0..1.0`.
+    pub fn new(values: impl Into<Vec<f64>>) -> Self {
+        let values = values.into();
+        assert!(!values.is_empty(), "a scripted RNG needs values");
+        assert!(
+            values.iter().all(|value| (0.0..1.0).contains(value)),
+            "scripted values have to be in 0.0..1.0, got {values:?}"
+        );
+        Self { values, draws: 0 }
+    }
+
+    /// How many values have been drawn so far.
+    pub fn draws(&self) -> usize {
+        self.draws
+    }
+
+    fn next_value(&mut self) -> f64 {
+        let value = self.values[self.draws % self.values.len()];
+        self.draws += 1;
+        value
+    }
+}
+
+impl RngCore for ScriptedRng {
+    /// Rounded up, so a value of `0.9` picks the index 9 of 10 despite rounding errors.
+    fn next_u32(&mut self) -> u32 {
+        (self.next_value() * 2f64.powi(32)).ceil() as u32
+    }
+
+    /// The 53 bits of precision of a float in the high bits, where rand takes them from.
+    fn next_u64(&mut self) -> u64 {
+        ((self.next_value() * 2f64.powi(53)) as u64) << 11
+    }
+
+    fn fill_bytes(&mut self, destination: &mut [u8]) {
+        for chunk in destination.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+}
+
+/// Panics unless no probability is negative and they sum up to 1.
+#[track_caller]
+pub fn assert_normalized(probabilities: impl IntoIterator<Item = f64>) {
+    let probabilities: Vec<f64> = probabilities.into_iter().collect();
+    assert!(
+        probabilities.iter().all(|probability| *probability >= 0.0),
+        "negative probability in {probabilities:?}"
+    );
+    let sum: f64 = probabilities.iter().sum();
+    assert!(
+        (sum - 1.0).abs() < 1e-9,
+        "probabilities sum up to {sum} instead of 1: {probabilities:?}"
+    );
+}
+
+/// Panics unless the slots of the session have exactly the expected weather.
+#[track_caller]
+pub fn assert_slots(forecast: &WeatherForecast, session: Sessions, expected: &[WeatherOptions]) {
+    let slots = forecast
+        .forecast
+        .get(&session)
+        .unwrap_or_else(|| panic!("the forecast has no {session}"));
+    assert_eq!(slots, expected, "weather of the slots of {session}");
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::*;
+    use crate::{config::Config, forecaster::WeatherForecaster, sampling::AliasTable};
+
+    #[test]
+    fn scripted_values_pick_the_samples() {
+        let mut rng = ScriptedRng::new([0.25, 0.9]);
+        assert_eq!(rng.random::<f64>(), 0.25);
+        assert_eq!(rng.random_range(0..10), 9);
+        assert_eq!(rng.random::<f64>(), 0.25);
+        assert_eq!(rng.draws(), 3);
+
+        // column 0 keeps its index below 0.5, the rest of it is taken by its alias 1
+        let table = AliasTable::new(vec![1.0, 3.0]);
+        assert_eq!(table.sample(&mut ScriptedRng::new([0.1, 0.3])), 0);
+        assert_eq!(table.sample(&mut ScriptedRng::new([0.1, 0.7])), 1);
+        assert_eq!(table.sample(&mut ScriptedRng::new([0.6, 0.1])), 1);
+
+        let probabilities = [(WeatherOptions::Clear, 1.0)].into_iter().collect();
+        let config = Config {
+            probabilities,
+            ..Config::default()
+        };
+        let mut forecaster = WeatherForecaster::with_rng(config, ScriptedRng::new([0.5])).unwrap();
+        assert_normalized(
+            forecaster
+                .probabilities()
+                .map(|(_, probability)| probability),
+        );
+        let forecast = forecaster.generate_forecast(&[Sessions::Race]).unwrap();
+        assert_slots(&forecast, Sessions::Race, &[WeatherOptions::Clear; 4]);
+    }
+}
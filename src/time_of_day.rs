@@ -0,0 +1,183 @@
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{error::ForecastError, forecaster::WeatherOptions};
+
+const SUNRISE: f64 = 6.0;
+const SUNSET: f64 = 20.0;
+
+/// Local time at which a session starts, written as `HH:MM` in the config.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    schemars::JsonSchema,
+)]
+#[serde(try_from = "String", into = "String")]
+pub struct TimeOfDay {
+    hour: u8,
+    minute: u8,
+}
+
+impl TimeOfDay {
+    pub fn new(hour: u8, minute: u8) -> Result<Self, ForecastError> {
+        if hour > 23 || minute > 59 {
+            return Err(ForecastError::InvalidTimeOfDay(format!("{hour}:{minute}")));
+        }
+        Ok(Self { hour, minute })
+    }
+
+    pub fn hour(&self) -> u8 {
+        self.hour
+    }
+
+    pub fn minute(&self) -> u8 {
+        self.minute
+    }
+
+    /// The time `minutes` later, wrapping around at midnight.
+    #[must_use]
+    pub fn add_minutes(&self, minutes: u32) -> Self {
+        let minutes = (u32::from(self.hour) * 60 + u32::from(self.minute) + minutes) % (24 * 60);
+        Self {
+            hour: (minutes / 60) as u8,
+            minute: (minutes % 60) as u8,
+        }
+    }
+
+    /// Strength of the daylight from `0.0` at night to `1.0` in the early afternoon, the sun rises
+    /// at 6:00 and sets at 20:00.
+    pub fn daylight(&self) -> f64 {
+        let hours = f64::from(self.hour) + f64::from(self.minute) / 60.0;
+        if (SUNRISE..=SUNSET).contains(&hours) {
+            (std::f64::consts::PI * (hours - SUNRISE) / (SUNSET - SUNRISE)).sin()
+        } else {
+            0.0
+        }
+    }
+
+    /// Factor the probability of `option` gets multiplied with at this time of day.
+    ///
+    /// Fog and haze build up during the night and burn off in the late morning, while
+    /// thunderstorms need the heat of the afternoon to develop.
+    pub fn probability_modifier(&self, option: WeatherOptions) -> f64 {
+        #[rustfmt::skip]
+        const FOG: [f64; 24] = [
+            1.5, 1.6, 1.8, 2.0, 2.3, 2.5, 2.5, 2.2, 1.7, 1.2, 0.8, 0.6,
+            0.5, 0.5, 0.5, 0.5, 0.6, 0.7, 0.8, 1.0, 1.1, 1.2, 1.3, 1.4,
+        ];
+        #[rustfmt::skip]
+        const HAZE: [f64; 24] = [
+            1.2, 1.2, 1.3, 1.3, 1.4, 1.5, 1.5, 1.4, 1.3, 1.1, 1.0, 0.9,
+            0.9, 0.9, 0.9, 0.9, 0.9, 1.0, 1.0, 1.0, 1.1, 1.1, 1.1, 1.2,
+        ];
+        #[rustfmt::skip]
+        const CONVECTIVE: [f64; 24] = [
+            0.5, 0.5, 0.4, 0.4, 0.4, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9, 1.1,
+            1.3, 1.6, 1.9, 2.1, 2.1, 1.8, 1.5, 1.2, 0.9, 0.7, 0.6, 0.5,
+        ];
+
+        let table = match option {
+            WeatherOptions::Foggy
+            | WeatherOptions::FogWithRain
+            | WeatherOptions::HeavyFog
+            | WeatherOptions::HeavyFogWithRain => &FOG,
+            WeatherOptions::Hazy => &HAZE,
+            WeatherOptions::Storm | WeatherOptions::Thunderstorm => &CONVECTIVE,
+            _ => return 1.0,
+        };
+
+        // interpolate between the full hours
+        let current = table[self.hour as usize];
+        let next = table[(self.hour as usize + 1) % 24];
+        current + (next - current) * f64::from(self.minute) / 60.0
+    }
+}
+
+/// Factor the probability of `option` gets multiplied with in a night session. Fog builds up in
+/// the dark, while haze needs the sun to be seen at all.
+pub fn night_probability_modifier(option: WeatherOptions) -> f64 {
+    match option {
+        WeatherOptions::Foggy
+        | WeatherOptions::FogWithRain
+        | WeatherOptions::HeavyFog
+        | WeatherOptions::HeavyFogWithRain => 2.0,
+        WeatherOptions::Hazy => 0.0,
+        _ => 1.0,
+    }
+}
+
+impl FromStr for TimeOfDay {
+    type Err = ForecastError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ForecastError::InvalidTimeOfDay(s.to_string());
+        let (hour, minute) = s.trim().split_once(':').ok_or_else(invalid)?;
+        let hour = hour.parse().map_err(|_| invalid())?;
+        let minute = minute.parse().map_err(|_| invalid())?;
+        Self::new(hour, minute).map_err(|_| invalid())
+    }
+}
+
+impl TryFrom<String> for TimeOfDay {
+    type Error = ForecastError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<TimeOfDay> for String {
+    fn from(value: TimeOfDay) -> Self {
+        value.to_string()
+    }
+}
+
+impl Display for TimeOfDay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:02}:{:02}", self.hour, self.minute)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_time_of_day() {
+        assert_eq!(
+            "07:30".parse::<TimeOfDay>().unwrap(),
+            TimeOfDay::new(7, 30).unwrap()
+        );
+        assert_eq!("14:05".parse::<TimeOfDay>().unwrap().to_string(), "14:05");
+        assert!("24:00".parse::<TimeOfDay>().is_err());
+        assert!("noon".parse::<TimeOfDay>().is_err());
+        assert_eq!(
+            TimeOfDay::new(23, 30).unwrap().add_minutes(45).to_string(),
+            "00:15"
+        );
+    }
+
+    #[test]
+    fn fog_in_the_morning_storms_in_the_afternoon() {
+        let morning = TimeOfDay::new(6, 0).unwrap();
+        let afternoon = TimeOfDay::new(15, 0).unwrap();
+        assert!(
+            morning.probability_modifier(WeatherOptions::HeavyFog)
+                > afternoon.probability_modifier(WeatherOptions::HeavyFog)
+        );
+        assert!(
+            morning.probability_modifier(WeatherOptions::Thunderstorm)
+                < afternoon.probability_modifier(WeatherOptions::Thunderstorm)
+        );
+        assert_eq!(morning.probability_modifier(WeatherOptions::Clear), 1.0);
+    }
+}
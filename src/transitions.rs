@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{catalog::WeatherCatalog, error::ForecastError, forecaster::WeatherOptions};
+
+/// Markov chain model for consecutive weather slots of a session.
+///
+/// The probability of the next slot is the base probability multiplied with the transition weight
+/// from the previous slot. Transitions missing in `matrix` use the built-in weights which favour
+/// small changes in cloudiness and rain intensity.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct TransitionConfig {
+    /// Transition weights as `from: { to: weight }`
+    #[serde(default)]
+    pub matrix: HashMap<WeatherOptions, HashMap<WeatherOptions, f64>>,
+}
+
+impl TransitionConfig {
+    pub fn validate(&self) -> Result<(), ForecastError> {
+        for (from, row) in &self.matrix {
+            for (to, weight) in row {
+                if !weight.is_finite() || *weight < 0.0 {
+                    return Err(ForecastError::InvalidTransitionWeight {
+                        from: *from,
+                        to: *to,
+                        value: *weight,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn weight(&self, from: WeatherOptions, to: WeatherOptions) -> f64 {
+        self.matrix
+            .get(&from)
+            .and_then(|row| row.get(&to))
+            .copied()
+            .unwrap_or_else(|| default_transition_weight(from, to))
+    }
+
+    /// Conditions the probabilities of the catalog entries on the entry of the previous slot.
+    /// Entries use the weights of the built-in option they are based on.
+    pub fn condition(
+        &self,
+        catalog: &WeatherCatalog,
+        probabilities: &[f64],
+        previous: usize,
+    ) -> Vec<f64> {
+        let previous = catalog[previous].option;
+        let mut conditioned: Vec<_> = catalog
+            .entries()
+            .iter()
+            .zip(probabilities)
+            .map(|(entry, probability)| probability * self.weight(previous, entry.option))
+            .collect();
+        let sum: f64 = conditioned.iter().sum();
+        if sum > 0.0 {
+            for probability in &mut conditioned {
+                *probability /= sum;
+            }
+        }
+        conditioned
+    }
+}
+
+/// Built-in transition weight, the bigger the jump in rain intensity and sunshine the less likely
+/// the transition is.
+pub fn default_transition_weight(from: WeatherOptions, to: WeatherOptions) -> f64 {
+    let rain = from.rain_intensity().abs_diff(to.rain_intensity()) as f64;
+    let sunshine = (from.sunshine() - to.sunshine()).abs();
+    1.0 / (1.0 + rain * rain + 2.0 * sunshine)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clear_rarely_jumps_to_thunderstorm() {
+        let transitions = TransitionConfig::default();
+        let clear = WeatherOptions::Clear;
+        assert!(
+            transitions.weight(clear, WeatherOptions::LightCloud)
+                > 5.0 * transitions.weight(clear, WeatherOptions::Thunderstorm)
+        );
+
+        let transitions = TransitionConfig {
+            matrix: [(
+                clear,
+                [(WeatherOptions::Thunderstorm, 2.0)].into_iter().collect(),
+            )]
+            .into_iter()
+            .collect(),
+        };
+        assert_eq!(transitions.weight(clear, WeatherOptions::Thunderstorm), 2.0);
+    }
+}
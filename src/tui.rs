@@ -0,0 +1,156 @@
+use rand::Rng;
+use ratatui::{
+    DefaultTerminal, Frame,
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout},
+    style::{Modifier, Style},
+    widgets::{Block, List, ListItem, ListState, Paragraph},
+};
+use strum::IntoEnumIterator;
+
+use crate::forecaster::{Sessions, WeatherForecast, WeatherForecaster, WeatherOptions};
+
+/// Step by which a probability is changed per key press.
+const PROBABILITY_STEP: f64 = 0.01;
+
+const HELP: &str =
+    "↑/↓ select  ←/→ probability  r reroll all  1-9 reroll session  e export  q quit";
+
+/// Full screen dashboard to tweak the probabilities and reroll `forecast` until it fits.
+///
+/// Returns the forecast and the sessions rerolled in the dashboard once it gets exported, or
+/// `None` if the user quit.
+pub fn run<R: Rng>(
+    forecaster: &mut WeatherForecaster<R>,
+    sessions: &[Sessions],
+    forecast: WeatherForecast,
+) -> std::io::Result<Option<(WeatherForecast, Vec<Sessions>)>> {
+    let mut app = App {
+        forecaster,
+        sessions,
+        forecast,
+        rerolled: Vec::new(),
+        options: ListState::default().with_selected(Some(0)),
+        status: String::new(),
+    };
+
+    let mut terminal = ratatui::init();
+    let result = app.run(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+struct App<'a, R: Rng> {
+    forecaster: &'a mut WeatherForecaster<R>,
+    sessions: &'a [Sessions],
+    forecast: WeatherForecast,
+    rerolled: Vec<Sessions>,
+    options: ListState,
+    status: String,
+}
+
+impl<R: Rng> App<'_, R> {
+    fn run(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+    ) -> std::io::Result<Option<(WeatherForecast, Vec<Sessions>)>> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            self.status.clear();
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(None),
+                KeyCode::Char('e') | KeyCode::Enter => {
+                    return Ok(Some((self.forecast.clone(), self.rerolled.clone())));
+                }
+                KeyCode::Up => self.options.select_previous(),
+                KeyCode::Down => self.options.select_next(),
+                KeyCode::Left | KeyCode::Char('-') => self.change_probability(-PROBABILITY_STEP),
+                KeyCode::Right | KeyCode::Char('+') => self.change_probability(PROBABILITY_STEP),
+                KeyCode::Char('r') => {
+                    match self.forecaster.generate_constrained_forecast(self.sessions) {
+                        Ok(forecast) => {
+                            self.forecast = forecast;
+                            self.rerolled = self.sessions.to_vec();
+                        }
+                        Err(error) => self.status = error.to_string(),
+                    }
+                }
+                KeyCode::Char(digit @ '1'..='9') => {
+                    let index = digit as usize - '1' as usize;
+                    if let Some(session) = self.sessions.get(index) {
+                        self.reroll(*session);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn selected_option(&self) -> Option<WeatherOptions> {
+        WeatherOptions::iter().nth(self.options.selected()?)
+    }
+
+    fn change_probability(&mut self, step: f64) {
+        let Some(option) = self.selected_option() else {
+            return;
+        };
+        let probability = (self.forecaster.get_probability(option) + step).clamp(0.0, 1.0);
+        if let Err(error) = self.forecaster.set_probability(option, probability) {
+            self.status = error.to_string();
+        }
+    }
+
+    fn reroll(&mut self, session: Sessions) {
+        if !self.forecast.contains(session) {
+            self.status = format!("The forecast has no {session} session");
+            return;
+        }
+        match self.forecaster.reroll(&self.forecast, session) {
+            Ok(forecast) => {
+                self.forecast = forecast;
+                if !self.rerolled.contains(&session) {
+                    self.rerolled.push(session);
+                }
+            }
+            Err(error) => self.status = error.to_string(),
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, status, help] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let [probabilities, forecast] =
+            Layout::horizontal([Constraint::Length(32), Constraint::Fill(1)]).areas(main);
+
+        let items: Vec<_> = WeatherOptions::iter()
+            .map(|option| {
+                let probability = self.forecaster.get_probability(option) * 100.0;
+                ListItem::new(format!("{:<18}{probability:>6.2}%", format!("{option:?}")))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(Block::bordered().title(" Probabilities "))
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, probabilities, &mut self.options);
+
+        let text = format!("{}{}", self.forecast, self.forecast.conditions_report());
+        frame.render_widget(
+            Paragraph::new(text).block(Block::bordered().title(" Forecast ")),
+            forecast,
+        );
+
+        frame.render_widget(Paragraph::new(self.status.as_str()), status);
+        frame.render_widget(Paragraph::new(HELP), help);
+    }
+}
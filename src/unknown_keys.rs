@@ -0,0 +1,210 @@
+use serde::{
+    Deserializer,
+    de::{DeserializeOwned, Visitor},
+};
+
+use crate::{
+    catalog::WeatherDefinition, circuits::Circuit, config::Config, constraints::Constraints,
+    coupling::CouplingConfig, drift::DriftConfig, geography::Geography, history::HistoryConfig,
+    mqtt::MqttConfig, output::OutputConfig, season::SeasonModifier, sessions::SessionDefinition,
+    temperature::TemperatureConfig, transitions::TransitionConfig, variety::ReintroductionConfig,
+    weekend::WeekendConfig,
+};
+
+/// A key of a config file that is not part of the config, e.g. because it is misspelled.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnknownKey {
+    /// Keys leading to the unknown key, list items are counted from 0
+    pub path: Vec<String>,
+    /// Known key with a similar name
+    pub suggestion: Option<&'static str>,
+}
+
+impl UnknownKey {
+    pub(crate) fn new(path: Vec<String>) -> Self {
+        let suggestion = path
+            .split_last()
+            .and_then(|(key, parent)| suggest(key, known_keys(parent)));
+        Self { path, suggestion }
+    }
+}
+
+impl std::fmt::Display for UnknownKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown key '{}'", self.path.join("."))?;
+        if let Some(suggestion) = self.suggestion {
+            write!(f, ", did you mean '{suggestion}'?")?;
+        }
+        Ok(())
+    }
+}
+
+/// Deserializes a config and collects the keys it ignores, including the keys of its profiles.
+pub fn deserialize<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<(Config, Vec<UnknownKey>), D::Error> {
+    let mut unknown = Vec::new();
+    let config: Config = serde_ignored::deserialize(deserializer, |path| {
+        unknown.push(UnknownKey::new(keys(&path)));
+    })?;
+
+    // profiles are only checked once they are applied, which ignores unknown keys as well
+    let fields = struct_fields::<Config>();
+    for (name, profile) in &config.profiles {
+        let keys = profile
+            .as_object()
+            .into_iter()
+            .flat_map(|profile| profile.keys());
+        for key in keys.filter(|key| !fields.contains(&key.as_str()) && *key != "profiles") {
+            unknown.push(UnknownKey::new(vec![
+                "profiles".to_string(),
+                name.clone(),
+                key.clone(),
+            ]));
+        }
+    }
+    Ok((config, unknown))
+}
+
+fn keys(path: &serde_ignored::Path) -> Vec<String> {
+    use serde_ignored::Path;
+    let mut keys = match path {
+        Path::Root => return Vec::new(),
+        Path::Seq { parent, .. }
+        | Path::Map { parent, .. }
+        | Path::Some { parent }
+        | Path::NewtypeStruct { parent }
+        | Path::NewtypeVariant { parent } => keys(parent),
+    };
+    match path {
+        Path::Seq { index, .. } => keys.push(index.to_string()),
+        Path::Map { key, .. } => keys.push(key.clone()),
+        _ => {}
+    }
+    keys
+}
+
+/// Keys that are valid below `parent`.
+pub(crate) fn known_keys(parent: &[String]) -> &'static [&'static str] {
+    let parent: Vec<&str> = parent.iter().map(String::as_str).collect();
+    match parent.as_slice() {
+        [] | ["profiles", _] => struct_fields::<Config>(),
+        ["coupling"] => struct_fields::<CouplingConfig>(),
+        ["transitions"] => struct_fields::<TransitionConfig>(),
+        ["drift"] => struct_fields::<DriftConfig>(),
+        ["geography"] => struct_fields::<Geography>(),
+        ["weekend"] => struct_fields::<WeekendConfig>(),
+        ["reintroduction"] => struct_fields::<ReintroductionConfig>(),
+        ["temperature"] => struct_fields::<TemperatureConfig>(),
+        ["constraints"] => struct_fields::<Constraints>(),
+        ["output"] => struct_fields::<OutputConfig>(),
+        ["mqtt"] => struct_fields::<MqttConfig>(),
+        ["history"] => struct_fields::<HistoryConfig>(),
+        ["sessions", _] => struct_fields::<SessionDefinition>(),
+        ["catalog", _] => struct_fields::<WeatherDefinition>(),
+        ["circuits", _] => struct_fields::<Circuit>(),
+        ["season_modifiers", _] => struct_fields::<SeasonModifier>(),
+        _ => &[],
+    }
+}
+
+/// The known key closest to `key`, if it is close enough to be a typo.
+fn suggest(key: &str, known: &[&'static str]) -> Option<&'static str> {
+    let key = key.to_ascii_lowercase();
+    known
+        .iter()
+        .map(|known| (edit_distance(&key, known), *known))
+        .filter(|(distance, _)| *distance <= (key.len() / 3).max(1))
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, known)| known)
+}
+
+/// Levenshtein distance of two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a != *b);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Names of the fields of a struct, taken from its `Deserialize` implementation.
+fn struct_fields<T: DeserializeOwned>() -> &'static [&'static str] {
+    let mut fields: &'static [&'static str] = &[];
+    let _ = T::deserialize(FieldNames(&mut fields));
+    fields
+}
+
+/// Deserializer that only records the fields of the struct it is asked for and fails otherwise.
+struct FieldNames<'a>(&'a mut &'static [&'static str]);
+
+impl<'de> Deserializer<'de> for FieldNames<'_> {
+    type Error = serde::de::value::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(serde::de::Error::custom("not a struct"))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        *self.0 = fields;
+        Err(serde::de::Error::custom("only the fields are needed"))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+        option unit unit_struct newtype_struct seq tuple tuple_struct map enum identifier
+        ignored_any
+    }
+}
+
+#[cfg(all(test, feature = "yaml"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_keys_are_collected_with_suggestions() {
+        let yaml = "
+weather_slots:
+  Race: 2
+max_weather_slot: 6
+set_clipboard: false
+coupling:
+  strenght: 0.5
+sessions:
+  - name: Sprint
+    session: Race
+    weather_slot: 2
+profiles:
+  wet:
+    probabilites:
+      Rain: 1.0
+nonsense: true
+";
+        let (config, unknown) = deserialize(serde_yaml::Deserializer::from_str(yaml)).unwrap();
+        assert_eq!(config.coupling, CouplingConfig::default());
+        let unknown: Vec<String> = unknown.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            unknown,
+            [
+                "unknown key 'max_weather_slot', did you mean 'max_weather_slots'?",
+                "unknown key 'coupling.strenght', did you mean 'strength'?",
+                "unknown key 'sessions.0.weather_slot', did you mean 'weather_slots'?",
+                "unknown key 'nonsense'",
+                "unknown key 'profiles.wet.probabilites', did you mean 'probabilities'?",
+            ]
+        );
+        assert!(struct_fields::<Config>().contains(&"coupling"));
+        assert_eq!(edit_distance("strenght", "strength"), 2);
+    }
+}
@@ -0,0 +1,157 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{catalog::WeatherCatalog, error::ForecastError, forecaster::Sessions};
+
+/// How quickly the weather groups a session used come back in the following sessions of the same
+/// kind when `variety_mode` is enabled.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ReintroductionConfig {
+    /// Factors the probabilities of a used group are multiplied with in the following sessions,
+    /// afterwards the group is back to its full probability
+    #[serde(default = "default_factors")]
+    pub factors: Vec<f64>,
+}
+
+fn default_factors() -> Vec<f64> {
+    vec![0.0, 0.5]
+}
+
+impl Default for ReintroductionConfig {
+    fn default() -> Self {
+        Self {
+            factors: default_factors(),
+        }
+    }
+}
+
+impl ReintroductionConfig {
+    pub fn validate(&self) -> Result<(), ForecastError> {
+        for factor in &self.factors {
+            if !factor.is_finite() || !(0.0..=1.0).contains(factor) {
+                return Err(ForecastError::InvalidProbability {
+                    option: "reintroduction factor".to_string(),
+                    value: *factor,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Weather groups the previous sessions of every kind used.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Variety {
+    factors: Vec<f64>,
+    /// Number of sessions since a group was last used, groups that are fully back are removed
+    since_used: HashMap<Sessions, HashMap<String, usize>>,
+}
+
+impl Variety {
+    pub fn new(reintroduction: &ReintroductionConfig) -> Self {
+        Self {
+            factors: reintroduction.factors.clone(),
+            since_used: HashMap::new(),
+        }
+    }
+
+    /// Disables the groups the previous sessions used, reintroduces them step by step and
+    /// normalizes the probabilities again. `None` if no group is held back or no weather would be
+    /// left, e.g. because the session has to stay dry and all dry groups were used.
+    pub fn probabilities(
+        &self,
+        session: Sessions,
+        catalog: &WeatherCatalog,
+        probabilities: &[f64],
+        might_rain: bool,
+    ) -> Option<Vec<f64>> {
+        let since_used = self
+            .since_used
+            .get(&session)
+            .filter(|used| !used.is_empty())?;
+        let varied: Vec<f64> = catalog
+            .entries()
+            .iter()
+            .zip(probabilities)
+            .map(|(entry, probability)| match since_used.get(&entry.group) {
+                Some(sessions) => probability * self.factors[*sessions],
+                None => *probability,
+            })
+            .collect();
+        let left: f64 = catalog
+            .entries()
+            .iter()
+            .zip(&varied)
+            .filter(|(entry, _)| might_rain || entry.rain_intensity == 0)
+            .map(|(_, probability)| probability)
+            .sum();
+        if left <= 0.0 {
+            return None;
+        }
+        let sum: f64 = varied.iter().sum();
+        Some(varied.iter().map(|probability| probability / sum).collect())
+    }
+
+    /// Remembers the groups of the entries a session used.
+    pub fn record(&mut self, session: Sessions, catalog: &WeatherCatalog, entries: &[usize]) {
+        let since_used = self.since_used.entry(session).or_default();
+        for sessions in since_used.values_mut() {
+            *sessions += 1;
+        }
+        for entry in entries {
+            since_used.insert(catalog[*entry].group.clone(), 0);
+        }
+        since_used.retain(|_, sessions| *sessions < self.factors.len());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::assert_approx_eq;
+
+    use super::*;
+    use crate::{forecaster::WeatherOptions, groups::WeatherGroups};
+
+    #[test]
+    fn used_groups_come_back_step_by_step() {
+        let catalog = WeatherCatalog::built_in(&WeatherGroups::default());
+        let uniform = vec![1.0 / catalog.len() as f64; catalog.len()];
+        let position = |option| catalog.position(option).unwrap();
+        let (storm, clear, overcast) = (
+            position(WeatherOptions::Storm),
+            position(WeatherOptions::Clear),
+            position(WeatherOptions::Overcast),
+        );
+        let mut variety = Variety::new(&ReintroductionConfig::default());
+        let probabilities =
+            |variety: &Variety| variety.probabilities(Sessions::Race, &catalog, &uniform, true);
+
+        assert_eq!(probabilities(&variety), None);
+        variety.record(Sessions::Race, &catalog, &[storm]);
+        let disabled = probabilities(&variety).unwrap();
+        assert_eq!(disabled[storm], 0.0);
+        assert_eq!(disabled[position(WeatherOptions::Thunderstorm)], 0.0);
+        assert_approx_eq!(f64, disabled.iter().sum(), 1.0);
+        assert!(disabled[overcast] > uniform[overcast]);
+        // other kinds of sessions are not affected
+        assert_eq!(
+            variety.probabilities(Sessions::Qualifying, &catalog, &uniform, true),
+            None
+        );
+
+        variety.record(Sessions::Race, &catalog, &[clear]);
+        let reintroduced = probabilities(&variety).unwrap();
+        assert_eq!(reintroduced[clear], 0.0);
+        assert_approx_eq!(f64, reintroduced[storm], 0.5 * reintroduced[overcast]);
+
+        variety.record(Sessions::Race, &catalog, &[clear]);
+        let back = probabilities(&variety).unwrap();
+        assert_approx_eq!(f64, back[storm], back[overcast]);
+        assert!(
+            ReintroductionConfig { factors: vec![2.0] }
+                .validate()
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,58 @@
+use serde::{Deserialize, de::IntoDeserializer};
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    config::{Config, ConfigFormat},
+    forecaster::WeatherForecaster,
+    output::OutputFormat,
+    sessions::{self, SessionDefinition},
+};
+
+/// The forecaster for JavaScript, e.g. a league admin panel running in the browser.
+#[wasm_bindgen(js_name = WeatherForecaster)]
+pub struct JsWeatherForecaster {
+    forecaster: WeatherForecaster,
+    sessions: Vec<SessionDefinition>,
+    emoji: bool,
+}
+
+#[wasm_bindgen(js_class = WeatherForecaster)]
+impl JsWeatherForecaster {
+    /// Creates a forecaster from a config in JSON, without one the default config is used. The
+    /// same seed and config always produce the same forecasts.
+    #[wasm_bindgen(constructor)]
+    pub fn new(config: Option<String>, seed: Option<u64>) -> Result<JsWeatherForecaster, JsError> {
+        let config: Config = match config {
+            Some(config) => ConfigFormat::Json.parse(&config)?,
+            None => Config::default(),
+        };
+        let sessions = config.sessions.clone();
+        let emoji = config.emoji;
+        let mut forecaster = WeatherForecaster::new(config)?;
+        if let Some(seed) = seed {
+            forecaster = forecaster.with_seed(seed);
+        }
+        Ok(Self {
+            forecaster,
+            sessions,
+            emoji,
+        })
+    }
+
+    /// Generates a forecast for the sessions, selected like with `--sessions`, and renders it in
+    /// one of the output formats, JSON by default.
+    pub fn generate(
+        &mut self,
+        sessions: Vec<String>,
+        format: Option<String>,
+    ) -> Result<String, JsError> {
+        let format = match format {
+            Some(format) => OutputFormat::deserialize(format.to_lowercase().into_deserializer())
+                .map_err(|error: serde::de::value::Error| JsError::new(&error.to_string()))?,
+            None => OutputFormat::Json,
+        };
+        let sessions = sessions::resolve(&self.sessions, &sessions)?;
+        let forecast = self.forecaster.generate_constrained_forecast(&sessions)?;
+        Ok(format.render(&forecast, None, None, self.emoji)?)
+    }
+}
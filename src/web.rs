@@ -0,0 +1,215 @@
+use std::{
+    fmt::Write as _,
+    io::{BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+};
+
+use rand::Rng;
+
+use crate::{
+    error::ForecastError,
+    forecaster::{WeatherForecast, WeatherForecaster},
+    history::History,
+    template::TemplateData,
+};
+
+/// Serves the forecasts of the archive as read-only web pages, the latest one at `/` and every
+/// archived one at `/forecasts/<id>`. Nothing can be generated through the server, so league
+/// members only see what got published. Handles one request at a time until the listener fails.
+pub fn serve<R: Rng>(
+    listener: &TcpListener,
+    history: &History,
+    forecaster: &WeatherForecaster<R>,
+) -> std::io::Result<()> {
+    for stream in listener.incoming() {
+        if let Err(error) = handle(stream?, history, forecaster) {
+            tracing::warn!("Failed to answer a request: {error}");
+        }
+    }
+    Ok(())
+}
+
+fn handle<R: Rng>(
+    stream: TcpStream,
+    history: &History,
+    forecaster: &WeatherForecaster<R>,
+) -> std::io::Result<()> {
+    let mut request = String::new();
+    BufReader::new(&stream).read_line(&mut request)?;
+    let mut parts = request.split_whitespace();
+    let (method, path) = (
+        parts.next().unwrap_or_default(),
+        parts.next().unwrap_or("/"),
+    );
+    let response = respond(method, path, history, forecaster);
+    tracing::info!("{method} {path} {}", response.status);
+
+    let mut stream = stream;
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\n\
+         Allow: GET\r\nConnection: close\r\n\r\n{}",
+        response.status,
+        response.body.len(),
+        response.body
+    )?;
+    stream.flush()
+}
+
+struct Response {
+    status: &'static str,
+    body: String,
+}
+
+impl Response {
+    fn new(status: &'static str, body: String) -> Self {
+        Self { status, body }
+    }
+}
+
+fn respond<R: Rng>(
+    method: &str,
+    path: &str,
+    history: &History,
+    forecaster: &WeatherForecaster<R>,
+) -> Response {
+    if method != "GET" {
+        return Response::new(
+            "405 Method Not Allowed",
+            page("Not allowed", "<p>The forecast can only be viewed.</p>"),
+        );
+    }
+    let entry = match path.trim_end_matches('/') {
+        "" => history.entries().map(|mut entries| {
+            let id = entries.len();
+            entries.pop().map(|entry| (id, entry))
+        }),
+        path => match path
+            .strip_prefix("/forecasts/")
+            .and_then(|id| id.parse().ok())
+        {
+            Some(id) => history.get(id).map(|entry| entry.map(|entry| (id, entry))),
+            None => Ok(None),
+        },
+    };
+    let forecast = entry.and_then(|entry| {
+        entry
+            .map(|(id, entry)| Ok((id, entry.seed, entry.restore(forecaster)?)))
+            .transpose()
+    });
+    match forecast {
+        Ok(Some((id, seed, forecast))) => {
+            Response::new("200 OK", forecast_page(id, seed, &forecast))
+        }
+        Ok(None) if path.trim_end_matches('/').is_empty() => Response::new(
+            "200 OK",
+            page(
+                "Weather forecast",
+                "<p>No forecast has been published yet.</p>",
+            ),
+        ),
+        Ok(None) => Response::new(
+            "404 Not Found",
+            page("Not found", "<p>There is no such forecast.</p>"),
+        ),
+        Err(error) => error_response(&error),
+    }
+}
+
+fn error_response(error: &ForecastError) -> Response {
+    tracing::warn!("Failed to read the archive: {error}");
+    Response::new(
+        "500 Internal Server Error",
+        page(
+            "Error",
+            "<p>The forecast could not be read from the archive.</p>",
+        ),
+    )
+}
+
+/// Page of the archived forecast `id` with a table of the slots of every session.
+pub fn forecast_page(id: usize, seed: u64, forecast: &WeatherForecast) -> String {
+    let data = TemplateData::new(forecast, Some(seed));
+    let mut body = String::new();
+    for session in &data.sessions {
+        let _ = writeln!(body, "<h2>{}</h2>", escape(&session.name));
+        let _ = writeln!(
+            body,
+            "<table>\n<tr><th>Slot</th><th>Weather</th><th>Ambient</th><th>Track</th></tr>"
+        );
+        for slot in &session.slots {
+            let format = |value: Option<f64>| {
+                value.map_or_else(|| "-".to_string(), |value| format!("{value}°C"))
+            };
+            let _ = writeln!(
+                body,
+                "<tr><td>{}</td><td>{} {}</td><td>{}</td><td>{}</td></tr>",
+                slot.number,
+                slot.emoji,
+                escape(&slot.weather),
+                format(slot.ambient_temperature),
+                format(slot.track_temperature),
+            );
+        }
+        body.push_str("</table>\n");
+    }
+    let _ = writeln!(body, "<p>{}</p>", escape(&data.summary));
+    let _ = writeln!(body, "<p><small>Forecast #{id}, seed {seed}</small></p>");
+    page("Weather forecast", &body)
+}
+
+fn page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n\
+         <style>body {{ font-family: sans-serif; max-width: 40em; margin: auto; }} \
+         td, th {{ padding: 0.2em 1em; text-align: left; }}</style>\n</head>\n<body>\n\
+         <h1>{title}</h1>\n{body}</body>\n</html>\n"
+    )
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::Config, forecaster::Sessions, history::HistoryEntry};
+
+    #[test]
+    fn archived_forecasts_can_only_be_viewed() {
+        let path = std::env::temp_dir().join(format!(
+            "weather_forecaster_web_{}.jsonl",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let history = History::new(&path);
+        let mut forecaster = WeatherForecaster::new(Config::default())
+            .unwrap()
+            .with_seed(3);
+        let status = |method, path, forecaster: &WeatherForecaster| {
+            respond(method, path, &history, forecaster).status
+        };
+        assert!(
+            respond("GET", "/", &history, &forecaster)
+                .body
+                .contains("No forecast has been published yet")
+        );
+
+        for _ in 0..2 {
+            let forecast = forecaster.generate_forecast(&[Sessions::Race]).unwrap();
+            history.append(&HistoryEntry::new(3, &forecast)).unwrap();
+        }
+        let latest = respond("GET", "/", &history, &forecaster);
+        assert_eq!(latest.status, "200 OK");
+        assert!(latest.body.contains("<h2>Race</h2>"));
+        assert!(latest.body.contains("Forecast #2, seed 3"));
+        assert_eq!(status("GET", "/forecasts/1", &forecaster), "200 OK");
+        assert_eq!(status("GET", "/forecasts/3", &forecaster), "404 Not Found");
+        assert_eq!(status("POST", "/", &forecaster), "405 Method Not Allowed");
+        std::fs::remove_file(path).unwrap();
+    }
+}
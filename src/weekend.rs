@@ -0,0 +1,163 @@
+use std::collections::{BTreeMap, HashMap};
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use strum::{EnumIter, IntoEnumIterator};
+
+use crate::{error::ForecastError, forecaster::Sessions};
+
+/// Race weekend of several days, every day has a weather regime its sessions share and the
+/// regime tends to persist from one day to the next.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(default)]
+pub struct WeekendConfig {
+    /// Chance that a day is wet
+    pub wet_chance: f64,
+    /// Chance that a day keeps the regime of the previous day instead of drawing a new one
+    pub persistence: f64,
+    /// Days of sessions that do not take place on their usual day
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub days: HashMap<Sessions, Day>,
+}
+
+impl Default for WeekendConfig {
+    fn default() -> Self {
+        Self {
+            wet_chance: 0.3,
+            persistence: 0.6,
+            days: HashMap::new(),
+        }
+    }
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    EnumIter,
+    Serialize,
+    Deserialize,
+    schemars::JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Day {
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+/// Weather of a whole day: dry days have no rain at all, wet days rain in every session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Regime {
+    Dry,
+    Wet,
+}
+
+impl WeekendConfig {
+    pub fn validate(&self) -> Result<(), ForecastError> {
+        for (name, value) in [
+            ("weekend wet chance", self.wet_chance),
+            ("weekend persistence", self.persistence),
+        ] {
+            if !value.is_finite() || !(0.0..=1.0).contains(&value) {
+                return Err(ForecastError::InvalidProbability {
+                    option: name.to_string(),
+                    value,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Day the session takes place on, practice on Friday, qualifying on Saturday and the race
+    /// on Sunday unless configured otherwise.
+    pub fn day(&self, session: Sessions) -> Day {
+        self.days.get(&session).copied().unwrap_or(match session {
+            Sessions::Fp1 | Sessions::Fp2 | Sessions::Practice => Day::Friday,
+            Sessions::Fp3 | Sessions::Qualifying | Sessions::Sprint => Day::Saturday,
+            Sessions::Warmup | Sessions::Race => Day::Sunday,
+        })
+    }
+
+    /// Regime of every day of the weekend, the first day is drawn from the wet chance and every
+    /// following day either keeps the regime of the day before or draws a new one.
+    pub fn regimes<R: Rng>(&self, rng: &mut R) -> BTreeMap<Day, Regime> {
+        let mut previous = None;
+        Day::iter()
+            .map(|day| {
+                let regime = match previous {
+                    Some(regime) if rng.random::<f64>() < self.persistence => regime,
+                    _ => self.draw_regime(rng),
+                };
+                previous = Some(regime);
+                (day, regime)
+            })
+            .collect()
+    }
+
+    fn draw_regime<R: Rng>(&self, rng: &mut R) -> Regime {
+        if rng.random::<f64>() < self.wet_chance {
+            Regime::Wet
+        } else {
+            Regime::Dry
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use super::*;
+
+    #[test]
+    fn regimes_persist_over_the_days() {
+        let weekend = WeekendConfig {
+            days: HashMap::from([(Sessions::Warmup, Day::Saturday)]),
+            ..WeekendConfig::default()
+        };
+        assert_eq!(weekend.day(Sessions::Fp2), Day::Friday);
+        assert_eq!(weekend.day(Sessions::Qualifying), Day::Saturday);
+        assert_eq!(weekend.day(Sessions::Warmup), Day::Saturday);
+        assert_eq!(weekend.day(Sessions::Race), Day::Sunday);
+
+        let mut rng = StdRng::seed_from_u64(2);
+        let persistent = WeekendConfig {
+            wet_chance: 0.5,
+            persistence: 1.0,
+            ..WeekendConfig::default()
+        };
+        for _ in 0..20 {
+            let regimes = persistent.regimes(&mut rng);
+            assert_eq!(regimes.len(), 3);
+            assert!(
+                regimes
+                    .values()
+                    .all(|regime| *regime == regimes[&Day::Friday])
+            );
+        }
+
+        let changeable = WeekendConfig {
+            wet_chance: 0.5,
+            persistence: 0.0,
+            ..WeekendConfig::default()
+        };
+        assert!((0..20).any(|_| {
+            let regimes = changeable.regimes(&mut rng);
+            regimes[&Day::Friday] != regimes[&Day::Sunday]
+        }));
+        assert!(
+            WeekendConfig {
+                persistence: 1.2,
+                ..WeekendConfig::default()
+            }
+            .validate()
+            .is_err()
+        );
+    }
+}
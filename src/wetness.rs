@@ -0,0 +1,79 @@
+use crate::forecaster::{Sessions, WeatherForecast, WeatherOptions};
+
+/// Share of the water that is left on the track between two sessions.
+const DRYING_BETWEEN_SESSIONS: f64 = 0.6;
+
+impl WeatherForecast {
+    /// Wetness of the track at the start of every session from `0.0` (dry) to `1.0` (standing
+    /// water), in the order of the sessions. The first session starts dry, rain leaves a damp
+    /// track for the following sessions.
+    pub fn starting_wetness(&self) -> Vec<(Sessions, f64)> {
+        let mut wetness = 0.0;
+        self.sessions()
+            .into_iter()
+            .map(|session| {
+                let start = wetness;
+                wetness = self.forecast[&session]
+                    .iter()
+                    .fold(wetness, |wetness, option| after_slot(wetness, *option));
+                wetness *= DRYING_BETWEEN_SESSIONS;
+                (session, start)
+            })
+            .collect()
+    }
+}
+
+/// Wetness of the track after a slot with the weather, rain soaks it according to its intensity
+/// while the sun dries it faster than clouds.
+fn after_slot(wetness: f64, option: WeatherOptions) -> f64 {
+    match option.rain_intensity() {
+        0 => wetness * (0.7 - 0.4 * option.sunshine()),
+        intensity => wetness.max(intensity as f64 / 3.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_cmp::assert_approx_eq;
+
+    use super::*;
+    use crate::{config::Config, forecaster::WeatherForecaster};
+
+    #[test]
+    fn rain_leaves_a_damp_track() {
+        let mut forecast = WeatherForecast::default();
+        forecast.forecast.insert(
+            Sessions::Practice,
+            vec![WeatherOptions::Clear, WeatherOptions::Rain],
+        );
+        forecast
+            .forecast
+            .insert(Sessions::Qualifying, vec![WeatherOptions::Overcast]);
+        forecast
+            .forecast
+            .insert(Sessions::Race, vec![WeatherOptions::Clear]);
+
+        let wetness = forecast.starting_wetness();
+        assert_eq!(wetness[0], (Sessions::Practice, 0.0));
+        assert_eq!(wetness[1].0, Sessions::Qualifying);
+        assert_approx_eq!(f64, wetness[1].1, 2.0 / 3.0 * 0.6);
+        let overcast = 0.7 - 0.4 * WeatherOptions::Overcast.sunshine();
+        assert_approx_eq!(f64, wetness[2].1, 2.0 / 3.0 * 0.6 * overcast * 0.6);
+    }
+
+    #[test]
+    fn wetness_keys_are_printed_when_enabled() {
+        let config = Config {
+            track_wetness: true,
+            ..Config::default()
+        };
+        let forecast = WeatherForecaster::new(config)
+            .unwrap()
+            .with_seed(1)
+            .generate_forecast(&[Sessions::Qualifying, Sessions::Race])
+            .unwrap();
+        let printed = forecast.to_string();
+        assert!(printed.contains("\"QualifyTrackWetness\": 0,"));
+        assert!(printed.contains("\"RaceTrackWetness\": "));
+    }
+}